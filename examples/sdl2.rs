@@ -0,0 +1,49 @@
+use sdl2::event::Event;
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia_bootstrap::{
+    DeviceBuilder, InstanceBuilder, PhysicalDeviceSelector, QueueType, SwapchainBuilder,
+};
+
+fn main() -> anyhow::Result<()> {
+    let sdl = sdl2::init().map_err(anyhow::Error::msg)?;
+    let video = sdl.video().map_err(anyhow::Error::msg)?;
+    let window = video
+        .window("Example Vulkan Application", 800, 600)
+        .vulkan()
+        .resizable()
+        .build()?;
+
+    // The window outlives the instance, it is only dropped at the end of main.
+    let instance = unsafe { InstanceBuilder::from_sdl2_window(&window)? }
+        .app_name("Example Vulkan Application")
+        .engine_name("Example Vulkan Engine")
+        .request_validation_layers(true)
+        .use_default_debug_messenger()
+        .build()?;
+
+    let physical_device = PhysicalDeviceSelector::new(instance.clone()).select()?;
+    let device = Arc::new(DeviceBuilder::new(physical_device, instance.clone()).build()?);
+    let (_graphics_queue_index, _graphics_queue) = device.get_queue(QueueType::Graphics)?;
+
+    let (width, height) = window.vulkan_drawable_size();
+    let swapchain = SwapchainBuilder::new(instance.clone(), device.clone())
+        .desired_size(vk::Extent2D { width, height })
+        .build()?;
+
+    let mut event_pump = sdl.event_pump().map_err(anyhow::Error::msg)?;
+    'running: loop {
+        for event in event_pump.poll_iter() {
+            if let Event::Quit { .. } = event {
+                break 'running;
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(16));
+    }
+
+    swapchain.destroy();
+    device.destroy();
+    instance.destroy();
+
+    Ok(())
+}
@@ -62,9 +62,8 @@ impl ApplicationHandler for App {
         _window_id: WindowId,
         event: WindowEvent,
     ) {
-        match event {
-            WindowEvent::CloseRequested => event_loop.exit(),
-            _ => (),
+        if let WindowEvent::CloseRequested = event {
+            event_loop.exit()
         }
     }
 }
@@ -0,0 +1,104 @@
+//! Draws a triangle with `TriangleStarter` to check the bootstrap end-to-end.
+
+use std::sync::Arc;
+use vulkanalia_bootstrap::{
+    Device, DeviceBuilder, Instance, InstanceBuilder, PhysicalDeviceSelector, Swapchain,
+    SwapchainBuilder, TriangleStarter,
+};
+use winit::application::ApplicationHandler;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::window::{Window, WindowAttributes, WindowId};
+
+#[derive(Debug)]
+struct Renderer {
+    window: Arc<Window>,
+    instance: Arc<Instance>,
+    device: Arc<Device>,
+    swapchain: Swapchain,
+    starter: TriangleStarter,
+}
+
+impl Renderer {
+    fn new(window: Arc<Window>) -> anyhow::Result<Self> {
+        let instance = InstanceBuilder::new(Some(window.clone()))
+            .app_name("Triangle")
+            .request_validation_layers(true)
+            .use_default_debug_messenger()
+            .build()?;
+
+        let physical_device = PhysicalDeviceSelector::new(instance.clone()).select()?;
+        let device = Arc::new(DeviceBuilder::new(physical_device, instance.clone()).build()?);
+        let swapchain = SwapchainBuilder::new(instance.clone(), device.clone()).build()?;
+        let starter = TriangleStarter::new(device.clone(), &swapchain)?;
+
+        Ok(Self {
+            window,
+            instance,
+            device,
+            swapchain,
+            starter,
+        })
+    }
+
+    fn destroy(&self) {
+        self.starter.destroy();
+        self.swapchain.destroy_image_views().ok();
+        self.swapchain.destroy();
+        self.device.destroy();
+        self.instance.destroy();
+    }
+}
+
+#[derive(Default, Debug)]
+struct App {
+    renderer: Option<Renderer>,
+}
+
+impl ApplicationHandler for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        let renderer = event_loop
+            .create_window(WindowAttributes::default().with_title("Triangle"))
+            .map_err(anyhow::Error::from)
+            .and_then(|window| Renderer::new(Arc::new(window)));
+
+        match renderer {
+            Ok(renderer) => {
+                renderer.window.request_redraw();
+                self.renderer.replace(renderer);
+            }
+            Err(e) => panic!("Could not initialize renderer: {e}"),
+        }
+    }
+
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        _window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        match event {
+            WindowEvent::CloseRequested => {
+                if let Some(renderer) = self.renderer.take() {
+                    renderer.destroy();
+                }
+                event_loop.exit();
+            }
+            WindowEvent::RedrawRequested => {
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.starter.draw(&renderer.swapchain).unwrap();
+                    renderer.window.request_redraw();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let event_loop = EventLoop::new()?;
+    let mut app = App::default();
+    event_loop.run_app(&mut app)?;
+
+    Ok(())
+}
@@ -34,7 +34,7 @@ struct VulkanEngine {
     device: Arc<Device>,
     swapchain: Swapchain,
     swapchain_images: Vec<vk::Image>,
-    swapchain_image_views: Vec<vk::ImageView>,
+    _swapchain_image_views: Vec<vk::ImageView>,
     graphics_queue: vk::Queue,
 
     frames: Vec<FrameData>,
@@ -51,18 +51,8 @@ impl VulkanEngine {
             .require_api_version(Version::new(1, 3, 0))
             .build()?;
 
-        let features12 = vk::PhysicalDeviceVulkan12Features::builder()
-            .buffer_device_address(true)
-            .descriptor_indexing(true);
-
-        let features13 = vk::PhysicalDeviceVulkan13Features::builder()
-            .synchronization2(true)
-            .dynamic_rendering(true);
-
-        let physical_device = PhysicalDeviceSelector::new(instance.clone())
+        let physical_device = PhysicalDeviceSelector::vk_guide_1_3(instance.clone())
             .preferred_device_type(PreferredDeviceType::Discrete)
-            .add_required_extension_feature(*features12)
-            .add_required_extension_feature(*features13)
             .select()?;
 
         let device = Arc::new(DeviceBuilder::new(physical_device, instance.clone()).build()?);
@@ -146,7 +136,7 @@ impl VulkanEngine {
             device,
             swapchain,
             swapchain_images,
-            swapchain_image_views,
+            _swapchain_image_views: swapchain_image_views,
             graphics_queue,
             frame_number: 0,
             frames,
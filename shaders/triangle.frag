@@ -0,0 +1,10 @@
+// Reference source of triangle.frag.spv, which is hand assembled so the crate does not need a
+// shader compiler. Keep both in sync.
+#version 450
+
+layout(location = 0) in vec3 color;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = vec4(color, 1.0);
+}
@@ -0,0 +1,10 @@
+// Reference source of triangle.vert.spv, which is hand assembled so the crate does not need a
+// shader compiler. Keep both in sync.
+#version 450
+
+layout(location = 0) out vec3 color;
+
+void main() {
+    gl_Position = vec4((float(gl_VertexIndex) - 1.0) * 0.5, gl_VertexIndex == 1 ? -0.5 : 0.5, 0.0, 1.0);
+    color = vec3(gl_VertexIndex == 0, gl_VertexIndex == 1, gl_VertexIndex == 2);
+}
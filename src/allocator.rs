@@ -0,0 +1,275 @@
+use std::alloc::Layout;
+use std::ffi::c_void;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vulkanalia::vk;
+
+/// A host memory allocator for Vulkan objects, see [`TrackedAllocationCallbacks`].
+///
+/// # Safety
+///
+/// Implementations must follow the rules of `vkAllocationFunction` and friends: returned memory
+/// is aligned to `alignment`, stays valid until freed and can be used from any thread. A null
+/// pointer signals an allocation failure.
+pub unsafe trait Allocator: Send + Sync {
+    /// Allocate `size` bytes aligned to `alignment`. `size` is never zero.
+    fn allocate(
+        &self,
+        size: usize,
+        alignment: usize,
+        scope: vk::SystemAllocationScope,
+    ) -> *mut c_void;
+
+    /// Grow or shrink an allocation, keeping its contents up to the smaller of both sizes.
+    ///
+    /// # Safety
+    ///
+    /// `original` is a non-null pointer returned by this allocator and not freed yet, `size` is
+    /// never zero.
+    unsafe fn reallocate(
+        &self,
+        original: *mut c_void,
+        size: usize,
+        alignment: usize,
+        scope: vk::SystemAllocationScope,
+    ) -> *mut c_void;
+
+    /// Free an allocation.
+    ///
+    /// # Safety
+    ///
+    /// `memory` is a non-null pointer returned by this allocator and not freed yet.
+    unsafe fn free(&self, memory: *mut c_void);
+}
+
+/// [`Allocator`] backed by the Rust global allocator, for counting allocations without
+/// changing where memory comes from.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemAllocator;
+
+/// Bytes in front of every [`SystemAllocator`] allocation storing its size and alignment.
+const HEADER_SIZE: usize = 2 * size_of::<usize>();
+
+impl SystemAllocator {
+    fn layout(size: usize, alignment: usize) -> Option<(Layout, usize)> {
+        let alignment = alignment.max(HEADER_SIZE);
+        let layout = Layout::from_size_align(size.checked_add(alignment)?, alignment).ok()?;
+        Some((layout, alignment))
+    }
+}
+
+unsafe impl Allocator for SystemAllocator {
+    fn allocate(
+        &self,
+        size: usize,
+        alignment: usize,
+        _scope: vk::SystemAllocationScope,
+    ) -> *mut c_void {
+        let Some((layout, offset)) = Self::layout(size, alignment) else {
+            return std::ptr::null_mut();
+        };
+
+        unsafe {
+            let base = std::alloc::alloc(layout);
+            if base.is_null() {
+                return std::ptr::null_mut();
+            }
+            let memory = base.add(offset);
+            let header = memory.sub(HEADER_SIZE).cast::<usize>();
+            header.write(size);
+            header.add(1).write(alignment);
+            memory.cast()
+        }
+    }
+
+    unsafe fn reallocate(
+        &self,
+        original: *mut c_void,
+        size: usize,
+        alignment: usize,
+        scope: vk::SystemAllocationScope,
+    ) -> *mut c_void {
+        let memory = self.allocate(size, alignment, scope);
+        if !memory.is_null() {
+            unsafe {
+                let original_size = original.cast::<usize>().sub(2).read();
+                std::ptr::copy_nonoverlapping(
+                    original.cast::<u8>(),
+                    memory.cast::<u8>(),
+                    original_size.min(size),
+                );
+                self.free(original);
+            }
+        }
+        memory
+    }
+
+    unsafe fn free(&self, memory: *mut c_void) {
+        unsafe {
+            let header = memory.cast::<usize>().sub(2);
+            let (size, alignment) = (header.read(), header.add(1).read());
+            let (layout, offset) = Self::layout(size, alignment).unwrap();
+            std::alloc::dealloc(memory.cast::<u8>().sub(offset), layout);
+        }
+    }
+}
+
+struct AllocatorState {
+    allocator: Box<dyn Allocator>,
+    live_allocations: AtomicUsize,
+    internal_bytes: AtomicUsize,
+}
+
+/// Shareable `vk::AllocationCallbacks` for an [`Allocator`], counting live allocations.
+///
+/// The allocator is reference counted and kept alive by the [`crate::Instance`],
+/// [`crate::Device`] and [`crate::Swapchain`] built with it, so the `user_data` pointer in the
+/// callbacks can't dangle while Vulkan may still call them. Cloning shares the same allocator
+/// and counters. [`crate::Instance::destroy`] reports allocations that are still alive
+/// afterwards.
+#[derive(Clone)]
+pub struct TrackedAllocationCallbacks {
+    state: Arc<AllocatorState>,
+}
+
+impl TrackedAllocationCallbacks {
+    pub fn new(allocator: impl Allocator + 'static) -> Self {
+        Self {
+            state: Arc::new(AllocatorState {
+                allocator: Box::new(allocator),
+                live_allocations: AtomicUsize::new(0),
+                internal_bytes: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Callbacks to pass to Vulkan. Only valid while this value or one of its clones is alive.
+    pub fn callbacks(&self) -> vk::AllocationCallbacks {
+        vk::AllocationCallbacks {
+            user_data: Arc::as_ptr(&self.state) as *mut c_void,
+            allocation: Some(allocation),
+            reallocation: Some(reallocation),
+            free: Some(free),
+            internal_allocation: Some(internal_allocation),
+            internal_free: Some(internal_free),
+        }
+    }
+
+    /// Allocations made through the callbacks that were not freed yet.
+    pub fn live_allocations(&self) -> usize {
+        self.state.live_allocations.load(Ordering::Relaxed)
+    }
+
+    /// Bytes the driver reported as allocated internally, outside the callbacks.
+    pub fn internal_allocated_bytes(&self) -> usize {
+        self.state.internal_bytes.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for TrackedAllocationCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackedAllocationCallbacks")
+            .field("live_allocations", &self.live_allocations())
+            .field("internal_allocated_bytes", &self.internal_allocated_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+unsafe fn state<'a>(user_data: *mut c_void) -> &'a AllocatorState {
+    unsafe { &*(user_data as *const AllocatorState) }
+}
+
+unsafe extern "system" fn allocation(
+    user_data: *mut c_void,
+    size: usize,
+    alignment: usize,
+    scope: vk::SystemAllocationScope,
+) -> *mut c_void {
+    let state = unsafe { state(user_data) };
+    if size == 0 {
+        return std::ptr::null_mut();
+    }
+
+    let memory = state.allocator.allocate(size, alignment, scope);
+    if !memory.is_null() {
+        state.live_allocations.fetch_add(1, Ordering::Relaxed);
+    }
+    memory
+}
+
+unsafe extern "system" fn reallocation(
+    user_data: *mut c_void,
+    original: *mut c_void,
+    size: usize,
+    alignment: usize,
+    scope: vk::SystemAllocationScope,
+) -> *mut c_void {
+    if original.is_null() {
+        return unsafe { allocation(user_data, size, alignment, scope) };
+    }
+    if size == 0 {
+        unsafe { free(user_data, original) };
+        return std::ptr::null_mut();
+    }
+
+    let state = unsafe { state(user_data) };
+    unsafe { state.allocator.reallocate(original, size, alignment, scope) }
+}
+
+unsafe extern "system" fn free(user_data: *mut c_void, memory: *mut c_void) {
+    if memory.is_null() {
+        return;
+    }
+
+    let state = unsafe { state(user_data) };
+    unsafe { state.allocator.free(memory) };
+    state.live_allocations.fetch_sub(1, Ordering::Relaxed);
+}
+
+unsafe extern "system" fn internal_allocation(
+    user_data: *mut c_void,
+    size: usize,
+    _allocation_type: vk::InternalAllocationType,
+    _scope: vk::SystemAllocationScope,
+) {
+    let state = unsafe { state(user_data) };
+    state.internal_bytes.fetch_add(size, Ordering::Relaxed);
+}
+
+unsafe extern "system" fn internal_free(
+    user_data: *mut c_void,
+    size: usize,
+    _allocation_type: vk::InternalAllocationType,
+    _scope: vk::SystemAllocationScope,
+) {
+    let state = unsafe { state(user_data) };
+    state.internal_bytes.fetch_sub(size, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn callbacks_count_live_allocations() {
+        let tracked = TrackedAllocationCallbacks::new(SystemAllocator);
+        let callbacks = tracked.callbacks();
+        let scope = vk::SystemAllocationScope::OBJECT;
+
+        unsafe {
+            let memory = (callbacks.allocation.unwrap())(callbacks.user_data, 24, 64, scope);
+            assert_eq!(memory as usize % 64, 0);
+            memory.cast::<u8>().write_bytes(7, 24);
+            assert_eq!(tracked.live_allocations(), 1);
+
+            let grown =
+                (callbacks.reallocation.unwrap())(callbacks.user_data, memory, 4096, 64, scope);
+            assert_eq!(grown.cast::<u8>().add(23).read(), 7);
+            assert_eq!(tracked.live_allocations(), 1);
+
+            (callbacks.free.unwrap())(callbacks.user_data, grown);
+        }
+        assert_eq!(tracked.live_allocations(), 0);
+    }
+}
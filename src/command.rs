@@ -0,0 +1,126 @@
+use crate::device::QueueType;
+use crate::thread_audit::ThreadOwner;
+use crate::{Device, Resettable};
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, Handle, HasBuilder};
+
+/// Hands out primary command buffers for one frame and recycles them all at once.
+///
+/// Keep one manager per frame in flight and per recording thread. Command buffers come from a
+/// transient pool on the family of the given queue; [`CommandManager::reset`] (also available
+/// through [`Resettable`], e.g. in a [`crate::FrameContext`]) resets the pool once the frame's
+/// work has finished on the GPU, and the buffers are handed out again.
+#[derive(Debug)]
+pub struct CommandManager {
+    device: Arc<Device>,
+    queue_family_index: u32,
+    command_pool: vk::CommandPool,
+    pool_owner: ThreadOwner,
+    free: Vec<vk::CommandBuffer>,
+    used: Vec<vk::CommandBuffer>,
+}
+
+impl CommandManager {
+    /// A manager recording for the family of `queue`. The pool is named `name`.
+    pub fn new(
+        device: Arc<Device>,
+        queue: QueueType,
+        name: impl Into<String>,
+    ) -> crate::Result<Self> {
+        let name = name.into();
+        let (queue_family_index, _) = device.get_queue(queue)?;
+
+        let command_pool_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+            .queue_family_index(queue_family_index as _);
+        let command_pool = unsafe {
+            device.create_command_pool(&command_pool_info, device.allocation_callbacks())
+        }
+        .map_err(|code| crate::CreateError {
+            kind: vk::ObjectType::COMMAND_POOL,
+            name: name.clone(),
+            details: format!("queue family {queue_family_index}"),
+            code,
+        })?;
+        if let Err(e) = device.set_object_name(command_pool, &name) {
+            unsafe { device.destroy_command_pool(command_pool, device.allocation_callbacks()) };
+            return Err(e);
+        }
+
+        Ok(Self {
+            device,
+            queue_family_index: queue_family_index as _,
+            command_pool,
+            pool_owner: ThreadOwner::default(),
+            free: vec![],
+            used: vec![],
+        })
+    }
+
+    /// Queue family the command buffers are recorded for.
+    pub fn queue_family_index(&self) -> u32 {
+        self.queue_family_index
+    }
+
+    /// A primary command buffer in the initial state that is free until the next reset.
+    pub fn command_buffer(&mut self) -> crate::Result<vk::CommandBuffer> {
+        self.pool_owner
+            .check(vk::ObjectType::COMMAND_POOL, self.command_pool.as_raw());
+
+        let command_buffer = match self.free.pop() {
+            Some(command_buffer) => command_buffer,
+            None => {
+                let allocate_info = vk::CommandBufferAllocateInfo::builder()
+                    .command_pool(self.command_pool)
+                    .level(vk::CommandBufferLevel::PRIMARY)
+                    .command_buffer_count(1);
+                unsafe { self.device.allocate_command_buffers(&allocate_info) }?[0]
+            }
+        };
+        self.used.push(command_buffer);
+
+        Ok(command_buffer)
+    }
+
+    /// Number of command buffers handed out since the last reset.
+    pub fn in_use(&self) -> usize {
+        self.used.len()
+    }
+
+    /// Reset every command buffer handed out since the last reset. The work using them must
+    /// have finished.
+    #[cfg_attr(feature = "enable_tracing", tracing::instrument(skip(self)))]
+    pub fn reset(&mut self) -> crate::Result<()> {
+        self.pool_owner
+            .check(vk::ObjectType::COMMAND_POOL, self.command_pool.as_raw());
+        unsafe {
+            self.device
+                .reset_command_pool(self.command_pool, vk::CommandPoolResetFlags::empty())
+        }?;
+
+        #[cfg(feature = "enable_tracing")]
+        tracing::trace!(count = self.used.len(), "Reset command buffers");
+
+        self.free.append(&mut self.used);
+
+        Ok(())
+    }
+
+    /// Destroy the command pool, which frees every command buffer of the manager.
+    pub fn destroy(&mut self) {
+        self.free.clear();
+        self.used.clear();
+        unsafe {
+            self.device
+                .destroy_command_pool(self.command_pool, self.device.allocation_callbacks())
+        };
+        self.command_pool = vk::CommandPool::null();
+    }
+}
+
+impl Resettable for CommandManager {
+    fn reset(&mut self) -> crate::Result<()> {
+        CommandManager::reset(self)
+    }
+}
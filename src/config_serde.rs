@@ -0,0 +1,355 @@
+//! `serde(with = ...)` helpers for the Vulkan types in [`crate::InstanceConfig`] and
+//! [`crate::DeviceConfig`], which vulkanalia does not implement serde for.
+//!
+//! Versions are written as `"major.minor.patch"`, extension and layer names as strings, flags as
+//! their raw bits, enums as their raw values and [`vk::PhysicalDeviceFeatures`] as the list of
+//! enabled feature names (e.g. `["sampler_anisotropy", "geometry_shader"]`).
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use vulkanalia::Version;
+use vulkanalia::vk;
+
+pub(crate) mod version {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        version: &Version,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(version)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Version, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        let mut parts = string.split('.').map(str::parse::<u32>);
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), patch, None) => {
+                let patch = patch.transpose().map_err(D::Error::custom)?.unwrap_or(0);
+                Ok(Version::new(major, minor, patch))
+            }
+            _ => Err(D::Error::custom(format!(
+                "invalid version \"{string}\", expected \"major.minor.patch\""
+            ))),
+        }
+    }
+}
+
+/// Extension and layer names, for any collection of [`vk::ExtensionName`].
+pub(crate) mod extension_names {
+    use super::*;
+
+    pub(crate) fn serialize<'a, T, S>(names: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a T: IntoIterator<Item = &'a vk::ExtensionName>,
+        S: Serializer,
+    {
+        serializer.collect_seq(crate::summary::extension_names(names))
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromIterator<vk::ExtensionName>,
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|name| {
+                if name.len() >= vk::MAX_EXTENSION_NAME_SIZE {
+                    Err(D::Error::custom(format!("name \"{name}\" is too long")))
+                } else {
+                    Ok(vk::ExtensionName::from_bytes(name.as_bytes()))
+                }
+            })
+            .collect()
+    }
+}
+
+pub(crate) trait RawFlags: Sized {
+    fn to_raw(&self) -> u32;
+    fn from_raw(bits: u32) -> Self;
+}
+
+macro_rules! raw_flags {
+    ($($flags:ty),* $(,)?) => {
+        $(
+            impl RawFlags for $flags {
+                fn to_raw(&self) -> u32 {
+                    self.bits()
+                }
+
+                fn from_raw(bits: u32) -> Self {
+                    Self::from_bits_truncate(bits)
+                }
+            }
+        )*
+    };
+}
+
+raw_flags!(
+    vk::InstanceCreateFlags,
+    vk::DebugUtilsMessageSeverityFlagsEXT,
+    vk::DebugUtilsMessageTypeFlagsEXT,
+    vk::ResolveModeFlags,
+    vk::SubgroupFeatureFlags,
+);
+
+/// Flags as their raw bits. Unknown bits are dropped when reading.
+pub(crate) mod flags {
+    use super::*;
+
+    pub(crate) fn serialize<T: RawFlags, S: Serializer>(
+        flags: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(flags.to_raw())
+    }
+
+    pub(crate) fn deserialize<'de, T: RawFlags, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        u32::deserialize(deserializer).map(T::from_raw)
+    }
+}
+
+pub(crate) mod resolve_modes {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        modes: &Option<(vk::ResolveModeFlags, vk::ResolveModeFlags)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        modes
+            .map(|(depth, stencil)| (depth.bits(), stencil.bits()))
+            .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<(vk::ResolveModeFlags, vk::ResolveModeFlags)>, D::Error> {
+        Ok(
+            Option::<(u32, u32)>::deserialize(deserializer)?.map(|(depth, stencil)| {
+                (
+                    vk::ResolveModeFlags::from_bits_truncate(depth),
+                    vk::ResolveModeFlags::from_bits_truncate(stencil),
+                )
+            }),
+        )
+    }
+}
+
+/// An optional extent as a `(width, height)` pair.
+pub(crate) mod extent {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        extent: &Option<vk::Extent2D>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        extent
+            .map(|extent| (extent.width, extent.height))
+            .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<vk::Extent2D>, D::Error> {
+        Ok(Option::<(u32, u32)>::deserialize(deserializer)?
+            .map(|(width, height)| vk::Extent2D { width, height }))
+    }
+}
+
+pub(crate) trait RawEnum: Sized {
+    fn to_raw(&self) -> i32;
+    fn from_raw(value: i32) -> Self;
+}
+
+macro_rules! raw_enum {
+    ($($enum:ty),* $(,)?) => {
+        $(
+            impl RawEnum for $enum {
+                fn to_raw(&self) -> i32 {
+                    self.as_raw()
+                }
+
+                fn from_raw(value: i32) -> Self {
+                    <$enum>::from_raw(value)
+                }
+            }
+        )*
+    };
+}
+
+raw_enum!(
+    vk::ValidationCheckEXT,
+    vk::ValidationFeatureEnableEXT,
+    vk::ValidationFeatureDisableEXT,
+    vk::Format,
+    vk::DriverId,
+);
+
+/// A list of enums as their raw values.
+pub(crate) mod enums {
+    use super::*;
+
+    pub(crate) fn serialize<T: RawEnum, S: Serializer>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(RawEnum::to_raw))
+    }
+
+    pub(crate) fn deserialize<'de, T: RawEnum, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        Ok(Vec::<i32>::deserialize(deserializer)?
+            .into_iter()
+            .map(T::from_raw)
+            .collect())
+    }
+}
+
+macro_rules! features {
+    ($($feature:ident),* $(,)?) => {
+        /// [`vk::PhysicalDeviceFeatures`] as the names of the enabled features.
+        pub(crate) mod features {
+            use super::*;
+
+            pub(crate) fn serialize<S: Serializer>(
+                features: &vk::PhysicalDeviceFeatures,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                let enabled = [$((stringify!($feature), features.$feature)),*];
+                serializer.collect_seq(
+                    enabled
+                        .into_iter()
+                        .filter(|(_, enabled)| *enabled == vk::TRUE)
+                        .map(|(name, _)| name),
+                )
+            }
+
+            pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<vk::PhysicalDeviceFeatures, D::Error> {
+                let mut features = vk::PhysicalDeviceFeatures::default();
+                for name in Vec::<String>::deserialize(deserializer)? {
+                    match name.as_str() {
+                        $(stringify!($feature) => features.$feature = vk::TRUE,)*
+                        _ => {
+                            return Err(D::Error::custom(format!(
+                                "unknown device feature \"{name}\""
+                            )));
+                        }
+                    }
+                }
+                Ok(features)
+            }
+        }
+    };
+}
+
+features!(
+    robust_buffer_access,
+    full_draw_index_uint32,
+    image_cube_array,
+    independent_blend,
+    geometry_shader,
+    tessellation_shader,
+    sample_rate_shading,
+    dual_src_blend,
+    logic_op,
+    multi_draw_indirect,
+    draw_indirect_first_instance,
+    depth_clamp,
+    depth_bias_clamp,
+    fill_mode_non_solid,
+    depth_bounds,
+    wide_lines,
+    large_points,
+    alpha_to_one,
+    multi_viewport,
+    sampler_anisotropy,
+    texture_compression_etc2,
+    texture_compression_astc_ldr,
+    texture_compression_bc,
+    occlusion_query_precise,
+    pipeline_statistics_query,
+    vertex_pipeline_stores_and_atomics,
+    fragment_stores_and_atomics,
+    shader_tessellation_and_geometry_point_size,
+    shader_image_gather_extended,
+    shader_storage_image_extended_formats,
+    shader_storage_image_multisample,
+    shader_storage_image_read_without_format,
+    shader_storage_image_write_without_format,
+    shader_uniform_buffer_array_dynamic_indexing,
+    shader_sampled_image_array_dynamic_indexing,
+    shader_storage_buffer_array_dynamic_indexing,
+    shader_storage_image_array_dynamic_indexing,
+    shader_clip_distance,
+    shader_cull_distance,
+    shader_float64,
+    shader_int64,
+    shader_int16,
+    shader_resource_residency,
+    shader_resource_min_lod,
+    sparse_binding,
+    sparse_residency_buffer,
+    sparse_residency_image_2d,
+    sparse_residency_image_3d,
+    sparse_residency2_samples,
+    sparse_residency4_samples,
+    sparse_residency8_samples,
+    sparse_residency16_samples,
+    sparse_residency_aliased,
+    variable_multisample_rate,
+    inherited_queries,
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{DeviceConfig, InstanceConfig, PreferredDeviceType};
+    use vulkanalia::Version;
+    use vulkanalia::vk;
+
+    #[test]
+    fn device_config_round_trip() {
+        let mut config = DeviceConfig {
+            preferred_device_type: PreferredDeviceType::Integrated,
+            required_version: Version::new(1, 3, 0),
+            required_formats: vec![vk::Format::R16G16B16A16_SFLOAT],
+            required_subgroup_operations: vk::SubgroupFeatureFlags::BALLOT,
+            ..Default::default()
+        };
+        config.required_features.sampler_anisotropy = vk::TRUE;
+        config
+            .required_extensions
+            .insert(vk::KHR_SWAPCHAIN_EXTENSION.name);
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""required_features":["sampler_anisotropy"]"#));
+        assert!(json.contains(r#""required_extensions":["VK_KHR_swapchain"]"#));
+
+        let parsed: DeviceConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn partial_instance_config() {
+        let config: InstanceConfig = serde_json::from_str(
+            r#"{
+                "app_name": "Editor",
+                "request_validation_layers": true,
+                "required_instance_version": "1.2"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.app_name, "Editor");
+        assert!(config.request_validation_layers);
+        assert_eq!(config.required_instance_version, Version::new(1, 2, 0));
+        assert_eq!(config.engine_name, InstanceConfig::default().engine_name);
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{Debug, Formatter};
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use vulkanalia::vk;
+
+/// Where the default debug messenger writes validation messages.
+#[derive(Clone, Default)]
+pub enum DebugOutput {
+    #[default]
+    Stdout,
+    Stderr,
+    /// Append to the file at `path`. Once it grows past `max_bytes` it is renamed to
+    /// `<path>.1` (shifting older files up to `<path>.<max_files>`) and a new file is started.
+    /// A `max_bytes` of zero disables rotation.
+    File {
+        path: PathBuf,
+        max_bytes: u64,
+        max_files: usize,
+    },
+    /// Write to a user provided writer.
+    Writer(Arc<Mutex<dyn Write + Send>>),
+}
+
+impl Debug for DebugOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stdout => f.write_str("Stdout"),
+            Self::Stderr => f.write_str("Stderr"),
+            Self::File {
+                path,
+                max_bytes,
+                max_files,
+            } => f
+                .debug_struct("File")
+                .field("path", path)
+                .field("max_bytes", max_bytes)
+                .field("max_files", max_files)
+                .finish(),
+            Self::Writer(_) => f.write_str("Writer"),
+        }
+    }
+}
+
+/// Receives shader debug printf output, see
+/// [`InstanceBuilder::debug_printf_callback`](crate::InstanceBuilder::debug_printf_callback).
+#[derive(Clone)]
+pub(crate) struct DebugPrintfCallback(pub(crate) Arc<dyn Fn(&str) + Send + Sync>);
+
+impl Debug for DebugPrintfCallback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DebugPrintfCallback")
+    }
+}
+
+/// Whether a message comes from the validation layer's shader debug printf.
+pub(crate) fn is_debug_printf(message_id_name: &str) -> bool {
+    message_id_name.contains("DEBUG-PRINTF")
+}
+
+struct RotatingFile {
+    file: Option<File>,
+    path: PathBuf,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+
+        Ok(Self {
+            file: Some(file),
+            path,
+            written,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    fn rotated_path(path: &Path, index: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+
+        if self.max_files > 0 {
+            for index in (1..self.max_files).rev() {
+                let from = Self::rotated_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(from, Self::rotated_path(&self.path, index + 1))?;
+                }
+            }
+            fs::rename(&self.path, Self::rotated_path(&self.path, 1))?;
+        }
+
+        self.file = Some(File::create(&self.path)?);
+        self.written = 0;
+        Ok(())
+    }
+
+    fn write(&mut self, message: &[u8]) -> io::Result<()> {
+        if self.max_bytes > 0
+            && self.written > 0
+            && self.written + message.len() as u64 > self.max_bytes
+        {
+            self.rotate()?;
+        }
+
+        if let Some(file) = &mut self.file {
+            file.write_all(message)?;
+            self.written += message.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+enum SinkTarget {
+    Stdout,
+    Stderr,
+    File(RotatingFile),
+    Writer(Arc<Mutex<dyn Write + Send>>),
+}
+
+/// Shared state of the built-in debug messengers, passed to them as user data.
+pub(crate) struct DebugSink {
+    target: Mutex<SinkTarget>,
+    colored: bool,
+    repeat_limit: u32,
+    repeats: Mutex<HashMap<u64, u32>>,
+    /// Severities the user asked for. Debug printf forces `INFO` on the messenger, other
+    /// messages outside these severities are dropped.
+    pub(crate) severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    pub(crate) printf_callback: Option<DebugPrintfCallback>,
+}
+
+impl Debug for DebugSink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugSink").finish_non_exhaustive()
+    }
+}
+
+impl DebugSink {
+    /// `colored` only applies when the output is stdout or stderr attached to a terminal. A
+    /// `repeat_limit` of zero reports every message.
+    pub(crate) fn new(output: DebugOutput, colored: bool, repeat_limit: u32) -> io::Result<Self> {
+        let colored = colored
+            && match output {
+                DebugOutput::Stdout => io::stdout().is_terminal(),
+                DebugOutput::Stderr => io::stderr().is_terminal(),
+                _ => false,
+            };
+        let target = match output {
+            DebugOutput::Stdout => SinkTarget::Stdout,
+            DebugOutput::Stderr => SinkTarget::Stderr,
+            DebugOutput::File {
+                path,
+                max_bytes,
+                max_files,
+            } => SinkTarget::File(RotatingFile::open(path, max_bytes, max_files)?),
+            DebugOutput::Writer(writer) => SinkTarget::Writer(writer),
+        };
+
+        Ok(Self {
+            target: Mutex::new(target),
+            colored,
+            repeat_limit,
+            repeats: Mutex::new(HashMap::new()),
+            severity: vk::DebugUtilsMessageSeverityFlagsEXT::all(),
+            printf_callback: None,
+        })
+    }
+
+    /// Hand a debug printf message to the printf callback. Returns false when there is none.
+    pub(crate) fn route_printf(&self, message: &str) -> bool {
+        match &self.printf_callback {
+            Some(callback) => {
+                (callback.0)(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Count another occurrence of a message and return how often it was seen, or `None` when
+    /// it should be suppressed.
+    ///
+    /// The first `repeat_limit` occurrences are reported, after that only every power of two
+    /// is, so a per-frame message shows up with a running counter instead of flooding the log.
+    pub(crate) fn repeat_count(&self, message_id_number: i32, message: &str) -> Option<u32> {
+        if self.repeat_limit == 0 {
+            return Some(1);
+        }
+
+        let mut hasher = DefaultHasher::new();
+        message_id_number.hash(&mut hasher);
+        message.hash(&mut hasher);
+
+        let mut repeats = self.repeats.lock().ok()?;
+        let count = repeats.entry(hasher.finish()).or_default();
+        *count = count.saturating_add(1);
+
+        (*count <= self.repeat_limit || count.is_power_of_two()).then_some(*count)
+    }
+
+    /// Suffix telling how often a message was repeated, empty below the repeat limit.
+    pub(crate) fn repeat_note(&self, count: u32) -> String {
+        if self.repeat_limit > 0 && count >= self.repeat_limit {
+            format!(" [repeated {count} times, further repeats are suppressed]")
+        } else {
+            String::new()
+        }
+    }
+
+    /// Write one message, prefixed with its severity. Errors are dropped, the messenger has no
+    /// way to report them.
+    pub(crate) fn write_message(
+        &self,
+        severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+        message: &str,
+    ) {
+        let message = if self.colored {
+            let color = match severity {
+                vk::DebugUtilsMessageSeverityFlagsEXT::ERROR => "31",
+                vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => "33",
+                vk::DebugUtilsMessageSeverityFlagsEXT::INFO => "36",
+                _ => "90",
+            };
+            format!("\x1b[{color}m{severity:?}\x1b[0m:\n{message}")
+        } else {
+            format!("{severity:?}:\n{message}")
+        };
+
+        let Ok(mut target) = self.target.lock() else {
+            return;
+        };
+
+        let _ = match &mut *target {
+            SinkTarget::Stdout => writeln!(io::stdout().lock(), "{message}"),
+            SinkTarget::Stderr => writeln!(io::stderr().lock(), "{message}"),
+            SinkTarget::File(file) => file.write(format!("{message}\n").as_bytes()),
+            SinkTarget::Writer(writer) => match writer.lock() {
+                Ok(mut writer) => writeln!(writer, "{message}"),
+                Err(_) => Ok(()),
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_rotation() {
+        let dir = std::env::temp_dir().join(format!("vkb-debug-output-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("validation.log");
+
+        let sink = DebugSink::new(
+            DebugOutput::File {
+                path: path.clone(),
+                max_bytes: 24,
+                max_files: 2,
+            },
+            true,
+            0,
+        )
+        .unwrap();
+        for message in ["first message", "second message", "third message"] {
+            sink.write_message(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR, message);
+        }
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "ERROR:\nthird message\n"
+        );
+        assert_eq!(
+            fs::read_to_string(RotatingFile::rotated_path(&path, 1)).unwrap(),
+            "ERROR:\nsecond message\n"
+        );
+        assert_eq!(
+            fs::read_to_string(RotatingFile::rotated_path(&path, 2)).unwrap(),
+            "ERROR:\nfirst message\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn repeated_messages_are_suppressed() {
+        let sink = DebugSink::new(DebugOutput::Stdout, false, 3).unwrap();
+
+        let reported = (0..20)
+            .filter_map(|_| sink.repeat_count(7, "per-frame message"))
+            .collect::<Vec<_>>();
+        assert_eq!(reported, [1, 2, 3, 4, 8, 16]);
+        assert_eq!(sink.repeat_count(7, "another message"), Some(1));
+    }
+}
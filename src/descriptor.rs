@@ -0,0 +1,162 @@
+use crate::{Device, Resettable};
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, HasBuilder};
+
+/// Upper bound of the sets in one pool, pools stop growing there.
+const MAX_SETS_PER_POOL: u32 = 4096;
+
+/// Number of descriptors of `descriptor_type` reserved per set in the pools of a
+/// [`DescriptorAllocator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolSizeRatio {
+    pub descriptor_type: vk::DescriptorType,
+    pub ratio: f32,
+}
+
+/// Allocates descriptor sets from a growing list of pools and recycles them all at once.
+///
+/// Keep one allocator per frame in flight. Pools are created when the current ones run out,
+/// each 1.5 times as large as the previous one (up to 4096 sets), and
+/// [`DescriptorAllocator::reset`] (also available through [`Resettable`], e.g. in a
+/// [`crate::FrameContext`]) frees every set once the frame's work has finished on the GPU.
+#[derive(Debug)]
+pub struct DescriptorAllocator {
+    device: Arc<Device>,
+    name: String,
+    ratios: Vec<PoolSizeRatio>,
+    sets_per_pool: u32,
+    ready_pools: Vec<vk::DescriptorPool>,
+    full_pools: Vec<vk::DescriptorPool>,
+}
+
+impl DescriptorAllocator {
+    /// An allocator whose first pool holds `initial_sets` sets. Pools are named `name[index]`.
+    pub fn new(
+        device: Arc<Device>,
+        name: impl Into<String>,
+        initial_sets: u32,
+        ratios: &[PoolSizeRatio],
+    ) -> Self {
+        Self {
+            device,
+            name: name.into(),
+            ratios: ratios.to_vec(),
+            sets_per_pool: initial_sets.clamp(1, MAX_SETS_PER_POOL),
+            ready_pools: vec![],
+            full_pools: vec![],
+        }
+    }
+
+    /// Number of pools created so far.
+    pub fn pool_count(&self) -> usize {
+        self.ready_pools.len() + self.full_pools.len()
+    }
+
+    /// A descriptor set with `layout` that is valid until the next reset. Allocates from a new
+    /// pool when the current one is full.
+    pub fn allocate(
+        &mut self,
+        layout: vk::DescriptorSetLayout,
+    ) -> crate::Result<vk::DescriptorSet> {
+        let pool = self.pool()?;
+        match self.allocate_from(pool, layout) {
+            Err(vk::ErrorCode::OUT_OF_POOL_MEMORY | vk::ErrorCode::FRAGMENTED_POOL) => {
+                self.full_pools.push(pool);
+                let pool = self.pool()?;
+                let result = self.allocate_from(pool, layout);
+                self.ready_pools.push(pool);
+                Ok(result?)
+            }
+            result => {
+                self.ready_pools.push(pool);
+                Ok(result?)
+            }
+        }
+    }
+
+    fn allocate_from(
+        &self,
+        pool: vk::DescriptorPool,
+        layout: vk::DescriptorSetLayout,
+    ) -> Result<vk::DescriptorSet, vk::ErrorCode> {
+        let layouts = [layout];
+        let allocate_info = vk::DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(pool)
+            .set_layouts(&layouts);
+        unsafe { self.device.allocate_descriptor_sets(&allocate_info) }.map(|sets| sets[0])
+    }
+
+    /// A pool with free space, taken out of the ready list.
+    fn pool(&mut self) -> crate::Result<vk::DescriptorPool> {
+        if let Some(pool) = self.ready_pools.pop() {
+            return Ok(pool);
+        }
+
+        let sets = self.sets_per_pool;
+        let pool_sizes = self
+            .ratios
+            .iter()
+            .map(|ratio| vk::DescriptorPoolSize {
+                type_: ratio.descriptor_type,
+                descriptor_count: ((ratio.ratio * sets as f32).ceil() as u32).max(1),
+            })
+            .collect::<Vec<_>>();
+        let pool_info = vk::DescriptorPoolCreateInfo::builder()
+            .max_sets(sets)
+            .pool_sizes(&pool_sizes);
+
+        let name = format!("{}[{}]", self.name, self.pool_count());
+        let device = &self.device;
+        let pool =
+            unsafe { device.create_descriptor_pool(&pool_info, device.allocation_callbacks()) }
+                .map_err(|code| crate::CreateError {
+                    kind: vk::ObjectType::DESCRIPTOR_POOL,
+                    name: name.clone(),
+                    details: format!("{sets} sets"),
+                    code,
+                })?;
+        if let Err(e) = device.set_object_name(pool, &name) {
+            unsafe { device.destroy_descriptor_pool(pool, device.allocation_callbacks()) };
+            return Err(e);
+        }
+
+        self.sets_per_pool = (sets + sets / 2).min(MAX_SETS_PER_POOL);
+
+        Ok(pool)
+    }
+
+    /// Free every set allocated since the last reset. The work using them must have finished.
+    #[cfg_attr(feature = "enable_tracing", tracing::instrument(skip(self), fields(name = %self.name)))]
+    pub fn reset(&mut self) -> crate::Result<()> {
+        for &pool in self.ready_pools.iter().chain(&self.full_pools) {
+            unsafe {
+                self.device
+                    .reset_descriptor_pool(pool, vk::DescriptorPoolResetFlags::empty())
+            }?;
+        }
+        self.ready_pools.append(&mut self.full_pools);
+
+        #[cfg(feature = "enable_tracing")]
+        tracing::trace!(pools = self.ready_pools.len(), "Reset descriptor pools");
+
+        Ok(())
+    }
+
+    /// Destroy every pool, which frees the sets allocated from them.
+    pub fn destroy(&mut self) {
+        let allocation_callbacks = self.device.allocation_callbacks();
+        for pool in self.ready_pools.drain(..).chain(self.full_pools.drain(..)) {
+            unsafe {
+                self.device
+                    .destroy_descriptor_pool(pool, allocation_callbacks)
+            };
+        }
+    }
+}
+
+impl Resettable for DescriptorAllocator {
+    fn reset(&mut self) -> crate::Result<()> {
+        DescriptorAllocator::reset(self)
+    }
+}
@@ -1,15 +1,23 @@
 use crate::Instance;
-use std::borrow::Cow;
-use std::cell::RefCell;
+use crate::allocator::TrackedAllocationCallbacks;
+use crate::leak::LiveObjects;
+use crate::summary::extension_names;
+use crate::swapchain::{SurfaceSupport, query_surface_support_details};
+use crate::thread_audit::QueueAudit;
+use std::any::{Any, TypeId};
 use std::cmp::Ordering;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::CString;
 use std::fmt::Debug;
 use std::hint::unreachable_unchecked;
-use std::ops::Deref;
-use std::sync::Arc;
+use std::ops::{Deref, Range};
+use std::sync::{Arc, Mutex};
 use vulkanalia::Version;
 use vulkanalia::vk::{
-    self, DeviceV1_0, HasBuilder, InstanceV1_0, InstanceV1_1, KhrSurfaceExtensionInstanceCommands,
+    self, DeviceV1_0, ExtDebugUtilsExtensionInstanceCommands,
+    ExtDeviceFaultExtensionDeviceCommands, ExtPageableDeviceLocalMemoryExtensionDeviceCommands,
+    Handle, HasBuilder, InstanceV1_0, InstanceV1_1, KhrCooperativeMatrixExtensionInstanceCommands,
+    KhrSurfaceExtensionInstanceCommands, NvCooperativeMatrixExtensionInstanceCommands,
 };
 use vulkanalia::vk::{AllocationCallbacks, DeviceV1_1};
 
@@ -86,6 +94,70 @@ fn supports_features(
     features_supported.match_all(features_requested)
 }
 
+fn merge_features(features: &mut vk::PhysicalDeviceFeatures, other: &vk::PhysicalDeviceFeatures) {
+    macro_rules! merge_feature {
+        ($feature: ident) => {
+            features.$feature |= other.$feature;
+        };
+    }
+
+    merge_feature!(robust_buffer_access);
+    merge_feature!(full_draw_index_uint32);
+    merge_feature!(image_cube_array);
+    merge_feature!(independent_blend);
+    merge_feature!(geometry_shader);
+    merge_feature!(tessellation_shader);
+    merge_feature!(sample_rate_shading);
+    merge_feature!(dual_src_blend);
+    merge_feature!(logic_op);
+    merge_feature!(multi_draw_indirect);
+    merge_feature!(draw_indirect_first_instance);
+    merge_feature!(depth_clamp);
+    merge_feature!(depth_bias_clamp);
+    merge_feature!(fill_mode_non_solid);
+    merge_feature!(depth_bounds);
+    merge_feature!(wide_lines);
+    merge_feature!(large_points);
+    merge_feature!(alpha_to_one);
+    merge_feature!(multi_viewport);
+    merge_feature!(sampler_anisotropy);
+    merge_feature!(texture_compression_etc2);
+    merge_feature!(texture_compression_astc_ldr);
+    merge_feature!(texture_compression_bc);
+    merge_feature!(occlusion_query_precise);
+    merge_feature!(pipeline_statistics_query);
+    merge_feature!(vertex_pipeline_stores_and_atomics);
+    merge_feature!(fragment_stores_and_atomics);
+    merge_feature!(shader_tessellation_and_geometry_point_size);
+    merge_feature!(shader_image_gather_extended);
+    merge_feature!(shader_storage_image_extended_formats);
+    merge_feature!(shader_storage_image_multisample);
+    merge_feature!(shader_storage_image_read_without_format);
+    merge_feature!(shader_storage_image_write_without_format);
+    merge_feature!(shader_uniform_buffer_array_dynamic_indexing);
+    merge_feature!(shader_sampled_image_array_dynamic_indexing);
+    merge_feature!(shader_storage_buffer_array_dynamic_indexing);
+    merge_feature!(shader_storage_image_array_dynamic_indexing);
+    merge_feature!(shader_clip_distance);
+    merge_feature!(shader_cull_distance);
+    merge_feature!(shader_float64);
+    merge_feature!(shader_int64);
+    merge_feature!(shader_int16);
+    merge_feature!(shader_resource_residency);
+    merge_feature!(shader_resource_min_lod);
+    merge_feature!(sparse_binding);
+    merge_feature!(sparse_residency_buffer);
+    merge_feature!(sparse_residency_image_2d);
+    merge_feature!(sparse_residency_image_3d);
+    merge_feature!(sparse_residency2_samples);
+    merge_feature!(sparse_residency4_samples);
+    merge_feature!(sparse_residency8_samples);
+    merge_feature!(sparse_residency16_samples);
+    merge_feature!(sparse_residency_aliased);
+    merge_feature!(variable_multisample_rate);
+    merge_feature!(inherited_queries);
+}
+
 #[inline]
 fn get_first_queue_index(
     families: &[vk::QueueFamilyProperties],
@@ -145,10 +217,8 @@ fn get_present_queue_index(
                 instance.get_physical_device_surface_support_khr(device, i as u32, surface)
             };
 
-            if let Ok(present_support) = present_support {
-                if present_support {
-                    return Some(i);
-                }
+            if let Ok(true) = present_support {
+                return Some(i);
             }
         }
     }
@@ -174,8 +244,171 @@ fn check_device_extension_support(
     extensions_to_enable
 }
 
+/// How [`PhysicalDeviceSelector::name`] is compared to the name reported by the driver, which
+/// can differ between driver versions ("NVIDIA GeForce RTX 3080" vs "GeForce RTX 3080").
+#[derive(Default, Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NameMatch {
+    #[default]
+    Exact,
+    ExactIgnoreCase,
+    /// The device name contains the requested name.
+    Contains,
+    ContainsIgnoreCase,
+    /// The requested name is a glob pattern, `*` matches any run of characters and `?` a single
+    /// character. See [`PhysicalDeviceSelector::name_matches`].
+    Glob,
+    /// The requested name is a regular expression that must match somewhere in the device name.
+    /// Selection fails with `ConfigError::InvalidNameRegex` for an invalid expression.
+    #[cfg(feature = "regex")]
+    Regex,
+}
+
+/// The requested device name with its regular expression compiled, built once per selection.
+struct NameFilter<'a> {
+    requested: &'a str,
+    name_match: NameMatch,
+    #[cfg(feature = "regex")]
+    regex: Option<regex::Regex>,
+}
+
+impl<'a> NameFilter<'a> {
+    fn new(criteria: &'a SelectionCriteria) -> crate::Result<Self> {
+        #[cfg(feature = "regex")]
+        let regex = if criteria.name_match == NameMatch::Regex && !criteria.name.is_empty() {
+            match &criteria.name_regex {
+                Some(regex) if regex.as_str() == criteria.name => Some(regex.clone()),
+                _ => Some(compile_name_regex(&criteria.name)?),
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            requested: &criteria.name,
+            name_match: criteria.name_match,
+            #[cfg(feature = "regex")]
+            regex,
+        })
+    }
+
+    fn matches(&self, device_name: &str) -> bool {
+        let requested = self.requested;
+        if requested.is_empty() {
+            return true;
+        }
+
+        match self.name_match {
+            NameMatch::Exact => device_name == requested,
+            NameMatch::ExactIgnoreCase => device_name.to_lowercase() == requested.to_lowercase(),
+            NameMatch::Contains => device_name.contains(requested),
+            NameMatch::ContainsIgnoreCase => device_name
+                .to_lowercase()
+                .contains(&requested.to_lowercase()),
+            NameMatch::Glob => glob_matches(requested, device_name),
+            #[cfg(feature = "regex")]
+            NameMatch::Regex => self
+                .regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(device_name)),
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+fn compile_name_regex(pattern: &str) -> crate::Result<regex::Regex> {
+    regex::Regex::new(pattern)
+        .map_err(|e| crate::ConfigError::InvalidNameRegex(e.to_string()).into())
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently absorbs up to.
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Power preference for hybrid (integrated + discrete GPU) systems.
+///
+/// The preference ranks suitable devices that are equally suitable and match the preferred
+/// device type (or `device_type_order`) equally well, by type and then by vendor. It can only
+/// choose among
+/// the devices the driver exposes. On Windows laptops with NVIDIA Optimus or AMD switchable
+/// graphics, the discrete GPU may be hidden unless the executable exports
+/// `NvOptimusEnablement` / `AmdPowerXpressRequestHighPerformance` (as `u32` values of 1). On
+/// Linux PRIME setups, set `__NV_PRIME_RENDER_OFFLOAD=1` or `DRI_PRIME=1` before launching.
+#[derive(Default, Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerPreference {
+    /// Keep the selector's default ranking.
+    #[default]
+    Default,
+    /// Prefer integrated GPUs, then Intel devices.
+    LowPower,
+    /// Prefer discrete GPUs, then NVIDIA and AMD devices.
+    HighPerformance,
+}
+
+impl PowerPreference {
+    fn score(self, properties: &vk::PhysicalDeviceProperties) -> u32 {
+        const VENDOR_AMD: u32 = 0x1002;
+        const VENDOR_NVIDIA: u32 = 0x10DE;
+        const VENDOR_INTEL: u32 = 0x8086;
+
+        let (type_score, vendor_score) = match self {
+            PowerPreference::Default => return 0,
+            PowerPreference::LowPower => (
+                match properties.device_type {
+                    vk::PhysicalDeviceType::INTEGRATED_GPU => 3,
+                    vk::PhysicalDeviceType::DISCRETE_GPU | vk::PhysicalDeviceType::VIRTUAL_GPU => 2,
+                    vk::PhysicalDeviceType::CPU => 1,
+                    _ => 0,
+                },
+                (properties.vendor_id == VENDOR_INTEL) as u32,
+            ),
+            PowerPreference::HighPerformance => (
+                match properties.device_type {
+                    vk::PhysicalDeviceType::DISCRETE_GPU => 3,
+                    vk::PhysicalDeviceType::INTEGRATED_GPU
+                    | vk::PhysicalDeviceType::VIRTUAL_GPU => 2,
+                    vk::PhysicalDeviceType::CPU => 1,
+                    _ => 0,
+                },
+                matches!(properties.vendor_id, VENDOR_NVIDIA | VENDOR_AMD) as u32,
+            ),
+        };
+
+        type_score * 2 + vendor_score
+    }
+}
+
 #[repr(u8)]
 #[derive(Default, Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PreferredDeviceType {
     Other = 0,
     Integrated = 1,
@@ -185,7 +418,7 @@ pub enum PreferredDeviceType {
     Cpu = 4,
 }
 
-#[derive(Default, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Suitable {
     #[default]
     Yes,
@@ -193,21 +426,268 @@ pub enum Suitable {
     No,
 }
 
-#[derive(Default, Debug)]
+/// Stable identity of a physical device across runs, used to remember the user's GPU choice.
+///
+/// Persist it with [`PhysicalDeviceId::to_bytes`] and pass it back to
+/// [`PhysicalDeviceSelector::prefer_previous_device`] on the next launch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicalDeviceId {
+    pub uuid: [u8; vk::UUID_SIZE],
+    pub luid: Option<[u8; vk::LUID_SIZE]>,
+}
+
+impl PhysicalDeviceId {
+    /// Serialize to a small settings blob: the UUID, followed by the LUID when it is valid.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.uuid.to_vec();
+        if let Some(luid) = self.luid {
+            bytes.extend_from_slice(&luid);
+        }
+        bytes
+    }
+
+    /// Parse a blob written by [`PhysicalDeviceId::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let uuid = bytes.get(..vk::UUID_SIZE)?.try_into().ok()?;
+        let luid = match &bytes[vk::UUID_SIZE..] {
+            [] => None,
+            luid => Some(luid.try_into().ok()?),
+        };
+
+        Some(Self { uuid, luid })
+    }
+
+    /// Whether `other` refers to the same device. The LUID is only compared when both have one.
+    pub fn matches(&self, other: &PhysicalDeviceId) -> bool {
+        match (self.luid, other.luid) {
+            (Some(a), Some(b)) => self.uuid == other.uuid && a == b,
+            _ => self.uuid == other.uuid,
+        }
+    }
+}
+
+/// A check of the device limits added with [`PhysicalDeviceSelector::required_limit`].
+#[derive(Clone)]
+pub struct LimitRequirement(Arc<dyn Fn(&vk::PhysicalDeviceLimits) -> bool + Send + Sync>);
+
+impl Debug for LimitRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LimitRequirement")
+    }
+}
+
+/// A properties struct requested with [`PhysicalDeviceSelector::request_properties`].
+#[derive(Debug, Clone, Copy)]
+pub struct PropertiesRequest {
+    type_id: TypeId,
+    query: fn(&Instance, vk::PhysicalDevice) -> Arc<dyn Any + Send + Sync>,
+}
+
+fn query_properties<T>(
+    instance: &Instance,
+    physical_device: vk::PhysicalDevice,
+) -> Arc<dyn Any + Send + Sync>
+where
+    T: vk::ExtendsPhysicalDeviceProperties2
+        + vk::Cast<Target = T>
+        + Default
+        + Send
+        + Sync
+        + 'static,
+{
+    let mut properties = T::default();
+    let mut properties2 = vk::PhysicalDeviceProperties2::builder().push_next(&mut properties);
+    unsafe {
+        instance
+            .instance
+            .get_physical_device_properties2(physical_device, &mut properties2)
+    };
+
+    Arc::new(properties)
+}
+
+/// Driver identification from `vk::PhysicalDeviceDriverProperties`, see
+/// [`PhysicalDevice::driver`].
+///
+/// Displays as the driver name and info, e.g. "radv Mesa 24.0.5".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriverInfo {
+    pub id: vk::DriverId,
+    pub name: String,
+    /// Free-form version information, e.g. "Mesa 24.0.5".
+    pub info: String,
+    /// Version of the Vulkan conformance test suite the driver passed.
+    pub conformance_version: vk::ConformanceVersion,
+}
+
+impl std::fmt::Display for DriverInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.info.is_empty() {
+            write!(f, " {}", self.info)?;
+        }
+        Ok(())
+    }
+}
+
+/// Depth/stencil resolve support from `vk::PhysicalDeviceDepthStencilResolveProperties`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DepthStencilResolveSupport {
+    pub supported_depth_resolve_modes: vk::ResolveModeFlags,
+    pub supported_stencil_resolve_modes: vk::ResolveModeFlags,
+    pub independent_resolve_none: bool,
+    pub independent_resolve: bool,
+}
+
+impl DepthStencilResolveSupport {
+    /// Whether a multisampled depth/stencil attachment can be resolved with these modes.
+    ///
+    /// Use it to validate the `depth_resolve_mode`/`stencil_resolve_mode` of a
+    /// `vk::SubpassDescriptionDepthStencilResolve` or `vk::RenderingAttachmentInfo` before
+    /// recording. `vk::ResolveModeFlags::NONE` skips resolving that aspect.
+    pub fn supports(&self, depth: vk::ResolveModeFlags, stencil: vk::ResolveModeFlags) -> bool {
+        let none = vk::ResolveModeFlags::NONE;
+        if (depth != none && !self.supported_depth_resolve_modes.contains(depth))
+            || (stencil != none && !self.supported_stencil_resolve_modes.contains(stencil))
+        {
+            return false;
+        }
+
+        if depth == stencil {
+            true
+        } else if depth == none || stencil == none {
+            self.independent_resolve_none || self.independent_resolve
+        } else {
+            self.independent_resolve
+        }
+    }
+}
+
+/// Budget and usage of a memory heap from `VK_EXT_memory_budget`, see
+/// [`PhysicalDevice::memory_budget`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryHeapBudget {
+    pub flags: vk::MemoryHeapFlags,
+    pub size: vk::DeviceSize,
+    /// How much the process can allocate from the heap before allocations may fail or hurt
+    /// performance. Changes over time, e.g. when other applications allocate.
+    pub budget: vk::DeviceSize,
+    /// How much the process currently uses of the heap.
+    pub usage: vk::DeviceSize,
+}
+
+/// Update-after-bind descriptor limits from `vk::PhysicalDeviceDescriptorIndexingProperties`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DescriptorIndexingLimits {
+    pub max_update_after_bind_descriptors_in_all_pools: u32,
+    pub max_per_stage_update_after_bind_resources: u32,
+    pub max_descriptor_set_update_after_bind_samplers: u32,
+    pub max_descriptor_set_update_after_bind_sampled_images: u32,
+    pub max_descriptor_set_update_after_bind_storage_images: u32,
+    pub max_descriptor_set_update_after_bind_storage_buffers: u32,
+    pub max_descriptor_set_update_after_bind_uniform_buffers: u32,
+}
+
+impl DescriptorIndexingLimits {
+    /// Whether a single bindless set can hold `count` samplers, sampled images, storage images
+    /// and storage buffers.
+    pub fn supports_bindless(&self, count: u32) -> bool {
+        self.max_update_after_bind_descriptors_in_all_pools >= count
+            && self.max_descriptor_set_update_after_bind_samplers >= count
+            && self.max_descriptor_set_update_after_bind_sampled_images >= count
+            && self.max_descriptor_set_update_after_bind_storage_images >= count
+            && self.max_descriptor_set_update_after_bind_storage_buffers >= count
+    }
+}
+
+/// Fragment shading rate limits from `vk::PhysicalDeviceFragmentShadingRatePropertiesKHR`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FragmentShadingRateSupport {
+    pub min_attachment_texel_size: vk::Extent2D,
+    pub max_attachment_texel_size: vk::Extent2D,
+    pub max_attachment_texel_size_aspect_ratio: u32,
+    pub max_fragment_size: vk::Extent2D,
+    pub max_fragment_size_aspect_ratio: u32,
+    pub layered_shading_rate_attachments: bool,
+    pub non_trivial_combiner_ops: bool,
+    /// Whether the `attachment_fragment_shading_rate` feature is supported.
+    pub attachment_fragment_shading_rate: bool,
+}
+
+impl FragmentShadingRateSupport {
+    /// Whether a shading rate attachment can use texels (tiles) of `size`: the
+    /// `attachment_fragment_shading_rate` feature is supported, both dimensions are powers of two
+    /// within the device limits, and their aspect ratio is not too large.
+    pub fn supports_attachment_texel_size(&self, size: vk::Extent2D) -> bool {
+        if !self.attachment_fragment_shading_rate {
+            return false;
+        }
+
+        let (min, max) = (
+            self.min_attachment_texel_size,
+            self.max_attachment_texel_size,
+        );
+        if !size.width.is_power_of_two()
+            || !size.height.is_power_of_two()
+            || !(min.width..=max.width).contains(&size.width)
+            || !(min.height..=max.height).contains(&size.height)
+        {
+            return false;
+        }
+
+        let aspect_ratio = size.width.max(size.height) / size.width.min(size.height);
+        aspect_ratio <= self.max_attachment_texel_size_aspect_ratio
+    }
+}
+
+/// Subgroup support from `vk::PhysicalDeviceSubgroupProperties` (Vulkan 1.1).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubgroupCapabilities {
+    pub size: u32,
+    pub supported_stages: vk::ShaderStageFlags,
+    pub supported_operations: vk::SubgroupFeatureFlags,
+    pub quad_operations_in_all_stages: bool,
+}
+
+/// Compute limits and subgroup support of a physical device, see
+/// [`PhysicalDevice::compute_capabilities`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeCapabilities {
+    /// `None` on Vulkan 1.0 devices or when the instance can't query extended properties.
+    pub subgroup: Option<SubgroupCapabilities>,
+    pub max_work_group_count: [u32; 3],
+    pub max_work_group_size: [u32; 3],
+    pub max_work_group_invocations: u32,
+    pub max_shared_memory_size: u32,
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct PhysicalDevice {
     name: String,
     physical_device: vk::PhysicalDevice,
     surface: Option<vk::SurfaceKHR>,
 
     features: vk::PhysicalDeviceFeatures,
+    supported_features: vk::PhysicalDeviceFeatures,
     pub properties: vk::PhysicalDeviceProperties,
     memory_properties: vk::PhysicalDeviceMemoryProperties,
     extensions_to_enable: BTreeSet<vk::ExtensionName>,
     available_extensions: BTreeSet<vk::ExtensionName>,
-    queue_families: Vec<vk::QueueFamilyProperties>,
+    pub(crate) queue_families: Vec<vk::QueueFamilyProperties>,
     defer_surface_initialization: bool,
+    require_present: bool,
     properties2_ext_enabled: bool,
-    //supported_format_properties: HashMap<vk::Format, vk::FormatProperties>,
+    /// Filled on first use by [`PhysicalDevice::format_properties`], shared between clones.
+    format_properties: Arc<Mutex<HashMap<vk::Format, vk::FormatProperties>>>,
+    device_id: Option<PhysicalDeviceId>,
+    driver: Option<DriverInfo>,
+    depth_stencil_resolve: Option<DepthStencilResolveSupport>,
+    descriptor_indexing: Option<DescriptorIndexingLimits>,
+    fragment_shading_rate: Option<FragmentShadingRateSupport>,
+    subgroup: Option<SubgroupCapabilities>,
+    cooperative_matrix_properties: Vec<vk::CooperativeMatrixPropertiesKHR>,
+    extension_properties: Vec<Arc<dyn Any + Send + Sync>>,
     suitable: Suitable,
     supported_features_chain: GenericFeatureChain,
     requested_features_chain: GenericFeatureChain,
@@ -242,6 +722,206 @@ impl Ord for PhysicalDevice {
 }
 
 impl PhysicalDevice {
+    /// Properties of every queue family, indexed by family index.
+    pub fn queue_families(&self) -> &[vk::QueueFamilyProperties] {
+        &self.queue_families
+    }
+
+    /// UUID/LUID of the device. `None` when the instance can't query `vk::PhysicalDeviceIDProperties`
+    /// (Vulkan 1.0 without `VK_KHR_get_physical_device_properties2`).
+    pub fn device_id(&self) -> Option<PhysicalDeviceId> {
+        self.device_id
+    }
+
+    /// A properties struct requested with [`PhysicalDeviceSelector::request_properties`], e.g.
+    /// `vk::PhysicalDeviceRayTracingPipelinePropertiesKHR`. `None` when it wasn't requested or
+    /// the instance can't query extended properties.
+    pub fn extension_properties<T: Copy + 'static>(&self) -> Option<T> {
+        self.extension_properties
+            .iter()
+            .find_map(|properties| properties.downcast_ref::<T>())
+            .copied()
+    }
+
+    /// Buffer, linear and optimal tiling features the device supports for `format`.
+    ///
+    /// Queried on first use and cached, also for clones of this `PhysicalDevice` and the
+    /// [`Device`] created from it.
+    pub fn format_properties(
+        &self,
+        instance: &Instance,
+        format: vk::Format,
+    ) -> vk::FormatProperties {
+        *self
+            .format_properties
+            .lock()
+            .unwrap()
+            .entry(format)
+            .or_insert_with(|| unsafe {
+                instance
+                    .instance
+                    .get_physical_device_format_properties(self.physical_device, format)
+            })
+    }
+
+    /// Whether images of `format` with `tiling` support all of `features`. Tilings other than
+    /// linear and optimal are reported as unsupported.
+    pub fn supports_format(
+        &self,
+        instance: &Instance,
+        format: vk::Format,
+        tiling: vk::ImageTiling,
+        features: vk::FormatFeatureFlags,
+    ) -> bool {
+        let properties = self.format_properties(instance, format);
+        match tiling {
+            vk::ImageTiling::LINEAR => properties.linear_tiling_features.contains(features),
+            vk::ImageTiling::OPTIMAL => properties.optimal_tiling_features.contains(features),
+            _ => false,
+        }
+    }
+
+    /// The first format of `candidates` that supports `features` with `tiling`, e.g. to pick a
+    /// depth format:
+    ///
+    /// ``` no_run
+    /// # use vulkanalia::vk;
+    /// # fn pick(instance: &vulkanalia_bootstrap::Instance, physical_device: &vulkanalia_bootstrap::PhysicalDevice) -> Option<vk::Format> {
+    /// physical_device.find_supported_format(
+    ///     instance,
+    ///     &[vk::Format::D32_SFLOAT, vk::Format::D32_SFLOAT_S8_UINT, vk::Format::D24_UNORM_S8_UINT],
+    ///     vk::ImageTiling::OPTIMAL,
+    ///     vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT,
+    /// )
+    /// # }
+    /// ```
+    pub fn find_supported_format(
+        &self,
+        instance: &Instance,
+        candidates: &[vk::Format],
+        tiling: vk::ImageTiling,
+        features: vk::FormatFeatureFlags,
+    ) -> Option<vk::Format> {
+        candidates
+            .iter()
+            .copied()
+            .find(|&format| self.supports_format(instance, format, tiling, features))
+    }
+
+    /// Driver ID, name and version info. `None` when the device is older than Vulkan 1.2 without
+    /// `VK_KHR_driver_properties`, or the instance can't query extended properties.
+    pub fn driver(&self) -> Option<&DriverInfo> {
+        self.driver.as_ref()
+    }
+
+    /// Query the capabilities, formats and present modes `surface` supports on this device,
+    /// e.g. to inspect the extent limits or supported composite alpha before building a swapchain.
+    pub fn surface_support(
+        &self,
+        instance: &Instance,
+        surface: vk::SurfaceKHR,
+    ) -> crate::Result<SurfaceSupport> {
+        query_surface_support_details(self.physical_device, instance, Some(surface))
+    }
+
+    /// Subgroup support and compute work group limits of the device.
+    pub fn compute_capabilities(&self) -> ComputeCapabilities {
+        let limits = &self.properties.limits;
+        ComputeCapabilities {
+            subgroup: self.subgroup,
+            max_work_group_count: limits.max_compute_work_group_count,
+            max_work_group_size: limits.max_compute_work_group_size,
+            max_work_group_invocations: limits.max_compute_work_group_invocations,
+            max_shared_memory_size: limits.max_compute_shared_memory_size,
+        }
+    }
+
+    /// Matrix configurations (tile shapes and component types) supported by
+    /// `VK_KHR_cooperative_matrix`, or by `VK_NV_cooperative_matrix` when only that is available.
+    /// Empty when neither extension is supported.
+    pub fn cooperative_matrix_properties(&self) -> &[vk::CooperativeMatrixPropertiesKHR] {
+        &self.cooperative_matrix_properties
+    }
+
+    /// Supported depth/stencil resolve modes. `None` when the device has neither Vulkan 1.2 nor
+    /// `VK_KHR_depth_stencil_resolve`, or the instance can't query extended properties.
+    pub fn depth_stencil_resolve(&self) -> Option<DepthStencilResolveSupport> {
+        self.depth_stencil_resolve
+    }
+
+    /// Update-after-bind descriptor limits. `None` when the device has neither Vulkan 1.2 nor
+    /// `VK_EXT_descriptor_indexing`, or the instance can't query extended properties.
+    pub fn descriptor_indexing_limits(&self) -> Option<DescriptorIndexingLimits> {
+        self.descriptor_indexing
+    }
+
+    /// Fragment shading rate limits. `None` when the device doesn't support
+    /// `VK_KHR_fragment_shading_rate`, or the instance can't query extended properties.
+    pub fn fragment_shading_rate(&self) -> Option<FragmentShadingRateSupport> {
+        self.fragment_shading_rate
+    }
+
+    /// Current budget and usage of each memory heap, indexed like the heaps of the memory
+    /// properties. `None` when the device doesn't support `VK_EXT_memory_budget` or the
+    /// instance can't query extended properties.
+    pub fn memory_budget(&self, instance: &Instance) -> Option<Vec<MemoryHeapBudget>> {
+        if !(instance.instance_version >= Version::V1_1_0 || self.properties2_ext_enabled)
+            || !self
+                .available_extensions
+                .contains(&vk::EXT_MEMORY_BUDGET_EXTENSION.name)
+        {
+            return None;
+        }
+
+        let mut budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let mut memory_properties2 =
+            vk::PhysicalDeviceMemoryProperties2::builder().push_next(&mut budget_properties);
+        unsafe {
+            instance.instance.get_physical_device_memory_properties2(
+                self.physical_device,
+                &mut memory_properties2,
+            )
+        };
+
+        let memory_properties = memory_properties2.memory_properties;
+        let heaps = &memory_properties.memory_heaps[..memory_properties.memory_heap_count as usize];
+        Some(
+            heaps
+                .iter()
+                .enumerate()
+                .map(|(index, heap)| MemoryHeapBudget {
+                    flags: heap.flags,
+                    size: heap.size,
+                    budget: budget_properties.heap_budget[index],
+                    usage: budget_properties.heap_usage[index],
+                })
+                .collect(),
+        )
+    }
+
+    /// Total size of the device local memory heaps. Includes shared system memory on integrated
+    /// GPUs that expose it as device local.
+    pub fn device_local_memory_size(&self) -> u64 {
+        let heaps = &self.memory_properties.memory_heaps
+            [..self.memory_properties.memory_heap_count as usize];
+        heaps
+            .iter()
+            .filter(|heap| heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL))
+            .map(|heap| heap.size)
+            .sum()
+    }
+
+    /// Find the index of a memory type allowed by `type_bits` (from `vk::MemoryRequirements`)
+    /// that has all of the requested property `flags`.
+    pub fn memory_type_index(&self, type_bits: u32, flags: vk::MemoryPropertyFlags) -> Option<u32> {
+        (0..self.memory_properties.memory_type_count).find(|&i| {
+            type_bits & (1 << i) != 0
+                && self.memory_properties.memory_types[i as usize]
+                    .property_flags
+                    .contains(flags)
+        })
+    }
+
     pub fn msaa_samples(&self) -> vk::SampleCountFlags {
         let limits = &self.properties.limits;
         let counts =
@@ -274,12 +954,63 @@ impl PhysicalDevice {
         vk::SampleCountFlags::_1
     }
 
+    /// Core features that will be enabled when creating the logical device: the required ones
+    /// plus the desired ones the device supports.
+    pub fn enabled_features(&self) -> vk::PhysicalDeviceFeatures {
+        self.features
+    }
+
+    /// Feature2 structs that will be enabled when creating the logical device: the required ones
+    /// plus the desired ones the device supports.
+    pub fn enabled_extension_features(&self) -> &[VulkanPhysicalDeviceFeature2] {
+        &self.requested_features_chain
+    }
+
+    /// Whether the device supports every feature set in `features`.
+    pub fn supports_core_features(&self, features: &vk::PhysicalDeviceFeatures) -> bool {
+        supports_features(
+            &self.supported_features,
+            features,
+            &GenericFeatureChain::new(),
+            &GenericFeatureChain::new(),
+        )
+    }
+
+    /// Whether the device supports every feature set in `feature` (a vulkan feature2 struct),
+    /// e.g. to decide on optional code paths after selection. Always false when `instance` can't
+    /// query extended features (Vulkan 1.0 without `VK_KHR_get_physical_device_properties2`).
+    pub fn supports_features<T: Into<VulkanPhysicalDeviceFeature2>>(
+        &self,
+        instance: &Instance,
+        feature: T,
+    ) -> bool {
+        if instance.instance_version < Version::V1_1_0 && !instance.properties2_ext_enabled {
+            return false;
+        }
+
+        let feature = feature.into();
+        let mut supported = GenericFeatureChain::new();
+        supported.add(feature.clone());
+        supported.query(instance, self.physical_device);
+        supported.matches(&feature)
+    }
+
+    /// If every feature in `features` is supported by this physical device, mark them to be
+    /// enabled when creating the logical device and return true. If any are missing, nothing is
+    /// enabled and false is returned.
+    pub fn enable_features_if_present(&mut self, features: vk::PhysicalDeviceFeatures) -> bool {
+        if self.supports_core_features(&features) {
+            merge_features(&mut self.features, &features);
+            true
+        } else {
+            false
+        }
+    }
+
     /// If the given device extension is available on this physical device, mark it to be
     /// enabled when creating a logical device and return true. Returns false if the
     /// extension is not present.
     pub fn enable_extension_if_present(&mut self, extension: vk::ExtensionName) -> bool {
-        let extension = extension.into();
-
         if self.available_extensions.contains(&extension) {
             self.extensions_to_enable.insert(extension)
         } else {
@@ -315,6 +1046,14 @@ pub enum VulkanPhysicalDeviceFeature2 {
     PhysicalDeviceVulkan11(vk::PhysicalDeviceVulkan11Features),
     PhysicalDeviceVulkan12(vk::PhysicalDeviceVulkan12Features),
     PhysicalDeviceVulkan13(vk::PhysicalDeviceVulkan13Features),
+    PhysicalDeviceCooperativeMatrixKHR(vk::PhysicalDeviceCooperativeMatrixFeaturesKHR),
+    PhysicalDeviceCooperativeMatrixNV(vk::PhysicalDeviceCooperativeMatrixFeaturesNV),
+    PhysicalDeviceAccelerationStructureKHR(vk::PhysicalDeviceAccelerationStructureFeaturesKHR),
+    PhysicalDeviceRayTracingPipelineKHR(vk::PhysicalDeviceRayTracingPipelineFeaturesKHR),
+    PhysicalDeviceMeshShaderEXT(vk::PhysicalDeviceMeshShaderFeaturesEXT),
+    PhysicalDeviceDynamicRendering(vk::PhysicalDeviceDynamicRenderingFeatures),
+    PhysicalDeviceDynamicRenderingLocalRead(vk::PhysicalDeviceDynamicRenderingLocalReadFeatures),
+    PhysicalDeviceFragmentShadingRateKHR(vk::PhysicalDeviceFragmentShadingRateFeaturesKHR),
 }
 
 fn match_features(
@@ -650,72 +1389,222 @@ fn match_features(
             }
             true
         }
-        _ => unsafe { unreachable_unchecked() },
-    }
-}
-impl<'a> VulkanPhysicalDeviceFeature2 {
-    fn combine(&mut self, other: &VulkanPhysicalDeviceFeature2) {
-        assert_eq!(self.s_type(), other.s_type());
-
-        match (self, other) {
-            (
-                Self::PhysicalDeviceVulkan11(f),
-                VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan11(other),
-            ) => {
-                f.storage_buffer_16bit_access |= other.storage_buffer_16bit_access;
-                f.uniform_and_storage_buffer_16bit_access |=
-                    other.uniform_and_storage_buffer_16bit_access;
-                f.storage_push_constant16 |= other.storage_push_constant16;
-                f.storage_input_output16 |= other.storage_input_output16;
-                f.multiview |= other.multiview;
-                f.multiview_geometry_shader |= other.multiview_geometry_shader;
-                f.multiview_tessellation_shader |= other.multiview_tessellation_shader;
-                f.variable_pointers_storage_buffer |= other.variable_pointers_storage_buffer;
-                f.variable_pointers |= other.variable_pointers;
-                f.protected_memory |= other.protected_memory;
-                f.sampler_ycbcr_conversion |= other.sampler_ycbcr_conversion;
-                f.shader_draw_parameters |= other.shader_draw_parameters;
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixKHR(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixKHR(s),
+        ) => {
+            if r.cooperative_matrix == vk::TRUE && s.cooperative_matrix == vk::FALSE {
+                return false;
             }
-            (
-                Self::PhysicalDeviceVulkan12(f),
-                VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan12(other),
-            ) => {
-                f.sampler_mirror_clamp_to_edge |= other.sampler_mirror_clamp_to_edge;
-                f.draw_indirect_count |= other.draw_indirect_count;
-                f.storage_buffer_8bit_access |= other.storage_buffer_8bit_access;
-                f.uniform_and_storage_buffer_8bit_access |=
-                    other.uniform_and_storage_buffer_8bit_access;
-                f.storage_push_constant8 |= other.storage_push_constant8;
-                f.shader_buffer_int64_atomics |= other.shader_buffer_int64_atomics;
-                f.shader_shared_int64_atomics |= other.shader_shared_int64_atomics;
-                f.shader_float16 |= other.shader_float16;
-                f.shader_int8 |= other.shader_int8;
-                f.descriptor_indexing |= other.descriptor_indexing;
-                f.shader_input_attachment_array_dynamic_indexing |=
-                    other.shader_input_attachment_array_dynamic_indexing;
-                f.shader_uniform_texel_buffer_array_dynamic_indexing |=
-                    other.shader_uniform_texel_buffer_array_dynamic_indexing;
-                f.shader_storage_texel_buffer_array_dynamic_indexing |=
-                    other.shader_storage_texel_buffer_array_dynamic_indexing;
-                f.shader_uniform_buffer_array_non_uniform_indexing |=
-                    other.shader_uniform_buffer_array_non_uniform_indexing;
-                f.shader_sampled_image_array_non_uniform_indexing |=
-                    other.shader_sampled_image_array_non_uniform_indexing;
-                f.shader_storage_buffer_array_non_uniform_indexing |=
-                    other.shader_storage_buffer_array_non_uniform_indexing;
-                f.shader_storage_image_array_non_uniform_indexing |=
-                    other.shader_storage_image_array_non_uniform_indexing;
-                f.shader_input_attachment_array_non_uniform_indexing |=
-                    other.shader_input_attachment_array_non_uniform_indexing;
-                f.shader_uniform_texel_buffer_array_non_uniform_indexing |=
-                    other.shader_uniform_texel_buffer_array_non_uniform_indexing;
-                f.shader_storage_texel_buffer_array_non_uniform_indexing |=
-                    other.shader_storage_texel_buffer_array_non_uniform_indexing;
-                f.descriptor_binding_uniform_buffer_update_after_bind |=
-                    other.descriptor_binding_uniform_buffer_update_after_bind;
-                f.descriptor_binding_sampled_image_update_after_bind |=
-                    other.descriptor_binding_sampled_image_update_after_bind;
-                f.descriptor_binding_storage_image_update_after_bind |=
+            if r.cooperative_matrix_robust_buffer_access == vk::TRUE
+                && s.cooperative_matrix_robust_buffer_access == vk::FALSE
+            {
+                return false;
+            }
+            true
+        }
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixNV(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixNV(s),
+        ) => {
+            if r.cooperative_matrix == vk::TRUE && s.cooperative_matrix == vk::FALSE {
+                return false;
+            }
+            if r.cooperative_matrix_robust_buffer_access == vk::TRUE
+                && s.cooperative_matrix_robust_buffer_access == vk::FALSE
+            {
+                return false;
+            }
+            true
+        }
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceAccelerationStructureKHR(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceAccelerationStructureKHR(s),
+        ) => {
+            if r.acceleration_structure == vk::TRUE && s.acceleration_structure == vk::FALSE {
+                return false;
+            }
+            if r.acceleration_structure_capture_replay == vk::TRUE
+                && s.acceleration_structure_capture_replay == vk::FALSE
+            {
+                return false;
+            }
+            if r.acceleration_structure_indirect_build == vk::TRUE
+                && s.acceleration_structure_indirect_build == vk::FALSE
+            {
+                return false;
+            }
+            if r.acceleration_structure_host_commands == vk::TRUE
+                && s.acceleration_structure_host_commands == vk::FALSE
+            {
+                return false;
+            }
+            if r.descriptor_binding_acceleration_structure_update_after_bind == vk::TRUE
+                && s.descriptor_binding_acceleration_structure_update_after_bind == vk::FALSE
+            {
+                return false;
+            }
+            true
+        }
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceRayTracingPipelineKHR(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceRayTracingPipelineKHR(s),
+        ) => {
+            if r.ray_tracing_pipeline == vk::TRUE && s.ray_tracing_pipeline == vk::FALSE {
+                return false;
+            }
+            if r.ray_tracing_pipeline_shader_group_handle_capture_replay == vk::TRUE
+                && s.ray_tracing_pipeline_shader_group_handle_capture_replay == vk::FALSE
+            {
+                return false;
+            }
+            if r.ray_tracing_pipeline_shader_group_handle_capture_replay_mixed == vk::TRUE
+                && s.ray_tracing_pipeline_shader_group_handle_capture_replay_mixed == vk::FALSE
+            {
+                return false;
+            }
+            if r.ray_tracing_pipeline_trace_rays_indirect == vk::TRUE
+                && s.ray_tracing_pipeline_trace_rays_indirect == vk::FALSE
+            {
+                return false;
+            }
+            if r.ray_traversal_primitive_culling == vk::TRUE
+                && s.ray_traversal_primitive_culling == vk::FALSE
+            {
+                return false;
+            }
+            true
+        }
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceMeshShaderEXT(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceMeshShaderEXT(s),
+        ) => {
+            if r.task_shader == vk::TRUE && s.task_shader == vk::FALSE {
+                return false;
+            }
+            if r.mesh_shader == vk::TRUE && s.mesh_shader == vk::FALSE {
+                return false;
+            }
+            if r.multiview_mesh_shader == vk::TRUE && s.multiview_mesh_shader == vk::FALSE {
+                return false;
+            }
+            if r.primitive_fragment_shading_rate_mesh_shader == vk::TRUE
+                && s.primitive_fragment_shading_rate_mesh_shader == vk::FALSE
+            {
+                return false;
+            }
+            if r.mesh_shader_queries == vk::TRUE && s.mesh_shader_queries == vk::FALSE {
+                return false;
+            }
+            true
+        }
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRendering(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRendering(s),
+        ) => {
+            if r.dynamic_rendering == vk::TRUE && s.dynamic_rendering == vk::FALSE {
+                return false;
+            }
+            true
+        }
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRenderingLocalRead(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRenderingLocalRead(s),
+        ) => {
+            if r.dynamic_rendering_local_read == vk::TRUE
+                && s.dynamic_rendering_local_read == vk::FALSE
+            {
+                return false;
+            }
+            true
+        }
+        (
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceFragmentShadingRateKHR(r),
+            VulkanPhysicalDeviceFeature2::PhysicalDeviceFragmentShadingRateKHR(s),
+        ) => {
+            if r.pipeline_fragment_shading_rate == vk::TRUE
+                && s.pipeline_fragment_shading_rate == vk::FALSE
+            {
+                return false;
+            }
+            if r.primitive_fragment_shading_rate == vk::TRUE
+                && s.primitive_fragment_shading_rate == vk::FALSE
+            {
+                return false;
+            }
+            if r.attachment_fragment_shading_rate == vk::TRUE
+                && s.attachment_fragment_shading_rate == vk::FALSE
+            {
+                return false;
+            }
+            true
+        }
+        _ => unsafe { unreachable_unchecked() },
+    }
+}
+impl VulkanPhysicalDeviceFeature2 {
+    fn combine(&mut self, other: &VulkanPhysicalDeviceFeature2) {
+        assert_eq!(self.s_type(), other.s_type());
+
+        match (self, other) {
+            (
+                Self::PhysicalDeviceVulkan11(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan11(other),
+            ) => {
+                f.storage_buffer_16bit_access |= other.storage_buffer_16bit_access;
+                f.uniform_and_storage_buffer_16bit_access |=
+                    other.uniform_and_storage_buffer_16bit_access;
+                f.storage_push_constant16 |= other.storage_push_constant16;
+                f.storage_input_output16 |= other.storage_input_output16;
+                f.multiview |= other.multiview;
+                f.multiview_geometry_shader |= other.multiview_geometry_shader;
+                f.multiview_tessellation_shader |= other.multiview_tessellation_shader;
+                f.variable_pointers_storage_buffer |= other.variable_pointers_storage_buffer;
+                f.variable_pointers |= other.variable_pointers;
+                f.protected_memory |= other.protected_memory;
+                f.sampler_ycbcr_conversion |= other.sampler_ycbcr_conversion;
+                f.shader_draw_parameters |= other.shader_draw_parameters;
+            }
+            (
+                Self::PhysicalDeviceVulkan12(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan12(other),
+            ) => {
+                f.sampler_mirror_clamp_to_edge |= other.sampler_mirror_clamp_to_edge;
+                f.draw_indirect_count |= other.draw_indirect_count;
+                f.storage_buffer_8bit_access |= other.storage_buffer_8bit_access;
+                f.uniform_and_storage_buffer_8bit_access |=
+                    other.uniform_and_storage_buffer_8bit_access;
+                f.storage_push_constant8 |= other.storage_push_constant8;
+                f.shader_buffer_int64_atomics |= other.shader_buffer_int64_atomics;
+                f.shader_shared_int64_atomics |= other.shader_shared_int64_atomics;
+                f.shader_float16 |= other.shader_float16;
+                f.shader_int8 |= other.shader_int8;
+                f.descriptor_indexing |= other.descriptor_indexing;
+                f.shader_input_attachment_array_dynamic_indexing |=
+                    other.shader_input_attachment_array_dynamic_indexing;
+                f.shader_uniform_texel_buffer_array_dynamic_indexing |=
+                    other.shader_uniform_texel_buffer_array_dynamic_indexing;
+                f.shader_storage_texel_buffer_array_dynamic_indexing |=
+                    other.shader_storage_texel_buffer_array_dynamic_indexing;
+                f.shader_uniform_buffer_array_non_uniform_indexing |=
+                    other.shader_uniform_buffer_array_non_uniform_indexing;
+                f.shader_sampled_image_array_non_uniform_indexing |=
+                    other.shader_sampled_image_array_non_uniform_indexing;
+                f.shader_storage_buffer_array_non_uniform_indexing |=
+                    other.shader_storage_buffer_array_non_uniform_indexing;
+                f.shader_storage_image_array_non_uniform_indexing |=
+                    other.shader_storage_image_array_non_uniform_indexing;
+                f.shader_input_attachment_array_non_uniform_indexing |=
+                    other.shader_input_attachment_array_non_uniform_indexing;
+                f.shader_uniform_texel_buffer_array_non_uniform_indexing |=
+                    other.shader_uniform_texel_buffer_array_non_uniform_indexing;
+                f.shader_storage_texel_buffer_array_non_uniform_indexing |=
+                    other.shader_storage_texel_buffer_array_non_uniform_indexing;
+                f.descriptor_binding_uniform_buffer_update_after_bind |=
+                    other.descriptor_binding_uniform_buffer_update_after_bind;
+                f.descriptor_binding_sampled_image_update_after_bind |=
+                    other.descriptor_binding_sampled_image_update_after_bind;
+                f.descriptor_binding_storage_image_update_after_bind |=
                     other.descriptor_binding_storage_image_update_after_bind;
                 f.descriptor_binding_storage_buffer_update_after_bind |=
                     other.descriptor_binding_storage_buffer_update_after_bind;
@@ -771,6 +1660,80 @@ impl<'a> VulkanPhysicalDeviceFeature2 {
                 f.shader_integer_dot_product |= other.shader_integer_dot_product;
                 f.maintenance4 |= other.maintenance4;
             }
+            (
+                Self::PhysicalDeviceCooperativeMatrixKHR(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixKHR(other),
+            ) => {
+                f.cooperative_matrix |= other.cooperative_matrix;
+                f.cooperative_matrix_robust_buffer_access |=
+                    other.cooperative_matrix_robust_buffer_access;
+            }
+            (
+                Self::PhysicalDeviceCooperativeMatrixNV(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixNV(other),
+            ) => {
+                f.cooperative_matrix |= other.cooperative_matrix;
+                f.cooperative_matrix_robust_buffer_access |=
+                    other.cooperative_matrix_robust_buffer_access;
+            }
+            (
+                Self::PhysicalDeviceAccelerationStructureKHR(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceAccelerationStructureKHR(other),
+            ) => {
+                f.acceleration_structure |= other.acceleration_structure;
+                f.acceleration_structure_capture_replay |=
+                    other.acceleration_structure_capture_replay;
+                f.acceleration_structure_indirect_build |=
+                    other.acceleration_structure_indirect_build;
+                f.acceleration_structure_host_commands |=
+                    other.acceleration_structure_host_commands;
+                f.descriptor_binding_acceleration_structure_update_after_bind |=
+                    other.descriptor_binding_acceleration_structure_update_after_bind;
+            }
+            (
+                Self::PhysicalDeviceRayTracingPipelineKHR(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceRayTracingPipelineKHR(other),
+            ) => {
+                f.ray_tracing_pipeline |= other.ray_tracing_pipeline;
+                f.ray_tracing_pipeline_shader_group_handle_capture_replay |=
+                    other.ray_tracing_pipeline_shader_group_handle_capture_replay;
+                f.ray_tracing_pipeline_shader_group_handle_capture_replay_mixed |=
+                    other.ray_tracing_pipeline_shader_group_handle_capture_replay_mixed;
+                f.ray_tracing_pipeline_trace_rays_indirect |=
+                    other.ray_tracing_pipeline_trace_rays_indirect;
+                f.ray_traversal_primitive_culling |= other.ray_traversal_primitive_culling;
+            }
+            (
+                Self::PhysicalDeviceMeshShaderEXT(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceMeshShaderEXT(other),
+            ) => {
+                f.task_shader |= other.task_shader;
+                f.mesh_shader |= other.mesh_shader;
+                f.multiview_mesh_shader |= other.multiview_mesh_shader;
+                f.primitive_fragment_shading_rate_mesh_shader |=
+                    other.primitive_fragment_shading_rate_mesh_shader;
+                f.mesh_shader_queries |= other.mesh_shader_queries;
+            }
+            (
+                Self::PhysicalDeviceDynamicRendering(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRendering(other),
+            ) => {
+                f.dynamic_rendering |= other.dynamic_rendering;
+            }
+            (
+                Self::PhysicalDeviceDynamicRenderingLocalRead(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRenderingLocalRead(other),
+            ) => {
+                f.dynamic_rendering_local_read |= other.dynamic_rendering_local_read;
+            }
+            (
+                Self::PhysicalDeviceFragmentShadingRateKHR(f),
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceFragmentShadingRateKHR(other),
+            ) => {
+                f.pipeline_fragment_shading_rate |= other.pipeline_fragment_shading_rate;
+                f.primitive_fragment_shading_rate |= other.primitive_fragment_shading_rate;
+                f.attachment_fragment_shading_rate |= other.attachment_fragment_shading_rate;
+            }
             _ => unsafe { unreachable_unchecked() },
         }
     }
@@ -780,6 +1743,31 @@ impl<'a> VulkanPhysicalDeviceFeature2 {
             Self::PhysicalDeviceVulkan11(f) => f.s_type,
             Self::PhysicalDeviceVulkan12(f) => f.s_type,
             Self::PhysicalDeviceVulkan13(f) => f.s_type,
+            Self::PhysicalDeviceCooperativeMatrixKHR(f) => f.s_type,
+            Self::PhysicalDeviceCooperativeMatrixNV(f) => f.s_type,
+            Self::PhysicalDeviceAccelerationStructureKHR(f) => f.s_type,
+            Self::PhysicalDeviceRayTracingPipelineKHR(f) => f.s_type,
+            Self::PhysicalDeviceMeshShaderEXT(f) => f.s_type,
+            Self::PhysicalDeviceDynamicRendering(f) => f.s_type,
+            Self::PhysicalDeviceDynamicRenderingLocalRead(f) => f.s_type,
+            Self::PhysicalDeviceFragmentShadingRateKHR(f) => f.s_type,
+        }
+    }
+
+    /// Drop the pointer to the next struct, which dangles once the chain it was in is gone.
+    fn clear_next(&mut self) {
+        match self {
+            Self::PhysicalDeviceVulkan11(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceVulkan12(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceVulkan13(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceCooperativeMatrixKHR(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceCooperativeMatrixNV(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceAccelerationStructureKHR(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceRayTracingPipelineKHR(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceMeshShaderEXT(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceDynamicRendering(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceDynamicRenderingLocalRead(f) => f.next = std::ptr::null_mut(),
+            Self::PhysicalDeviceFragmentShadingRateKHR(f) => f.next = std::ptr::null_mut(),
         }
     }
 }
@@ -801,6 +1789,54 @@ impl From<vk::PhysicalDeviceVulkan13Features> for VulkanPhysicalDeviceFeature2 {
         Self::PhysicalDeviceVulkan13(value)
     }
 }
+
+impl From<vk::PhysicalDeviceCooperativeMatrixFeaturesKHR> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceCooperativeMatrixFeaturesKHR) -> Self {
+        Self::PhysicalDeviceCooperativeMatrixKHR(value)
+    }
+}
+
+impl From<vk::PhysicalDeviceCooperativeMatrixFeaturesNV> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceCooperativeMatrixFeaturesNV) -> Self {
+        Self::PhysicalDeviceCooperativeMatrixNV(value)
+    }
+}
+
+impl From<vk::PhysicalDeviceAccelerationStructureFeaturesKHR> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceAccelerationStructureFeaturesKHR) -> Self {
+        Self::PhysicalDeviceAccelerationStructureKHR(value)
+    }
+}
+
+impl From<vk::PhysicalDeviceRayTracingPipelineFeaturesKHR> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceRayTracingPipelineFeaturesKHR) -> Self {
+        Self::PhysicalDeviceRayTracingPipelineKHR(value)
+    }
+}
+
+impl From<vk::PhysicalDeviceMeshShaderFeaturesEXT> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceMeshShaderFeaturesEXT) -> Self {
+        Self::PhysicalDeviceMeshShaderEXT(value)
+    }
+}
+
+impl From<vk::PhysicalDeviceDynamicRenderingFeatures> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceDynamicRenderingFeatures) -> Self {
+        Self::PhysicalDeviceDynamicRendering(value)
+    }
+}
+
+impl From<vk::PhysicalDeviceDynamicRenderingLocalReadFeatures> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceDynamicRenderingLocalReadFeatures) -> Self {
+        Self::PhysicalDeviceDynamicRenderingLocalRead(value)
+    }
+}
+
+impl From<vk::PhysicalDeviceFragmentShadingRateFeaturesKHR> for VulkanPhysicalDeviceFeature2 {
+    fn from(value: vk::PhysicalDeviceFragmentShadingRateFeaturesKHR) -> Self {
+        Self::PhysicalDeviceFragmentShadingRateKHR(value)
+    }
+}
 //endregion vulkanfeatures
 
 #[derive(Debug, Clone, Default)]
@@ -835,55 +1871,131 @@ impl GenericFeatureChain {
     }
 
     fn match_all(&self, features_requested: &GenericFeatureChain) -> bool {
-        if features_requested.nodes.len() != self.nodes.len() {
-            return false;
-        }
+        features_requested
+            .nodes
+            .iter()
+            .all(|requested_node| self.matches(requested_node))
+    }
 
-        let features_requested = features_requested.nodes.as_slice();
-        let features = self.nodes.as_slice();
+    /// Fill every node with what `physical_device` supports.
+    fn query(&mut self, instance: &Instance, physical_device: vk::PhysicalDevice) {
+        let mut local_features = vk::PhysicalDeviceFeatures2::builder();
 
-        for (requested_node, node) in features_requested.iter().zip(features) {
-            if !match_features(requested_node, node) {
-                return false;
-            }
+        for node in self.nodes.iter_mut() {
+            local_features = match node {
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan11(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan12(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan13(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixKHR(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixNV(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceAccelerationStructureKHR(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceRayTracingPipelineKHR(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceMeshShaderEXT(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRendering(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRenderingLocalRead(features) => {
+                    local_features.push_next(features)
+                }
+                VulkanPhysicalDeviceFeature2::PhysicalDeviceFragmentShadingRateKHR(features) => {
+                    local_features.push_next(features)
+                }
+            };
         }
 
-        true
+        unsafe {
+            instance
+                .instance
+                .get_physical_device_features2(physical_device, &mut local_features)
+        };
+    }
+
+    /// Whether the node with the same structure type supports everything `requested` asks for.
+    fn matches(&self, requested: &VulkanPhysicalDeviceFeature2) -> bool {
+        self.nodes
+            .iter()
+            .find(|node| node.s_type() == requested.s_type())
+            .is_some_and(|node| match_features(requested, node))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct SelectionCriteria {
     name: String,
+    name_match: NameMatch,
     preferred_device_type: PreferredDeviceType,
     allow_any_type: bool,
+    device_type_order: Vec<PreferredDeviceType>,
+    allow_software_rasterizer: bool,
     require_present: bool,
     require_dedicated_transfer_queue: bool,
     require_dedicated_compute_queue: bool,
     require_separate_transfer_queue: bool,
     require_separate_compute_queue: bool,
+    require_sparse_binding_queue: bool,
     required_mem_size: vk::DeviceSize,
     required_extensions: BTreeSet<vk::ExtensionName>,
     required_version: Version,
     required_features: vk::PhysicalDeviceFeatures,
     required_formats: Vec<vk::Format>,
-    requested_features_chain: RefCell<GenericFeatureChain>,
+    requested_features_chain: GenericFeatureChain,
+    desired_features: Vec<vk::PhysicalDeviceFeatures>,
+    desired_extension_features: Vec<VulkanPhysicalDeviceFeature2>,
+    requested_properties: Vec<PropertiesRequest>,
     defer_surface_initialization: bool,
     use_first_gpu_unconditionally: bool,
     enable_portability_subset: bool,
+    enable_metal_objects: bool,
+    preferred_device_id: Option<PhysicalDeviceId>,
+    required_depth_stencil_resolve: Option<(vk::ResolveModeFlags, vk::ResolveModeFlags)>,
+    required_subgroup_operations: vk::SubgroupFeatureFlags,
+    required_shading_rate_texel_size: Option<vk::Extent2D>,
+    required_bindless_descriptors: Option<u32>,
+    required_max_push_constants_size: u32,
+    required_max_bound_descriptor_sets: u32,
+    required_max_compute_work_group_invocations: u32,
+    required_limits: Vec<LimitRequirement>,
+    require_dynamic_rendering: bool,
+    require_dynamic_rendering_local_read: bool,
+    required_driver_ids: Vec<vk::DriverId>,
+    preferred_driver_ids: Vec<vk::DriverId>,
+    power_preference: PowerPreference,
+    /// `name` compiled by [`PhysicalDeviceSelector::name_matches_regex`].
+    #[cfg(feature = "regex")]
+    name_regex: Option<regex::Regex>,
 }
 
 impl Default for SelectionCriteria {
     fn default() -> Self {
         Self {
             name: String::new(),
+            name_match: NameMatch::Exact,
             preferred_device_type: PreferredDeviceType::Discrete,
             allow_any_type: true,
+            device_type_order: vec![],
+            allow_software_rasterizer: false,
             require_present: true,
             require_dedicated_transfer_queue: false,
             require_dedicated_compute_queue: false,
             require_separate_transfer_queue: false,
             require_separate_compute_queue: false,
+            require_sparse_binding_queue: false,
             required_mem_size: 0,
             required_extensions: BTreeSet::new(),
             required_version: Version::V1_0_0,
@@ -891,69 +2003,723 @@ impl Default for SelectionCriteria {
             defer_surface_initialization: false,
             use_first_gpu_unconditionally: false,
             enable_portability_subset: true,
-            requested_features_chain: RefCell::new(GenericFeatureChain::new()),
+            enable_metal_objects: false,
+            requested_features_chain: GenericFeatureChain::new(),
+            desired_features: vec![],
+            desired_extension_features: vec![],
+            requested_properties: vec![],
             required_formats: vec![],
+            preferred_device_id: None,
+            required_depth_stencil_resolve: None,
+            required_subgroup_operations: vk::SubgroupFeatureFlags::empty(),
+            required_shading_rate_texel_size: None,
+            required_bindless_descriptors: None,
+            required_max_push_constants_size: 0,
+            required_max_bound_descriptor_sets: 0,
+            required_max_compute_work_group_invocations: 0,
+            required_limits: vec![],
+            require_dynamic_rendering: false,
+            require_dynamic_rendering_local_read: false,
+            required_driver_ids: vec![],
+            preferred_driver_ids: vec![],
+            power_preference: PowerPreference::Default,
+            #[cfg(feature = "regex")]
+            name_regex: None,
         }
     }
 }
 
-pub struct PhysicalDeviceSelector {
-    instance: Arc<Instance>,
-    surface: Option<vk::SurfaceKHR>,
-    selection_criteria: SelectionCriteria,
+/// Plain-data snapshot of the [`PhysicalDeviceSelector`] criteria.
+///
+/// Captures device selection settings independently of the fluent API. Apply it with
+/// [`PhysicalDeviceSelector::with_config`].
+///
+/// With the `serde` feature it can be loaded from a config file; missing fields keep their
+/// defaults and `required_features` is the list of enabled feature names.
+/// `required_extension_features`, `desired_features`, `desired_extension_features`,
+/// `requested_properties` and `required_limits` are not serialized.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct DeviceConfig {
+    pub name: String,
+    pub name_match: NameMatch,
+    pub preferred_device_type: PreferredDeviceType,
+    pub allow_any_type: bool,
+    pub device_type_order: Vec<PreferredDeviceType>,
+    pub allow_software_rasterizer: bool,
+    pub require_present: bool,
+    pub require_dedicated_transfer_queue: bool,
+    pub require_dedicated_compute_queue: bool,
+    pub require_separate_transfer_queue: bool,
+    pub require_separate_compute_queue: bool,
+    pub require_sparse_binding_queue: bool,
+    pub required_mem_size: vk::DeviceSize,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::config_serde::extension_names")
+    )]
+    pub required_extensions: BTreeSet<vk::ExtensionName>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::version"))]
+    pub required_version: Version,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::features"))]
+    pub required_features: vk::PhysicalDeviceFeatures,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub required_extension_features: Vec<VulkanPhysicalDeviceFeature2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub desired_features: Vec<vk::PhysicalDeviceFeatures>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub desired_extension_features: Vec<VulkanPhysicalDeviceFeature2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub requested_properties: Vec<PropertiesRequest>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::enums"))]
+    pub required_formats: Vec<vk::Format>,
+    pub defer_surface_initialization: bool,
+    pub use_first_gpu_unconditionally: bool,
+    pub enable_portability_subset: bool,
+    pub enable_metal_objects: bool,
+    pub preferred_device_id: Option<PhysicalDeviceId>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::resolve_modes"))]
+    pub required_depth_stencil_resolve: Option<(vk::ResolveModeFlags, vk::ResolveModeFlags)>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::flags"))]
+    pub required_subgroup_operations: vk::SubgroupFeatureFlags,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::extent"))]
+    pub required_shading_rate_texel_size: Option<vk::Extent2D>,
+    pub required_bindless_descriptors: Option<u32>,
+    pub required_max_push_constants_size: u32,
+    pub required_max_bound_descriptor_sets: u32,
+    pub required_max_compute_work_group_invocations: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub required_limits: Vec<LimitRequirement>,
+    pub require_dynamic_rendering: bool,
+    pub require_dynamic_rendering_local_read: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::enums"))]
+    pub required_driver_ids: Vec<vk::DriverId>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::enums"))]
+    pub preferred_driver_ids: Vec<vk::DriverId>,
+    pub power_preference: PowerPreference,
 }
 
-impl PhysicalDeviceSelector {
-    /// Create a new `PhysicalDeviceSelector` for the provided `Instance`.
-    ///
-    /// The selector can be configured with builder-style methods before calling `select`.
-    pub fn new(instance: Arc<Instance>) -> PhysicalDeviceSelector {
-        let enable_portability_subset = cfg!(feature = "portability");
-        let require_present = instance.surface.is_some();
-        let required_version = instance.api_version;
-        Self {
-            surface: instance.surface,
-            instance,
-            selection_criteria: SelectionCriteria {
-                require_present,
-                required_version,
+impl Default for DeviceConfig {
+    fn default() -> Self {
+        SelectionCriteria::default().to_config()
+    }
+}
+
+impl SelectionCriteria {
+    fn to_config(&self) -> DeviceConfig {
+        DeviceConfig {
+            name: self.name.clone(),
+            name_match: self.name_match,
+            preferred_device_type: self.preferred_device_type,
+            allow_any_type: self.allow_any_type,
+            device_type_order: self.device_type_order.clone(),
+            allow_software_rasterizer: self.allow_software_rasterizer,
+            require_present: self.require_present,
+            require_dedicated_transfer_queue: self.require_dedicated_transfer_queue,
+            require_dedicated_compute_queue: self.require_dedicated_compute_queue,
+            require_separate_transfer_queue: self.require_separate_transfer_queue,
+            require_separate_compute_queue: self.require_separate_compute_queue,
+            require_sparse_binding_queue: self.require_sparse_binding_queue,
+            required_mem_size: self.required_mem_size,
+            required_extensions: self.required_extensions.clone(),
+            required_version: self.required_version,
+            required_features: self.required_features,
+            required_extension_features: self.requested_features_chain.nodes.clone(),
+            desired_features: self.desired_features.clone(),
+            desired_extension_features: self.desired_extension_features.clone(),
+            requested_properties: self.requested_properties.clone(),
+            required_formats: self.required_formats.clone(),
+            defer_surface_initialization: self.defer_surface_initialization,
+            use_first_gpu_unconditionally: self.use_first_gpu_unconditionally,
+            enable_portability_subset: self.enable_portability_subset,
+            enable_metal_objects: self.enable_metal_objects,
+            preferred_device_id: self.preferred_device_id,
+            required_depth_stencil_resolve: self.required_depth_stencil_resolve,
+            required_subgroup_operations: self.required_subgroup_operations,
+            required_shading_rate_texel_size: self.required_shading_rate_texel_size,
+            required_bindless_descriptors: self.required_bindless_descriptors,
+            required_max_push_constants_size: self.required_max_push_constants_size,
+            required_max_bound_descriptor_sets: self.required_max_bound_descriptor_sets,
+            required_max_compute_work_group_invocations: self
+                .required_max_compute_work_group_invocations,
+            required_limits: self.required_limits.clone(),
+            require_dynamic_rendering: self.require_dynamic_rendering,
+            require_dynamic_rendering_local_read: self.require_dynamic_rendering_local_read,
+            required_driver_ids: self.required_driver_ids.clone(),
+            preferred_driver_ids: self.preferred_driver_ids.clone(),
+            power_preference: self.power_preference,
+        }
+    }
+}
+
+impl SelectionCriteria {
+    /// Position of the device type in `device_type_order`, lower is better. `None` when an
+    /// order is set and the type isn't part of it.
+    fn device_type_rank(&self, device_type: vk::PhysicalDeviceType) -> Option<usize> {
+        if self.device_type_order.is_empty() {
+            return Some(0);
+        }
+
+        self.device_type_order.iter().position(|preferred| {
+            vk::PhysicalDeviceType::from_raw(*preferred as u8 as i32) == device_type
+        })
+    }
+
+    /// Whether CPU devices were asked for explicitly, not only accepted as a fallback.
+    fn cpu_type_requested(&self) -> bool {
+        if self.device_type_order.is_empty() {
+            self.preferred_device_type == PreferredDeviceType::Cpu
+        } else {
+            self.device_type_order.contains(&PreferredDeviceType::Cpu)
+        }
+    }
+
+    /// Sort key of a suitable device, lower is better. Ties between equally suitable devices of
+    /// the same type rank go to the device the power preference scores higher, then to the
+    /// device with more device local memory.
+    fn rank(
+        &self,
+        device: &PhysicalDevice,
+    ) -> (
+        Suitable,
+        bool,
+        usize,
+        std::cmp::Reverse<u32>,
+        std::cmp::Reverse<u64>,
+    ) {
+        let device_type = device.properties.device_type;
+        let software_fallback =
+            device_type == vk::PhysicalDeviceType::CPU && !self.cpu_type_requested();
+        let type_rank = if self.device_type_order.is_empty() {
+            let preferred =
+                vk::PhysicalDeviceType::from_raw(self.preferred_device_type as u8 as i32);
+            (device_type != preferred) as usize
+        } else {
+            self.device_type_rank(device_type).unwrap_or(usize::MAX)
+        };
+
+        (
+            device.suitable,
+            software_fallback,
+            type_rank,
+            std::cmp::Reverse(self.power_preference.score(&device.properties)),
+            std::cmp::Reverse(device.device_local_memory_size()),
+        )
+    }
+
+    /// Sort suitable devices best first, then move the device from `preferred_device_id` to the
+    /// front so the others stay in ranked order as fallbacks.
+    fn sort_devices(&self, devices: &mut Vec<PhysicalDevice>) {
+        devices.sort_by_key(|device| self.rank(device));
+
+        if let Some(preferred) = &self.preferred_device_id
+            && let Some(index) = devices
+                .iter()
+                .position(|d| d.device_id.is_some_and(|id| id.matches(preferred)))
+        {
+            let preferred = devices.remove(index);
+            devices.insert(0, preferred);
+        }
+    }
+
+    /// Extensions and feature structs for requirements that were promoted to core, which depend
+    /// on the API version of the device.
+    fn promoted_requirements(
+        &self,
+        api_version: u32,
+    ) -> (Vec<vk::ExtensionName>, Vec<VulkanPhysicalDeviceFeature2>) {
+        let mut extensions = vec![];
+        let mut features = vec![];
+
+        if self.require_dynamic_rendering {
+            if api_version >= u32::from(Version::V1_3_0) {
+                features.push(
+                    vk::PhysicalDeviceVulkan13Features::builder()
+                        .dynamic_rendering(true)
+                        .build()
+                        .into(),
+                );
+            } else {
+                extensions.push(vk::KHR_DYNAMIC_RENDERING_EXTENSION.name);
+                features.push(
+                    vk::PhysicalDeviceDynamicRenderingFeatures::builder()
+                        .dynamic_rendering(true)
+                        .build()
+                        .into(),
+                );
+            }
+        }
+
+        if self.require_dynamic_rendering_local_read {
+            extensions.push(vk::KHR_DYNAMIC_RENDERING_LOCAL_READ_EXTENSION.name);
+            features.push(
+                vk::PhysicalDeviceDynamicRenderingLocalReadFeatures::builder()
+                    .dynamic_rendering_local_read(true)
+                    .build()
+                    .into(),
+            );
+        }
+
+        (extensions, features)
+    }
+}
+
+impl From<DeviceConfig> for SelectionCriteria {
+    fn from(config: DeviceConfig) -> Self {
+        let mut requested_features_chain = GenericFeatureChain::new();
+        for feature in config.required_extension_features {
+            requested_features_chain.add(feature);
+        }
+
+        Self {
+            name: config.name,
+            name_match: config.name_match,
+            preferred_device_type: config.preferred_device_type,
+            allow_any_type: config.allow_any_type,
+            device_type_order: config.device_type_order,
+            allow_software_rasterizer: config.allow_software_rasterizer,
+            require_present: config.require_present,
+            require_dedicated_transfer_queue: config.require_dedicated_transfer_queue,
+            require_dedicated_compute_queue: config.require_dedicated_compute_queue,
+            require_separate_transfer_queue: config.require_separate_transfer_queue,
+            require_separate_compute_queue: config.require_separate_compute_queue,
+            require_sparse_binding_queue: config.require_sparse_binding_queue,
+            required_mem_size: config.required_mem_size,
+            required_extensions: config.required_extensions,
+            required_version: config.required_version,
+            required_features: config.required_features,
+            required_formats: config.required_formats,
+            requested_features_chain,
+            desired_features: config.desired_features,
+            desired_extension_features: config.desired_extension_features,
+            requested_properties: config.requested_properties,
+            defer_surface_initialization: config.defer_surface_initialization,
+            use_first_gpu_unconditionally: config.use_first_gpu_unconditionally,
+            enable_portability_subset: config.enable_portability_subset,
+            enable_metal_objects: config.enable_metal_objects,
+            preferred_device_id: config.preferred_device_id,
+            required_depth_stencil_resolve: config.required_depth_stencil_resolve,
+            required_subgroup_operations: config.required_subgroup_operations,
+            required_shading_rate_texel_size: config.required_shading_rate_texel_size,
+            required_bindless_descriptors: config.required_bindless_descriptors,
+            required_max_push_constants_size: config.required_max_push_constants_size,
+            required_max_bound_descriptor_sets: config.required_max_bound_descriptor_sets,
+            required_max_compute_work_group_invocations: config
+                .required_max_compute_work_group_invocations,
+            required_limits: config.required_limits,
+            require_dynamic_rendering: config.require_dynamic_rendering,
+            require_dynamic_rendering_local_read: config.require_dynamic_rendering_local_read,
+            required_driver_ids: config.required_driver_ids,
+            preferred_driver_ids: config.preferred_driver_ids,
+            power_preference: config.power_preference,
+            #[cfg(feature = "regex")]
+            name_regex: None,
+        }
+    }
+}
+
+/// Selects a physical device matching the configured criteria.
+///
+/// The selector is `Send + Sync`, so selection can run on a background thread during
+/// asynchronous initialization.
+#[derive(Debug, Clone)]
+pub struct PhysicalDeviceSelector {
+    instance: Arc<Instance>,
+    surface: Option<vk::SurfaceKHR>,
+    selection_criteria: SelectionCriteria,
+}
+
+// Keep the selector and device builder movable to other threads, see `PhysicalDeviceSelector`.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<PhysicalDeviceSelector>();
+    assert_send_sync::<DeviceBuilder>();
+};
+
+impl PhysicalDeviceSelector {
+    /// Create a new `PhysicalDeviceSelector` for the provided `Instance`.
+    ///
+    /// The selector can be configured with builder-style methods before calling `select`.
+    pub fn new(instance: Arc<Instance>) -> PhysicalDeviceSelector {
+        let enable_portability_subset = instance.portability_enumeration_enabled;
+        let enable_metal_objects = instance.metal_objects_requested;
+        let require_present = instance.surface.is_some();
+        let required_version = instance.api_version;
+        Self {
+            surface: instance.surface,
+            instance,
+            selection_criteria: SelectionCriteria {
+                require_present,
+                required_version,
                 enable_portability_subset,
+                enable_metal_objects,
                 ..Default::default()
             },
         }
     }
 
+    /// Preset for the vkguide.dev setup: Vulkan 1.3 with dynamic rendering and synchronization2,
+    /// and Vulkan 1.2 buffer device address and descriptor indexing.
+    ///
+    /// The instance needs API version 1.3 as well, see
+    /// [`InstanceBuilder::require_api_version`](crate::InstanceBuilder::require_api_version).
+    pub fn vk_guide_1_3(instance: Arc<Instance>) -> PhysicalDeviceSelector {
+        let mut selector = Self::new(instance);
+        let criteria = &mut selector.selection_criteria;
+        criteria.required_version = criteria.required_version.max(Version::V1_3_0);
+        selector
+            .add_required_extension_feature(
+                vk::PhysicalDeviceVulkan12Features::builder()
+                    .buffer_device_address(true)
+                    .descriptor_indexing(true)
+                    .build(),
+            )
+            .add_required_extension_feature(
+                vk::PhysicalDeviceVulkan13Features::builder()
+                    .dynamic_rendering(true)
+                    .synchronization2(true)
+                    .build(),
+            )
+    }
+
+    /// Preset for hardware ray tracing: [`PhysicalDeviceSelector::require_ray_tracing`] plus
+    /// Vulkan 1.2 descriptor indexing.
+    pub fn raytracing(instance: Arc<Instance>) -> PhysicalDeviceSelector {
+        Self::new(instance)
+            .require_ray_tracing()
+            .add_required_extension_feature(
+                vk::PhysicalDeviceVulkan12Features::builder()
+                    .descriptor_indexing(true)
+                    .build(),
+            )
+    }
+
+    /// Preset for headless compute: presentation support is not required, even when the
+    /// instance has a surface, and the device is created without `VK_KHR_swapchain`.
+    ///
+    /// Combined with an instance built without a window, no WSI extension is enabled anywhere:
+    ///
+    /// ``` no_run
+    /// # use std::sync::Arc;
+    /// # use vulkanalia_bootstrap::{DeviceBuilder, InstanceBuilder, PhysicalDeviceSelector, QueueType};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let instance = InstanceBuilder::new(None).headless(true).build()?;
+    /// let physical_device = PhysicalDeviceSelector::compute_only(instance.clone()).select()?;
+    /// let device = Arc::new(DeviceBuilder::new(physical_device, instance.clone()).build()?);
+    /// let (_compute_family, _compute_queue) = device.get_queue(QueueType::Compute)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compute_only(instance: Arc<Instance>) -> PhysicalDeviceSelector {
+        Self::new(instance).require_present(false)
+    }
+
+    /// Capture the current selection criteria as plain data.
+    pub fn to_config(&self) -> DeviceConfig {
+        self.selection_criteria.to_config()
+    }
+
+    /// Replace the selection criteria of this selector with `config`.
+    pub fn with_config(mut self, config: DeviceConfig) -> Self {
+        self.selection_criteria = config.into();
+        self
+    }
+
     /// Specify a surface to use when evaluating device presentation support.
     pub fn surface(mut self, surface: vk::SurfaceKHR) -> Self {
         self.surface.replace(surface);
         self
     }
 
+    /// Require `VK_KHR_cooperative_matrix` and enable its `cooperative_matrix` feature.
+    /// Query the supported tile shapes with [`PhysicalDevice::cooperative_matrix_properties`].
+    pub fn require_cooperative_matrix(mut self) -> Self {
+        self.selection_criteria
+            .required_extensions
+            .insert(vk::KHR_COOPERATIVE_MATRIX_EXTENSION.name);
+        self.add_required_extension_feature(
+            vk::PhysicalDeviceCooperativeMatrixFeaturesKHR::builder()
+                .cooperative_matrix(true)
+                .build(),
+        )
+    }
+
+    /// Require `VK_KHR_acceleration_structure`, `VK_KHR_ray_tracing_pipeline` and
+    /// `VK_KHR_deferred_host_operations`, and enable the `acceleration_structure` and
+    /// `ray_tracing_pipeline` features together with Vulkan 1.2 `buffer_device_address`, which
+    /// acceleration structures need. Devices missing any part of the set are not selected.
+    pub fn require_ray_tracing(mut self) -> Self {
+        let criteria = &mut self.selection_criteria;
+        criteria.required_version = criteria.required_version.max(Version::V1_2_0);
+        criteria.required_extensions.extend([
+            vk::KHR_ACCELERATION_STRUCTURE_EXTENSION.name,
+            vk::KHR_RAY_TRACING_PIPELINE_EXTENSION.name,
+            vk::KHR_DEFERRED_HOST_OPERATIONS_EXTENSION.name,
+        ]);
+        self.add_required_extension_feature(
+            vk::PhysicalDeviceVulkan12Features::builder()
+                .buffer_device_address(true)
+                .build(),
+        )
+        .add_required_extension_feature(
+            vk::PhysicalDeviceAccelerationStructureFeaturesKHR::builder()
+                .acceleration_structure(true)
+                .build(),
+        )
+        .add_required_extension_feature(
+            vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::builder()
+                .ray_tracing_pipeline(true)
+                .build(),
+        )
+    }
+
+    /// Require bindless descriptors: Vulkan 1.2 with runtime descriptor arrays, partially bound
+    /// and variable count bindings, update-after-bind for sampled images, storage images and
+    /// storage buffers, and non-uniform indexing of those arrays. Devices must also allow
+    /// `descriptor_count` of each of them in one update-after-bind set, see
+    /// [`DescriptorIndexingLimits::supports_bindless`].
+    pub fn require_bindless(mut self, descriptor_count: u32) -> Self {
+        let criteria = &mut self.selection_criteria;
+        criteria.required_version = criteria.required_version.max(Version::V1_2_0);
+        criteria.required_bindless_descriptors = Some(
+            criteria
+                .required_bindless_descriptors
+                .map_or(descriptor_count, |count| count.max(descriptor_count)),
+        );
+        self.add_required_extension_feature(
+            vk::PhysicalDeviceVulkan12Features::builder()
+                .descriptor_indexing(true)
+                .runtime_descriptor_array(true)
+                .descriptor_binding_partially_bound(true)
+                .descriptor_binding_variable_descriptor_count(true)
+                .descriptor_binding_update_unused_while_pending(true)
+                .descriptor_binding_sampled_image_update_after_bind(true)
+                .descriptor_binding_storage_image_update_after_bind(true)
+                .descriptor_binding_storage_buffer_update_after_bind(true)
+                .shader_sampled_image_array_non_uniform_indexing(true)
+                .shader_storage_image_array_non_uniform_indexing(true)
+                .shader_storage_buffer_array_non_uniform_indexing(true)
+                .build(),
+        )
+    }
+
+    /// Require dynamic rendering: the Vulkan 1.3 `dynamic_rendering` feature, or
+    /// `VK_KHR_dynamic_rendering` and its feature struct on Vulkan 1.2 devices. With
+    /// `local_read`, `VK_KHR_dynamic_rendering_local_read` is required as well.
+    pub fn require_dynamic_rendering(mut self, local_read: bool) -> Self {
+        let criteria = &mut self.selection_criteria;
+        criteria.required_version = criteria.required_version.max(Version::V1_2_0);
+        criteria.require_dynamic_rendering = true;
+        criteria.require_dynamic_rendering_local_read |= local_read;
+        self
+    }
+
+    /// Require `VK_EXT_mesh_shader` and enable its `mesh_shader` and `task_shader` features.
+    /// The extension depends on `VK_KHR_spirv_1_4`, so devices also need Vulkan 1.2, where that
+    /// is core.
+    pub fn require_mesh_shading(mut self) -> Self {
+        let criteria = &mut self.selection_criteria;
+        criteria.required_version = criteria.required_version.max(Version::V1_2_0);
+        criteria
+            .required_extensions
+            .insert(vk::EXT_MESH_SHADER_EXTENSION.name);
+        self.add_required_extension_feature(
+            vk::PhysicalDeviceMeshShaderFeaturesEXT::builder()
+                .mesh_shader(true)
+                .task_shader(true)
+                .build(),
+        )
+    }
+
+    /// Require `VK_KHR_fragment_shading_rate` (variable rate shading) and enable its
+    /// `pipeline_fragment_shading_rate` feature. With `attachment_texel_size`, the
+    /// `attachment_fragment_shading_rate` feature is enabled too and devices must support
+    /// shading rate attachments with texels of that size, see
+    /// [`FragmentShadingRateSupport::supports_attachment_texel_size`]. The extension depends on
+    /// `VK_KHR_create_renderpass2`, so devices also need Vulkan 1.2, where that is core.
+    pub fn require_fragment_shading_rate(
+        mut self,
+        attachment_texel_size: Option<vk::Extent2D>,
+    ) -> Self {
+        let criteria = &mut self.selection_criteria;
+        criteria.required_version = criteria.required_version.max(Version::V1_2_0);
+        criteria
+            .required_extensions
+            .insert(vk::KHR_FRAGMENT_SHADING_RATE_EXTENSION.name);
+        if attachment_texel_size.is_some() {
+            criteria.required_shading_rate_texel_size = attachment_texel_size;
+        }
+        self.add_required_extension_feature(
+            vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::builder()
+                .pipeline_fragment_shading_rate(true)
+                .attachment_fragment_shading_rate(attachment_texel_size.is_some())
+                .build(),
+        )
+    }
+
+    /// Like [`PhysicalDeviceSelector::require_cooperative_matrix`], using the older
+    /// `VK_NV_cooperative_matrix` extension.
+    pub fn require_cooperative_matrix_nv(mut self) -> Self {
+        self.selection_criteria
+            .required_extensions
+            .insert(vk::NV_COOPERATIVE_MATRIX_EXTENSION.name);
+        self.add_required_extension_feature(
+            vk::PhysicalDeviceCooperativeMatrixFeaturesNV::builder()
+                .cooperative_matrix(true)
+                .build(),
+        )
+    }
+
     /// Add an additional device feature (vulkan feature2 struct) that must be supported by
     /// the physical device in order to be selected.
     pub fn add_required_extension_feature<T: Into<VulkanPhysicalDeviceFeature2>>(
-        self,
+        mut self,
         feature: T,
     ) -> Self {
         self.selection_criteria
             .requested_features_chain
-            .borrow_mut()
             .add(feature);
         self
     }
 
+    /// Enable the features of `feature` (a vulkan feature2 struct) when the selected device
+    /// supports all of them, without disqualifying devices that don't. Call it once per feature
+    /// to enable them independently. See [`PhysicalDevice::enabled_extension_features`] for what
+    /// got enabled. Features of a device extension also need that extension, see
+    /// [`PhysicalDevice::enable_extension_if_present`].
+    pub fn add_desired_extension_feature<T: Into<VulkanPhysicalDeviceFeature2>>(
+        mut self,
+        feature: T,
+    ) -> Self {
+        self.selection_criteria
+            .desired_extension_features
+            .push(feature.into());
+        self
+    }
+
+    /// Query the properties struct `T` (one that extends `vk::PhysicalDeviceProperties2`, e.g.
+    /// `vk::PhysicalDeviceRayTracingPipelinePropertiesKHR`) for every candidate device, see
+    /// [`PhysicalDevice::extension_properties`].
+    ///
+    /// Structs of an extension are only filled in by devices that support it, the others leave
+    /// them at their defaults.
+    pub fn request_properties<T>(mut self) -> Self
+    where
+        T: vk::ExtendsPhysicalDeviceProperties2
+            + vk::Cast<Target = T>
+            + Default
+            + Send
+            + Sync
+            + 'static,
+    {
+        let requests = &mut self.selection_criteria.requested_properties;
+        if !requests
+            .iter()
+            .any(|request| request.type_id == TypeId::of::<T>())
+        {
+            requests.push(PropertiesRequest {
+                type_id: TypeId::of::<T>(),
+                query: query_properties::<T>,
+            });
+        }
+        self
+    }
+
+    /// Like [`PhysicalDeviceSelector::add_desired_extension_feature`] for core
+    /// `vk::PhysicalDeviceFeatures`, see [`PhysicalDevice::enabled_features`].
+    pub fn add_desired_features(mut self, features: vk::PhysicalDeviceFeatures) -> Self {
+        self.selection_criteria.desired_features.push(features);
+        self
+    }
+
     /// Require the given `vk::PhysicalDeviceFeatures` when selecting a physical device.
     pub fn add_required_features(mut self, features: vk::PhysicalDeviceFeatures) -> Self {
         self.selection_criteria.required_features = features;
         self
     }
 
-    /// Restrict selection to devices whose name matches `name`.
+    /// Restrict selection to devices whose name matches `name`, exactly unless set otherwise
+    /// with [`PhysicalDeviceSelector::name_match`].
     pub fn name(mut self, name: impl Into<String>) -> Self {
         self.selection_criteria.name = name.into();
         self
     }
 
+    /// How the name set with [`PhysicalDeviceSelector::name`] is matched.
+    pub fn name_match(mut self, name_match: NameMatch) -> Self {
+        self.selection_criteria.name_match = name_match;
+        self
+    }
+
+    /// Restrict selection to devices whose name matches the glob `pattern`, e.g.
+    /// `"AMD Radeon RX 7*"`.
+    pub fn name_matches(mut self, pattern: impl Into<String>) -> Self {
+        self.selection_criteria.name = pattern.into();
+        self.selection_criteria.name_match = NameMatch::Glob;
+        self
+    }
+
+    /// Restrict selection to devices whose name matches the regular expression `pattern`.
+    /// Fails with `ConfigError::InvalidNameRegex` when `pattern` is not a valid expression.
+    #[cfg(feature = "regex")]
+    pub fn name_matches_regex(mut self, pattern: impl Into<String>) -> crate::Result<Self> {
+        let pattern = pattern.into();
+        self.selection_criteria.name_regex = Some(compile_name_regex(&pattern)?);
+        self.selection_criteria.name = pattern;
+        self.selection_criteria.name_match = NameMatch::Regex;
+        Ok(self)
+    }
+
+    /// Re-select the device identified by `id` (usually saved from a previous run) when it is
+    /// still present and suitable. Falls back to the regular ranking otherwise.
+    pub fn prefer_previous_device(mut self, id: PhysicalDeviceId) -> Self {
+        self.selection_criteria.preferred_device_id = Some(id);
+        self
+    }
+
+    /// Require that multisampled depth/stencil attachments can be resolved with the given modes
+    /// (see [`DepthStencilResolveSupport::supports`]). Enables `VK_KHR_depth_stencil_resolve` on
+    /// devices older than Vulkan 1.2.
+    pub fn require_depth_stencil_resolve(
+        mut self,
+        depth: vk::ResolveModeFlags,
+        stencil: vk::ResolveModeFlags,
+    ) -> Self {
+        self.selection_criteria.required_depth_stencil_resolve = Some((depth, stencil));
+        self
+    }
+
+    /// Require the given subgroup operations to be supported in compute shaders,
+    /// e.g. `vk::SubgroupFeatureFlags::BALLOT | vk::SubgroupFeatureFlags::SHUFFLE`.
+    pub fn require_subgroup_ops(mut self, operations: vk::SubgroupFeatureFlags) -> Self {
+        self.selection_criteria.required_subgroup_operations |= operations;
+        self
+    }
+
+    /// Prefer the most power efficient suitable device. See [`PowerPreference`].
+    ///
+    /// Also prefers integrated GPUs, call [`PhysicalDeviceSelector::preferred_device_type`]
+    /// afterwards to prefer another type and only rank by the power preference within it.
+    pub fn prefer_power_saving(mut self) -> Self {
+        self.selection_criteria.power_preference = PowerPreference::LowPower;
+        self.selection_criteria.preferred_device_type = PreferredDeviceType::Integrated;
+        self
+    }
+
+    /// Prefer the fastest suitable device. See [`PowerPreference`].
+    ///
+    /// Also prefers discrete GPUs, call [`PhysicalDeviceSelector::preferred_device_type`]
+    /// afterwards to prefer another type and only rank by the power preference within it.
+    pub fn prefer_high_performance(mut self) -> Self {
+        self.selection_criteria.power_preference = PowerPreference::HighPerformance;
+        self.selection_criteria.preferred_device_type = PreferredDeviceType::Discrete;
+        self
+    }
+
     /// Prefer devices of the given `PreferredDeviceType` when ranking candidates.
     pub fn preferred_device_type(mut self, device_type: PreferredDeviceType) -> Self {
         self.selection_criteria.preferred_device_type = device_type;
@@ -966,6 +2732,52 @@ impl PhysicalDeviceSelector {
         self
     }
 
+    /// Rank suitable devices by type in the given order, e.g. `[Discrete, Integrated,
+    /// VirtualGpu]`, and reject devices of types not in the list. Replaces
+    /// [`PhysicalDeviceSelector::preferred_device_type`] and
+    /// [`PhysicalDeviceSelector::allow_any_gpu_device_type`] when set.
+    pub fn device_type_order(
+        mut self,
+        order: impl IntoIterator<Item = PreferredDeviceType>,
+    ) -> Self {
+        self.selection_criteria.device_type_order = order.into_iter().collect();
+        self
+    }
+
+    /// Accept CPU implementations such as lavapipe and SwiftShader as a fallback, ranked below
+    /// every GPU. They are rejected by default unless [`PreferredDeviceType::Cpu`] is the
+    /// preferred device type or part of the device type order. Useful for CI and for
+    /// applications that must run without a GPU.
+    pub fn allow_software_rasterizer(mut self, allow: bool) -> Self {
+        self.selection_criteria.allow_software_rasterizer = allow;
+        self
+    }
+
+    /// Only accept devices running one of the drivers passed to this method, e.g.
+    /// `vk::DriverId::MESA_RADV` to rule out AMDVLK. Devices that can't report their driver
+    /// (Vulkan 1.0/1.1 without `VK_KHR_driver_properties`) are rejected.
+    pub fn require_driver(mut self, driver_id: vk::DriverId) -> Self {
+        self.selection_criteria.required_driver_ids.push(driver_id);
+        self
+    }
+
+    /// Rank devices running one of the drivers passed to this method above the others, e.g.
+    /// `vk::DriverId::NVIDIA_PROPRIETARY` over NVK when both expose the same GPU.
+    pub fn prefer_driver(mut self, driver_id: vk::DriverId) -> Self {
+        self.selection_criteria.preferred_driver_ids.push(driver_id);
+        self
+    }
+
+    /// Require a queue family that can present to the instance surface. Defaults to true when
+    /// the instance has a surface.
+    ///
+    /// Devices selected without the requirement are created without `VK_KHR_swapchain`, e.g. a
+    /// compute device next to a separate device that presents.
+    pub fn require_present(mut self, require: bool) -> Self {
+        self.selection_criteria.require_present = require;
+        self
+    }
+
     /// Require a dedicated transfer-only queue family to be present on the physical device.
     pub fn require_dedicated_transfer_queue(mut self, require: bool) -> Self {
         self.selection_criteria.require_dedicated_transfer_queue = require;
@@ -990,12 +2802,65 @@ impl PhysicalDeviceSelector {
         self
     }
 
+    /// Require a queue family that supports sparse binding, see [`QueueType::SparseBinding`].
+    pub fn require_sparse_binding_queue(mut self, require: bool) -> Self {
+        self.selection_criteria.require_sparse_binding_queue = require;
+        self
+    }
+
+    /// Require the `sparse_binding` feature together with a sparse binding queue.
+    pub fn require_sparse_binding(mut self) -> Self {
+        self.selection_criteria.required_features.sparse_binding = vk::TRUE;
+        self.require_sparse_binding_queue(true)
+    }
+
+    /// Require sparse residency for 2D images (e.g. virtual textures): the `sparse_binding` and
+    /// `sparse_residency_image_2d` features together with a sparse binding queue.
+    pub fn require_sparse_residency_2d(mut self) -> Self {
+        self.selection_criteria
+            .required_features
+            .sparse_residency_image_2d = vk::TRUE;
+        self.require_sparse_binding()
+    }
+
     /// Require the device to have at least `required` bytes of device-local memory.
     pub fn required_device_memory_size(mut self, required: vk::DeviceSize) -> Self {
         self.selection_criteria.required_mem_size = required;
         self
     }
 
+    /// Require `maxPushConstantsSize` to be at least `size` bytes.
+    pub fn required_max_push_constants_size(mut self, size: u32) -> Self {
+        self.selection_criteria.required_max_push_constants_size = size;
+        self
+    }
+
+    /// Require `maxBoundDescriptorSets` to be at least `count`.
+    pub fn required_max_bound_descriptor_sets(mut self, count: u32) -> Self {
+        self.selection_criteria.required_max_bound_descriptor_sets = count;
+        self
+    }
+
+    /// Require `maxComputeWorkGroupInvocations` to be at least `invocations`.
+    pub fn required_max_compute_work_group_invocations(mut self, invocations: u32) -> Self {
+        self.selection_criteria
+            .required_max_compute_work_group_invocations = invocations;
+        self
+    }
+
+    /// Only accept devices whose limits pass `check`, e.g.
+    /// `required_limit(|limits| limits.max_image_dimension_2d >= 16384)`. Can be called several
+    /// times, every check has to pass.
+    pub fn required_limit(
+        mut self,
+        check: impl Fn(&vk::PhysicalDeviceLimits) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.selection_criteria
+            .required_limits
+            .push(LimitRequirement(Arc::new(check)));
+        self
+    }
+
     /// Require support for the provided list of `vk::Format`s on the device's surface.
     pub fn required_formats(mut self, required: impl IntoIterator<Item = vk::Format>) -> Self {
         self.selection_criteria.required_formats = required.into_iter().collect();
@@ -1009,12 +2874,12 @@ impl PhysicalDeviceSelector {
         self
     }
 
-    fn set_is_suitable(&self, device: &mut PhysicalDevice) {
+    fn set_is_suitable(&self, device: &mut PhysicalDevice, name_filter: &NameFilter) {
         let criteria = &self.selection_criteria;
 
         let device_name = device.properties.device_name.to_string_lossy();
 
-        if !criteria.name.is_empty() && Cow::Borrowed(&criteria.name) != device_name {
+        if !name_filter.matches(&device_name) {
             #[cfg(feature = "enable_tracing")]
             {
                 tracing::warn!(
@@ -1094,6 +2959,14 @@ impl PhysicalDeviceSelector {
             return;
         }
 
+        if criteria.require_sparse_binding_queue
+            && get_first_queue_index(&device.queue_families, vk::QueueFlags::SPARSE_BINDING)
+                .is_none()
+        {
+            device.suitable = Suitable::No;
+            return;
+        }
+
         if criteria.require_present
             && present_queue.is_none()
             && !criteria.defer_surface_initialization
@@ -1112,49 +2985,100 @@ impl PhysicalDeviceSelector {
             return;
         }
 
-        if !criteria.defer_surface_initialization && criteria.require_present {
-            if let Some(surface) = self.surface {
-                let formats = unsafe {
-                    self.instance
-                        .instance
-                        .get_physical_device_surface_formats_khr(device.physical_device, surface)
-                };
-                let Ok(formats) = formats else {
-                    device.suitable = Suitable::No;
-                    return;
-                };
-
-                let present_modes = unsafe {
-                    self.instance
-                        .instance
-                        .get_physical_device_surface_present_modes_khr(
-                            device.physical_device,
-                            surface,
-                        )
-                };
-                let Ok(present_modes) = present_modes else {
-                    device.suitable = Suitable::No;
-                    return;
-                };
+        if !criteria.defer_surface_initialization
+            && criteria.require_present
+            && let Some(surface) = self.surface
+        {
+            let formats = unsafe {
+                self.instance
+                    .instance
+                    .get_physical_device_surface_formats_khr(device.physical_device, surface)
+            };
+            let Ok(formats) = formats else {
+                device.suitable = Suitable::No;
+                return;
+            };
 
-                if present_modes.is_empty() || formats.is_empty() {
-                    device.suitable = Suitable::No;
-                    return;
-                }
+            let present_modes = unsafe {
+                self.instance
+                    .instance
+                    .get_physical_device_surface_present_modes_khr(device.physical_device, surface)
+            };
+            let Ok(present_modes) = present_modes else {
+                device.suitable = Suitable::No;
+                return;
             };
+
+            if present_modes.is_empty() || formats.is_empty() {
+                device.suitable = Suitable::No;
+                return;
+            }
         };
 
         let preferred_device_type =
             vk::PhysicalDeviceType::from_raw(criteria.preferred_device_type as u8 as i32);
-        if !criteria.allow_any_type && device.properties.device_type != preferred_device_type {
+        if device.properties.device_type == vk::PhysicalDeviceType::CPU
+            && !criteria.cpu_type_requested()
+        {
+            // Software rasterizers are only a fallback, ranked below every GPU.
+            if !criteria.allow_software_rasterizer {
+                device.suitable = Suitable::No;
+                return;
+            }
+            device.suitable = Suitable::Partial;
+        } else if !criteria.device_type_order.is_empty() {
+            if criteria
+                .device_type_rank(device.properties.device_type)
+                .is_none()
+            {
+                device.suitable = Suitable::No;
+                return;
+            }
+        } else if !criteria.allow_any_type && device.properties.device_type != preferred_device_type
+        {
+            device.suitable = Suitable::Partial;
+        }
+
+        if !criteria.required_driver_ids.is_empty()
+            && !device
+                .driver
+                .as_ref()
+                .is_some_and(|driver| criteria.required_driver_ids.contains(&driver.id))
+        {
+            device.suitable = Suitable::No;
+            return;
+        }
+
+        if !criteria.preferred_driver_ids.is_empty()
+            && !device
+                .driver
+                .as_ref()
+                .is_some_and(|driver| criteria.preferred_driver_ids.contains(&driver.id))
+        {
             device.suitable = Suitable::Partial;
         }
 
+        let (promoted_extensions, _) =
+            criteria.promoted_requirements(device.properties.api_version);
+        if !promoted_extensions
+            .iter()
+            .all(|ext| device.available_extensions.contains(ext))
+        {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!(
+                "Device {} is not suitable. Extensions {:?} not all supported",
+                device_name,
+                promoted_extensions
+            );
+            device.suitable = Suitable::No;
+            return;
+        }
+
         let required_features_supported = supports_features(
             &device.features,
             &criteria.required_features,
             &device.supported_features_chain,
-            &criteria.requested_features_chain.borrow(),
+            &device.requested_features_chain,
         );
 
         if !required_features_supported {
@@ -1162,14 +3086,98 @@ impl PhysicalDeviceSelector {
             return;
         }
 
-        //let supported_formats = &device.format_properties;
+        if let Some((depth, stencil)) = criteria.required_depth_stencil_resolve
+            && !device
+                .depth_stencil_resolve
+                .is_some_and(|support| support.supports(depth, stencil))
+        {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!(
+                "Device {} is not suitable. Depth/stencil resolve {:?}/{:?} not supported",
+                device_name,
+                depth,
+                stencil
+            );
+            device.suitable = Suitable::No;
+            return;
+        }
 
-        for memory_heap in device.memory_properties.memory_heaps {
-            if memory_heap
-                .flags
-                .contains(vk::MemoryHeapFlags::DEVICE_LOCAL)
-                && memory_heap.size < criteria.required_mem_size
-            {
+        if !criteria.required_subgroup_operations.is_empty()
+            && !device.subgroup.is_some_and(|subgroup| {
+                subgroup
+                    .supported_stages
+                    .contains(vk::ShaderStageFlags::COMPUTE)
+                    && subgroup
+                        .supported_operations
+                        .contains(criteria.required_subgroup_operations)
+            })
+        {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!(
+                "Device {} is not suitable. Subgroup operations {:?} not supported in compute",
+                device_name,
+                criteria.required_subgroup_operations
+            );
+            device.suitable = Suitable::No;
+            return;
+        }
+
+        if let Some(size) = criteria.required_shading_rate_texel_size
+            && !device
+                .fragment_shading_rate
+                .is_some_and(|support| support.supports_attachment_texel_size(size))
+        {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!(
+                "Device {} is not suitable. Shading rate attachment texel size {}x{} not supported",
+                device_name,
+                size.width,
+                size.height
+            );
+            device.suitable = Suitable::No;
+            return;
+        }
+
+        if let Some(count) = criteria.required_bindless_descriptors
+            && !device
+                .descriptor_indexing
+                .is_some_and(|limits| limits.supports_bindless(count))
+        {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!(
+                "Device {} is not suitable. {} bindless descriptors per set not supported",
+                device_name,
+                count
+            );
+            device.suitable = Suitable::No;
+            return;
+        }
+
+        let limits = &device.properties.limits;
+        if limits.max_push_constants_size < criteria.required_max_push_constants_size
+            || limits.max_bound_descriptor_sets < criteria.required_max_bound_descriptor_sets
+            || limits.max_compute_work_group_invocations
+                < criteria.required_max_compute_work_group_invocations
+            || !criteria
+                .required_limits
+                .iter()
+                .all(|requirement| (requirement.0)(limits))
+        {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!(
+                "Device {} is not suitable. Required limits not met",
+                device_name
+            );
+            device.suitable = Suitable::No;
+            return;
+        }
+
+        for memory_heap in device.memory_properties.memory_heaps {
+            if memory_heap
+                .flags
+                .contains(vk::MemoryHeapFlags::DEVICE_LOCAL)
+                && memory_heap.size < criteria.required_mem_size
+            {
                 device.suitable = Suitable::No;
                 return;
             }
@@ -1187,6 +3195,7 @@ impl PhysicalDeviceSelector {
             physical_device: vk_phys_device,
             surface: instance.surface,
             defer_surface_initialization: criteria.defer_surface_initialization,
+            require_present: criteria.require_present,
             queue_families: unsafe {
                 instance
                     .instance
@@ -1207,34 +3216,18 @@ impl PhysicalDeviceSelector {
                     .instance
                     .get_physical_device_memory_properties(vk_phys_device)
             },
-            // supported_format_properties: {
-            //     // vulkan has 185 formats in ash
-            //     let range = 0..185;
-            //     range
-            //         .filter_map(|format| {
-            //             let format = vk::Format::from_raw(format);
-            //             let format_properties = unsafe {
-            //                 instance
-            //                     .instance
-            //                     .get_physical_device_format_properties(vk_phys_device, format)
-            //             };
-            //             if !format_properties.optimal_tiling_features.is_empty()
-            //                 || !format_properties.buffer_features.is_empty()
-            //                 || !format_properties.linear_tiling_features.is_empty()
-            //             {
-            //                 Some((format, format_properties))
-            //             } else {
-            //                 None
-            //             }
-            //         })
-            //         .collect()
-            // },
             properties2_ext_enabled: instance.properties2_ext_enabled,
-            requested_features_chain: criteria.requested_features_chain.clone().into_inner(),
+            requested_features_chain: criteria.requested_features_chain.clone(),
             ..Default::default()
         };
 
-        physical_device.name = physical_device.properties.clone().device_name.to_string();
+        physical_device.name = physical_device.properties.device_name.to_string();
+
+        let (_, promoted_features) =
+            criteria.promoted_requirements(physical_device.properties.api_version);
+        for feature in promoted_features {
+            physical_device.requested_features_chain.add(feature);
+        }
 
         let available_extensions = unsafe {
             instance
@@ -1257,51 +3250,221 @@ impl PhysicalDeviceSelector {
 
         physical_device.properties2_ext_enabled = instance.properties2_ext_enabled;
 
-        let requested_features_chain = criteria.requested_features_chain.borrow();
-        let instance_is_11 = instance.instance_version >= Version::V1_1_0;
-        if !requested_features_chain.is_empty()
-            && (instance_is_11 || instance.properties2_ext_enabled)
-        {
-            let mut supported_features = requested_features_chain.clone();
-            let mut local_features = vk::PhysicalDeviceFeatures2::builder();
+        if instance.instance_version >= Version::V1_1_0 || instance.properties2_ext_enabled {
+            let device_is_11 = physical_device.properties.api_version >= u32::from(Version::V1_1_0);
+            let depth_stencil_resolve_available = physical_device.properties.api_version
+                >= u32::from(Version::V1_2_0)
+                || physical_device
+                    .available_extensions
+                    .contains(&vk::KHR_DEPTH_STENCIL_RESOLVE_EXTENSION.name);
+
+            let driver_properties_available = physical_device.properties.api_version
+                >= u32::from(Version::V1_2_0)
+                || physical_device
+                    .available_extensions
+                    .contains(&vk::KHR_DRIVER_PROPERTIES_EXTENSION.name);
+
+            let mut id_properties = vk::PhysicalDeviceIDProperties::default();
+            let mut driver_properties = vk::PhysicalDeviceDriverProperties::default();
+            let mut subgroup_properties = vk::PhysicalDeviceSubgroupProperties::default();
+            let descriptor_indexing_available = physical_device.properties.api_version
+                >= u32::from(Version::V1_2_0)
+                || physical_device
+                    .available_extensions
+                    .contains(&vk::EXT_DESCRIPTOR_INDEXING_EXTENSION.name);
+
+            let fragment_shading_rate_available = physical_device
+                .available_extensions
+                .contains(&vk::KHR_FRAGMENT_SHADING_RATE_EXTENSION.name);
+
+            let mut resolve_properties = vk::PhysicalDeviceDepthStencilResolveProperties::default();
+            let mut shading_rate_properties =
+                vk::PhysicalDeviceFragmentShadingRatePropertiesKHR::default();
+            let mut descriptor_indexing_properties =
+                vk::PhysicalDeviceDescriptorIndexingProperties::default();
+            let mut properties2 =
+                vk::PhysicalDeviceProperties2::builder().push_next(&mut id_properties);
+            if device_is_11 {
+                properties2 = properties2.push_next(&mut subgroup_properties);
+            }
+            if driver_properties_available {
+                properties2 = properties2.push_next(&mut driver_properties);
+            }
+            if depth_stencil_resolve_available {
+                properties2 = properties2.push_next(&mut resolve_properties);
+            }
+            if descriptor_indexing_available {
+                properties2 = properties2.push_next(&mut descriptor_indexing_properties);
+            }
+            if fragment_shading_rate_available {
+                properties2 = properties2.push_next(&mut shading_rate_properties);
+            }
+            unsafe {
+                instance
+                    .instance
+                    .get_physical_device_properties2(vk_phys_device, &mut properties2)
+            };
 
-            for node in supported_features.nodes.iter_mut() {
-                match node {
-                    VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan11(features) => {
-                        local_features.push_next(features)
-                    }
-                    VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan12(features) => {
-                        local_features.push_next(features)
-                    }
-                    VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan13(features) => {
-                        local_features.push_next(features)
-                    }
+            physical_device.device_id = Some(PhysicalDeviceId {
+                uuid: id_properties.device_uuid.0,
+                luid: (id_properties.device_luid_valid == vk::TRUE)
+                    .then_some(id_properties.device_luid.0),
+            });
+
+            if driver_properties_available {
+                physical_device.driver = Some(DriverInfo {
+                    id: driver_properties.driver_id,
+                    name: driver_properties.driver_name.to_string_lossy().into_owned(),
+                    info: driver_properties.driver_info.to_string_lossy().into_owned(),
+                    conformance_version: driver_properties.conformance_version,
+                });
+            }
+
+            if device_is_11 {
+                physical_device.subgroup = Some(SubgroupCapabilities {
+                    size: subgroup_properties.subgroup_size,
+                    supported_stages: subgroup_properties.supported_stages,
+                    supported_operations: subgroup_properties.supported_operations,
+                    quad_operations_in_all_stages: subgroup_properties
+                        .quad_operations_in_all_stages
+                        == vk::TRUE,
+                });
+            }
+
+            if depth_stencil_resolve_available {
+                physical_device.depth_stencil_resolve = Some(DepthStencilResolveSupport {
+                    supported_depth_resolve_modes: resolve_properties.supported_depth_resolve_modes,
+                    supported_stencil_resolve_modes: resolve_properties
+                        .supported_stencil_resolve_modes,
+                    independent_resolve_none: resolve_properties.independent_resolve_none
+                        == vk::TRUE,
+                    independent_resolve: resolve_properties.independent_resolve == vk::TRUE,
+                });
+            }
+
+            if descriptor_indexing_available {
+                let p = &descriptor_indexing_properties;
+                physical_device.descriptor_indexing = Some(DescriptorIndexingLimits {
+                    max_update_after_bind_descriptors_in_all_pools: p
+                        .max_update_after_bind_descriptors_in_all_pools,
+                    max_per_stage_update_after_bind_resources: p
+                        .max_per_stage_update_after_bind_resources,
+                    max_descriptor_set_update_after_bind_samplers: p
+                        .max_descriptor_set_update_after_bind_samplers,
+                    max_descriptor_set_update_after_bind_sampled_images: p
+                        .max_descriptor_set_update_after_bind_sampled_images,
+                    max_descriptor_set_update_after_bind_storage_images: p
+                        .max_descriptor_set_update_after_bind_storage_images,
+                    max_descriptor_set_update_after_bind_storage_buffers: p
+                        .max_descriptor_set_update_after_bind_storage_buffers,
+                    max_descriptor_set_update_after_bind_uniform_buffers: p
+                        .max_descriptor_set_update_after_bind_uniform_buffers,
+                });
+            }
+
+            if fragment_shading_rate_available {
+                let mut shading_rate_features =
+                    vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::default();
+                let mut features2 =
+                    vk::PhysicalDeviceFeatures2::builder().push_next(&mut shading_rate_features);
+                unsafe {
+                    instance
+                        .instance
+                        .get_physical_device_features2(vk_phys_device, &mut features2)
                 };
+
+                let p = &shading_rate_properties;
+                physical_device.fragment_shading_rate = Some(FragmentShadingRateSupport {
+                    min_attachment_texel_size: p.min_fragment_shading_rate_attachment_texel_size,
+                    max_attachment_texel_size: p.max_fragment_shading_rate_attachment_texel_size,
+                    max_attachment_texel_size_aspect_ratio: p
+                        .max_fragment_shading_rate_attachment_texel_size_aspect_ratio,
+                    max_fragment_size: p.max_fragment_size,
+                    max_fragment_size_aspect_ratio: p.max_fragment_size_aspect_ratio,
+                    layered_shading_rate_attachments: p.layered_shading_rate_attachments
+                        == vk::TRUE,
+                    non_trivial_combiner_ops: p.fragment_shading_rate_non_trivial_combiner_ops
+                        == vk::TRUE,
+                    attachment_fragment_shading_rate: shading_rate_features
+                        .attachment_fragment_shading_rate
+                        == vk::TRUE,
+                });
             }
 
+            physical_device.extension_properties = criteria
+                .requested_properties
+                .iter()
+                .map(|request| (request.query)(instance, vk_phys_device))
+                .collect();
+        }
+
+        physical_device.cooperative_matrix_properties = if physical_device
+            .available_extensions
+            .contains(&vk::KHR_COOPERATIVE_MATRIX_EXTENSION.name)
+        {
             unsafe {
-                instance.instance.get_physical_device_features2(
-                    physical_device.physical_device,
-                    &mut local_features,
-                )
-            };
+                instance
+                    .instance
+                    .get_physical_device_cooperative_matrix_properties_khr(vk_phys_device)
+            }
+            .unwrap_or_default()
+        } else if physical_device
+            .available_extensions
+            .contains(&vk::NV_COOPERATIVE_MATRIX_EXTENSION.name)
+        {
+            unsafe {
+                instance
+                    .instance
+                    .get_physical_device_cooperative_matrix_properties_nv(vk_phys_device)
+            }
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| vk::CooperativeMatrixPropertiesKHR {
+                m_size: p.m_size,
+                n_size: p.n_size,
+                k_size: p.k_size,
+                a_type: p.a_type,
+                b_type: p.b_type,
+                c_type: p.c_type,
+                result_type: p.d_type,
+                scope: p.scope,
+                ..Default::default()
+            })
+            .collect()
+        } else {
+            vec![]
+        };
+
+        let mut queried_features_chain = physical_device.requested_features_chain.clone();
+        for feature in &criteria.desired_extension_features {
+            queried_features_chain.add(feature.clone());
+        }
+        let instance_is_11 = instance.instance_version >= Version::V1_1_0;
+        if !queried_features_chain.is_empty()
+            && (instance_is_11 || instance.properties2_ext_enabled)
+        {
+            let mut supported_features = queried_features_chain;
+            supported_features.query(instance, physical_device.physical_device);
 
-            physical_device.supported_features_chain = supported_features.clone();
+            physical_device.supported_features_chain = supported_features;
         }
 
         Ok(physical_device)
     }
 
-    fn select_devices(&self) -> crate::Result<BTreeSet<PhysicalDevice>> {
+    /// Suitable devices, best first.
+    fn select_devices(&self) -> crate::Result<Vec<PhysicalDevice>> {
         let criteria = &self.selection_criteria;
         let instance = self.instance.as_ref();
         if criteria.require_present
             && !criteria.defer_surface_initialization
             && instance.surface.is_none()
         {
-            return Err(crate::PhysicalDeviceError::NoSurfaceProvided.into());
+            return Err(crate::ConfigError::NoSurfaceProvided.into());
         };
 
+        let name_filter = NameFilter::new(criteria)?;
+
         let physical_devices = unsafe { instance.instance.enumerate_physical_devices() }
             .map_err(|_| crate::PhysicalDeviceError::FailedToEnumeratePhysicalDevices)?;
         if physical_devices.is_empty() {
@@ -1309,9 +3472,22 @@ impl PhysicalDeviceSelector {
         };
 
         let fill_out_phys_dev_with_criteria = |physical_device: &mut PhysicalDevice| {
+            physical_device.supported_features = physical_device.features;
             physical_device.features = criteria.required_features;
+            for features in &criteria.desired_features {
+                physical_device.enable_features_if_present(*features);
+            }
+            for feature in &criteria.desired_extension_features {
+                if physical_device.supported_features_chain.matches(feature) {
+                    physical_device
+                        .requested_features_chain
+                        .add(feature.clone());
+                }
+            }
             let mut portability_ext_available = false;
-            let portability_name = vk::KHR_PORTABILITY_ENUMERATION_EXTENSION.name;
+            // VK_KHR_portability_subset is provisional and only exposed by vulkanalia behind its
+            // `provisional` feature.
+            let portability_name = vk::ExtensionName::from_bytes(b"VK_KHR_portability_subset");
             for ext in &physical_device.available_extensions {
                 if criteria.enable_portability_subset && ext == &portability_name {
                     portability_ext_available = true;
@@ -1323,26 +3499,50 @@ impl PhysicalDeviceSelector {
                 .extensions_to_enable
                 .extend(criteria.required_extensions.clone());
 
+            if criteria.required_depth_stencil_resolve.is_some()
+                && physical_device.properties.api_version < u32::from(Version::V1_2_0)
+            {
+                physical_device
+                    .extensions_to_enable
+                    .insert(vk::KHR_DEPTH_STENCIL_RESOLVE_EXTENSION.name);
+            }
+
+            let (promoted_extensions, _) =
+                criteria.promoted_requirements(physical_device.properties.api_version);
+            physical_device
+                .extensions_to_enable
+                .extend(promoted_extensions);
+
             if portability_ext_available {
                 physical_device
                     .extensions_to_enable
                     .insert(portability_name);
             }
+
+            if criteria.enable_metal_objects
+                && physical_device
+                    .available_extensions
+                    .contains(&vk::EXT_METAL_OBJECTS_EXTENSION.name)
+            {
+                physical_device
+                    .extensions_to_enable
+                    .insert(vk::EXT_METAL_OBJECTS_EXTENSION.name);
+            }
         };
 
         if criteria.use_first_gpu_unconditionally {
             let mut device = self.populate_device_details(physical_devices[0])?;
             fill_out_phys_dev_with_criteria(&mut device);
-            return Ok(BTreeSet::from([device]));
+            return Ok(vec![device]);
         };
 
-        let physical_devices = physical_devices
+        let mut physical_devices = physical_devices
             .into_iter()
             .filter_map(|p| {
                 let mut phys_dev = self.populate_device_details(p).ok();
 
                 if let Some(phys_dev) = phys_dev.as_mut() {
-                    self.set_is_suitable(phys_dev);
+                    self.set_is_suitable(phys_dev, &name_filter);
                 }
 
                 phys_dev.and_then(|mut phys_dev| {
@@ -1355,7 +3555,24 @@ impl PhysicalDeviceSelector {
                     }
                 })
             })
-            .collect::<BTreeSet<_>>();
+            .collect::<Vec<_>>();
+        criteria.sort_devices(&mut physical_devices);
+
+        #[cfg(feature = "enable_tracing")]
+        if criteria.power_preference == PowerPreference::HighPerformance
+            && !physical_devices
+                .iter()
+                .any(|d| d.properties.device_type == vk::PhysicalDeviceType::DISCRETE_GPU)
+            && instance
+                .system_info
+                .is_layer_available(vk::ExtensionName::from_bytes(b"VK_LAYER_NV_optimus"))
+                .unwrap_or(false)
+        {
+            tracing::warn!(
+                "Only integrated GPUs are visible while VK_LAYER_NV_optimus is installed, the \
+                 discrete GPU may need the NvOptimusEnablement export to be selectable"
+            );
+        }
 
         Ok(physical_devices)
     }
@@ -1363,7 +3580,37 @@ impl PhysicalDeviceSelector {
     /// Select a suitable `PhysicalDevice` according to the configured criteria.
     ///
     /// Returns a `PhysicalDevice` on success or an error if no suitable device could be found.
-    pub fn select(self) -> crate::Result<PhysicalDevice> {
+    /// The selector is left untouched, so it can select again, e.g. after adjusting the criteria
+    /// of a clone, and the returned device can be cloned to create several logical devices.
+    pub fn select(&self) -> crate::Result<PhysicalDevice> {
+        self.select_ranked()?
+            .into_iter()
+            .next()
+            .map(|ranked| ranked.physical_device)
+            .ok_or_else(|| crate::PhysicalDeviceError::NoSuitableDevice.into())
+    }
+
+    /// All suitable devices, best first, for falling back to the next device when creating a
+    /// logical device on the best one fails. The first entry is the device
+    /// [`PhysicalDeviceSelector::select`] returns; an empty list means no device is suitable.
+    ///
+    /// ``` no_run
+    /// # fn run(instance: std::sync::Arc<vulkanalia_bootstrap::Instance>) -> vulkanalia_bootstrap::Result<()> {
+    /// use vulkanalia_bootstrap::{DeviceBuilder, PhysicalDeviceSelector};
+    ///
+    /// let ranked = PhysicalDeviceSelector::new(instance.clone()).select_ranked()?;
+    /// let device = ranked
+    ///     .into_iter()
+    ///     .find_map(|candidate| {
+    ///         DeviceBuilder::new(candidate.physical_device, instance.clone())
+    ///             .build()
+    ///             .ok()
+    ///     })
+    ///     .ok_or(vulkanalia_bootstrap::PhysicalDeviceError::NoSuitableDevice)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn select_ranked(&self) -> crate::Result<Vec<RankedPhysicalDevice>> {
         let devices = self.select_devices()?;
         #[cfg(feature = "enable_tracing")]
         {
@@ -1376,20 +3623,74 @@ impl PhysicalDeviceSelector {
             );
         }
 
-        if devices.is_empty() {
-            Err(crate::PhysicalDeviceError::NoSuitableDevice.into())
-        } else {
-            Ok(unsafe { devices.into_iter().next().unwrap_unchecked() })
-        }
+        Ok(devices
+            .into_iter()
+            .enumerate()
+            .map(|(rank, physical_device)| RankedPhysicalDevice {
+                partial: physical_device.suitable == Suitable::Partial,
+                rank,
+                physical_device,
+            })
+            .collect())
     }
 }
 
+/// A suitable device returned by [`PhysicalDeviceSelector::select_ranked`].
+#[derive(Debug, Clone)]
+pub struct RankedPhysicalDevice {
+    pub physical_device: PhysicalDevice,
+    /// Whether a preference wasn't met, e.g. the preferred device type or a desired extension.
+    pub partial: bool,
+    /// Position in the ranking, 0 is the best device.
+    pub rank: usize,
+}
+
+/// What [`DeviceBuilder::build`] would create, returned by [`DeviceBuilder::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevicePlan {
+    pub physical_device: String,
+    pub extensions: Vec<vk::ExtensionName>,
+    /// Every queue family gets at least one queue, see [`DeviceBuilder::queue_priorities`].
+    pub queue_families: u32,
+    pub device_fault: bool,
+    pub memory_priority: bool,
+    pub pageable_device_local_memory: bool,
+    pub memory_budget: bool,
+    pub present_id: bool,
+    pub present_wait: bool,
+    pub display_timing: bool,
+    pub low_latency: bool,
+}
+
+/// Optional feature structs queried for the extensions requested on a [`DeviceBuilder`].
+struct OptionalFeatures {
+    features2_enabled: bool,
+    fault: vk::PhysicalDeviceFaultFeaturesEXT,
+    memory_priority: vk::PhysicalDeviceMemoryPriorityFeaturesEXT,
+    pageable: vk::PhysicalDevicePageableDeviceLocalMemoryFeaturesEXT,
+    memory_budget_enabled: bool,
+    present_id: vk::PhysicalDevicePresentIdFeaturesKHR,
+    present_wait: vk::PhysicalDevicePresentWaitFeaturesKHR,
+    display_timing_enabled: bool,
+    low_latency_enabled: bool,
+}
+
+#[derive(Debug, Clone)]
 pub struct DeviceBuilder {
     instance: Arc<Instance>,
     physical_device: PhysicalDevice,
     allocation_callbacks: Option<AllocationCallbacks>,
+    allocator: Option<TrackedAllocationCallbacks>,
+    enable_device_fault: bool,
+    enable_memory_priority: bool,
+    enable_memory_budget: bool,
+    enable_present_id: bool,
+    enable_present_wait: bool,
+    enable_display_timing: bool,
+    enable_low_latency: bool,
+    /// Queue priorities per family index, families without an entry get one queue.
+    queue_priorities: Vec<(u32, Vec<f32>)>,
     // TODO: pNext chains for features
-    // TODO: queue descriptions
 }
 
 impl DeviceBuilder {
@@ -1397,19 +3698,303 @@ impl DeviceBuilder {
         Self {
             physical_device,
             allocation_callbacks: None,
+            allocator: None,
+            enable_device_fault: false,
+            enable_memory_priority: false,
+            enable_memory_budget: false,
+            enable_present_id: false,
+            enable_present_wait: false,
+            enable_display_timing: false,
+            enable_low_latency: false,
+            queue_priorities: vec![],
             instance,
         }
     }
 
     pub fn allocation_callbacks(mut self, allocation_callbacks: AllocationCallbacks) -> Self {
         self.allocation_callbacks.replace(allocation_callbacks);
+        self.allocator = None;
+        self
+    }
+
+    /// Allocate host memory for the device and the objects the crate creates with it with
+    /// `allocator`. The device keeps the allocator alive until it is dropped.
+    pub fn allocator(mut self, allocator: TrackedAllocationCallbacks) -> Self {
+        self.allocation_callbacks = Some(allocator.callbacks());
+        self.allocator = Some(allocator);
+        self
+    }
+
+    /// Enable `VK_EXT_device_fault` when the device supports it, so [`Device::fault_info`] can
+    /// describe what went wrong after `VK_ERROR_DEVICE_LOST`.
+    pub fn enable_device_fault(mut self, enable: bool) -> Self {
+        self.enable_device_fault = enable;
+        self
+    }
+
+    /// Enable `VK_EXT_memory_priority`, and `VK_EXT_pageable_device_local_memory` on top of it,
+    /// when the device supports them. Allocations made through
+    /// [`Device::allocate_memory_with_priority`] then carry their priority, which lets the
+    /// driver keep the important ones resident under VRAM pressure (mostly relevant on Windows).
+    pub fn enable_memory_priority(mut self, enable: bool) -> Self {
+        self.enable_memory_priority = enable;
+        self
+    }
+
+    /// Enable `VK_EXT_memory_budget` when the device supports it, so [`Device::memory_budget`]
+    /// reports the budget and usage of each memory heap for residency management.
+    pub fn enable_memory_budget(mut self, enable: bool) -> Self {
+        self.enable_memory_budget = enable;
+        self
+    }
+
+    /// Enable `VK_KHR_present_id` on presenting devices that support it, so presents can be
+    /// tagged with [`crate::Swapchain::present_with_id`].
+    pub fn enable_present_id(mut self, enable: bool) -> Self {
+        self.enable_present_id = enable;
+        self
+    }
+
+    /// Enable `VK_KHR_present_wait`, and `VK_KHR_present_id` which it depends on, on presenting
+    /// devices that support them, so [`crate::Swapchain::wait_for_present`] can block until a
+    /// tagged present reached the screen. Useful to measure and pace frame latency.
+    pub fn enable_present_wait(mut self, enable: bool) -> Self {
+        self.enable_present_wait = enable;
+        self
+    }
+
+    /// Enable `VK_GOOGLE_display_timing` on presenting devices that support it (mostly Android
+    /// and ChromeOS), for frame pacing with [`crate::Swapchain::refresh_cycle_duration`] and
+    /// [`crate::Swapchain::present_with_time`].
+    pub fn enable_display_timing(mut self, enable: bool) -> Self {
+        self.enable_display_timing = enable;
+        self
+    }
+
+    /// Enable `VK_NV_low_latency2` (NVIDIA Reflex) on presenting devices that support it, and
+    /// `VK_KHR_present_id` to tag the frames its markers refer to. Swapchains opt in with
+    /// [`crate::SwapchainBuilder::low_latency`].
+    pub fn enable_low_latency(mut self, enable: bool) -> Self {
+        self.enable_low_latency = enable;
+        self
+    }
+
+    /// Create one queue per entry of `priorities` in queue family `family_index`, with the given
+    /// priorities between 0.0 and 1.0. Families without priorities get a single queue at
+    /// priority 1.0. The extra queues are fetched with [`Device::get_queue_at`].
+    pub fn queue_priorities(mut self, family_index: u32, priorities: Vec<f32>) -> Self {
+        self.queue_priorities
+            .retain(|(index, _)| *index != family_index);
+        self.queue_priorities.push((family_index, priorities));
         self
     }
 
+    /// Like [`DeviceBuilder::build`], also returning the resolved queues so they don't have to
+    /// be fetched one by one with [`Device::get_queue`].
+    pub fn build_with_queues(self) -> crate::Result<(Device, DeviceQueues)> {
+        let device = self.build()?;
+        let queues = device.queues();
+        Ok((device, queues))
+    }
+
+    /// Check what [`DeviceBuilder::build`] would create, without creating anything.
+    pub fn plan(&self) -> DevicePlan {
+        let mut builder = self.clone();
+        let OptionalFeatures {
+            fault,
+            memory_priority,
+            pageable,
+            memory_budget_enabled,
+            present_id,
+            present_wait,
+            display_timing_enabled,
+            low_latency_enabled,
+            ..
+        } = builder.enable_optional_extensions();
+
+        let physical_device = &builder.physical_device;
+        DevicePlan {
+            physical_device: physical_device.name.clone(),
+            extensions: physical_device
+                .extensions_to_enable
+                .iter()
+                .copied()
+                .collect(),
+            queue_families: physical_device.queue_families.len() as u32,
+            device_fault: fault.device_fault == vk::TRUE,
+            memory_priority: memory_priority.memory_priority == vk::TRUE,
+            pageable_device_local_memory: pageable.pageable_device_local_memory == vk::TRUE,
+            memory_budget: memory_budget_enabled,
+            present_id: present_id.present_id == vk::TRUE,
+            present_wait: present_wait.present_wait == vk::TRUE,
+            display_timing: display_timing_enabled,
+            low_latency: low_latency_enabled,
+        }
+    }
+
+    /// Add the optional extensions requested on the builder that the device supports, and the
+    /// swapchain extension for presenting devices.
+    fn enable_optional_extensions(&mut self) -> OptionalFeatures {
+        let features2_enabled = self.instance.instance_version >= Version::V1_1_0
+            || self.physical_device.properties2_ext_enabled;
+
+        let mut fault_features = vk::PhysicalDeviceFaultFeaturesEXT::default();
+        if self.enable_device_fault
+            && features2_enabled
+            && self
+                .physical_device
+                .available_extensions
+                .contains(&vk::EXT_DEVICE_FAULT_EXTENSION.name)
+        {
+            let mut features2 =
+                vk::PhysicalDeviceFeatures2::builder().push_next(&mut fault_features);
+            unsafe {
+                self.instance.instance.get_physical_device_features2(
+                    self.physical_device.physical_device,
+                    &mut features2,
+                )
+            };
+            fault_features.next = std::ptr::null_mut();
+
+            if fault_features.device_fault == vk::TRUE {
+                self.physical_device
+                    .extensions_to_enable
+                    .insert(vk::EXT_DEVICE_FAULT_EXTENSION.name);
+            }
+        }
+
+        let mut memory_priority_features = vk::PhysicalDeviceMemoryPriorityFeaturesEXT::default();
+        let mut pageable_features =
+            vk::PhysicalDevicePageableDeviceLocalMemoryFeaturesEXT::default();
+        let available_extensions = &self.physical_device.available_extensions;
+        if self.enable_memory_priority
+            && features2_enabled
+            && available_extensions.contains(&vk::EXT_MEMORY_PRIORITY_EXTENSION.name)
+        {
+            let pageable_available =
+                available_extensions.contains(&vk::EXT_PAGEABLE_DEVICE_LOCAL_MEMORY_EXTENSION.name);
+            let mut features2 =
+                vk::PhysicalDeviceFeatures2::builder().push_next(&mut memory_priority_features);
+            if pageable_available {
+                features2 = features2.push_next(&mut pageable_features);
+            }
+            unsafe {
+                self.instance.instance.get_physical_device_features2(
+                    self.physical_device.physical_device,
+                    &mut features2,
+                )
+            };
+            memory_priority_features.next = std::ptr::null_mut();
+            pageable_features.next = std::ptr::null_mut();
+
+            if memory_priority_features.memory_priority == vk::TRUE {
+                self.physical_device
+                    .extensions_to_enable
+                    .insert(vk::EXT_MEMORY_PRIORITY_EXTENSION.name);
+            } else {
+                pageable_features.pageable_device_local_memory = vk::FALSE;
+            }
+            if pageable_features.pageable_device_local_memory == vk::TRUE {
+                self.physical_device
+                    .extensions_to_enable
+                    .insert(vk::EXT_PAGEABLE_DEVICE_LOCAL_MEMORY_EXTENSION.name);
+            }
+        }
+
+        let memory_budget_enabled = self.enable_memory_budget
+            && features2_enabled
+            && available_extensions.contains(&vk::EXT_MEMORY_BUDGET_EXTENSION.name);
+        if memory_budget_enabled {
+            self.physical_device
+                .extensions_to_enable
+                .insert(vk::EXT_MEMORY_BUDGET_EXTENSION.name);
+        }
+
+        // Only devices selected for presentation get the swapchain extension, so compute-only
+        // devices work on drivers and instances without WSI support.
+        let presenting = self.physical_device.require_present
+            && (self.physical_device.surface.is_some()
+                || self.physical_device.defer_surface_initialization);
+        if presenting {
+            self.physical_device
+                .extensions_to_enable
+                .insert(vk::KHR_SWAPCHAIN_EXTENSION.name);
+        }
+
+        let mut present_id_features = vk::PhysicalDevicePresentIdFeaturesKHR::default();
+        let mut present_wait_features = vk::PhysicalDevicePresentWaitFeaturesKHR::default();
+        let available_extensions = &self.physical_device.available_extensions;
+        if (self.enable_present_id || self.enable_present_wait || self.enable_low_latency)
+            && presenting
+            && features2_enabled
+            && available_extensions.contains(&vk::KHR_PRESENT_ID_EXTENSION.name)
+        {
+            let present_wait_available = self.enable_present_wait
+                && available_extensions.contains(&vk::KHR_PRESENT_WAIT_EXTENSION.name);
+            let mut features2 =
+                vk::PhysicalDeviceFeatures2::builder().push_next(&mut present_id_features);
+            if present_wait_available {
+                features2 = features2.push_next(&mut present_wait_features);
+            }
+            unsafe {
+                self.instance.instance.get_physical_device_features2(
+                    self.physical_device.physical_device,
+                    &mut features2,
+                )
+            };
+            present_id_features.next = std::ptr::null_mut();
+            present_wait_features.next = std::ptr::null_mut();
+
+            if present_id_features.present_id == vk::TRUE {
+                self.physical_device
+                    .extensions_to_enable
+                    .insert(vk::KHR_PRESENT_ID_EXTENSION.name);
+            } else {
+                present_wait_features.present_wait = vk::FALSE;
+            }
+            if present_wait_features.present_wait == vk::TRUE {
+                self.physical_device
+                    .extensions_to_enable
+                    .insert(vk::KHR_PRESENT_WAIT_EXTENSION.name);
+            }
+        }
+
+        let display_timing_enabled = self.enable_display_timing
+            && presenting
+            && available_extensions.contains(&vk::GOOGLE_DISPLAY_TIMING_EXTENSION.name);
+        if display_timing_enabled {
+            self.physical_device
+                .extensions_to_enable
+                .insert(vk::GOOGLE_DISPLAY_TIMING_EXTENSION.name);
+        }
+
+        let low_latency_enabled = self.enable_low_latency
+            && presenting
+            && available_extensions.contains(&vk::NV_LOW_LATENCY2_EXTENSION.name);
+        if low_latency_enabled {
+            self.physical_device
+                .extensions_to_enable
+                .insert(vk::NV_LOW_LATENCY2_EXTENSION.name);
+        }
+
+        OptionalFeatures {
+            features2_enabled,
+            fault: fault_features,
+            memory_priority: memory_priority_features,
+            pageable: pageable_features,
+            memory_budget_enabled,
+            present_id: present_id_features,
+            present_wait: present_wait_features,
+            display_timing_enabled,
+            low_latency_enabled,
+        }
+    }
+
     /// Create a logical `Device` from the configured `PhysicalDevice`.
     ///
     /// What this does:
-    /// - Builds queue create infos for each discovered queue family (default priority 1.0).
+    /// - Builds queue create infos for each discovered queue family, with the priorities set by
+    ///   [`DeviceBuilder::queue_priorities`] (default one queue at priority 1.0).
     /// - Enables any device extensions that were marked on the `PhysicalDevice` (and the
     ///   `VK_KHR_swapchain` extension when a surface is present or surface init is deferred).
     /// - Pushes a `vk::PhysicalDeviceFeatures2` and any requested feature-chain nodes onto the
@@ -1422,20 +4007,34 @@ impl DeviceBuilder {
     /// - An error if device creation fails.
     ///
     /// Notes:
-    /// - Queue configuration is simplified: every queue family discovered by the physical
-    ///   device is created with a single queue at priority 1.0. Customize if you need
-    ///   different priorities or explicit queue counts.
+    /// - Every queue family discovered by the physical device gets at least one queue.
     /// - Any allocation callbacks previously set via `DeviceBuilder::allocation_callbacks`
     ///   are forwarded to `vkCreateDevice` and stored in the returned `Device`.
     pub fn build(mut self) -> crate::Result<Device> {
-        // TODO: custom queue setup
-        // (index, priorities)
-        let queue_descriptions = self
-            .physical_device
-            .queue_families
+        let queue_descriptions =
+            queue_descriptions(&self.physical_device.queue_families, &self.queue_priorities)?;
+
+        let OptionalFeatures {
+            features2_enabled,
+            fault: mut fault_features,
+            memory_priority: mut memory_priority_features,
+            pageable: mut pageable_features,
+            memory_budget_enabled,
+            present_id: mut present_id_features,
+            present_wait: mut present_wait_features,
+            display_timing_enabled,
+            low_latency_enabled,
+        } = self.enable_optional_extensions();
+        let device_fault_enabled = fault_features.device_fault == vk::TRUE;
+        let memory_priority_enabled = memory_priority_features.memory_priority == vk::TRUE;
+        let pageable_device_local_memory_enabled =
+            pageable_features.pageable_device_local_memory == vk::TRUE;
+        let present_id_enabled = present_id_features.present_id == vk::TRUE;
+        let present_wait_enabled = present_wait_features.present_wait == vk::TRUE;
+
+        let queue_counts = queue_descriptions
             .iter()
-            .enumerate()
-            .map(|(index, _)| (index, [1.]))
+            .map(|(_, priorities)| priorities.len() as u32)
             .collect::<Vec<_>>();
 
         let queue_create_infos = queue_descriptions
@@ -1447,19 +4046,13 @@ impl DeviceBuilder {
             })
             .collect::<Vec<_>>();
 
-        let mut extensions_to_enable = self
+        let extensions_to_enable = self
             .physical_device
             .extensions_to_enable
             .iter()
             .map(|ext| ext.as_ptr())
             .collect::<Vec<_>>();
 
-        if self.physical_device.surface.is_some()
-            || self.physical_device.defer_surface_initialization
-        {
-            extensions_to_enable.push(vk::KHR_SWAPCHAIN_EXTENSION.name.as_ptr());
-        }
-
         let mut device_create_info = vk::DeviceCreateInfo::builder()
             .queue_create_infos(&queue_create_infos)
             .enabled_extension_names(&extensions_to_enable);
@@ -1469,11 +4062,29 @@ impl DeviceBuilder {
         let mut features2 =
             vk::PhysicalDeviceFeatures2::builder().features(self.physical_device.features);
 
-        if self.instance.instance_version >= Version::V1_1_0
-            || self.physical_device.properties2_ext_enabled
-        {
+        if features2_enabled {
             device_create_info = device_create_info.push_next(&mut features2);
 
+            if device_fault_enabled {
+                device_create_info = device_create_info.push_next(&mut fault_features);
+            }
+
+            if memory_priority_enabled {
+                device_create_info = device_create_info.push_next(&mut memory_priority_features);
+            }
+
+            if pageable_device_local_memory_enabled {
+                device_create_info = device_create_info.push_next(&mut pageable_features);
+            }
+
+            if present_id_enabled {
+                device_create_info = device_create_info.push_next(&mut present_id_features);
+            }
+
+            if present_wait_enabled {
+                device_create_info = device_create_info.push_next(&mut present_wait_features);
+            }
+
             for node in requested_features_chain.nodes.iter_mut() {
                 match node {
                     VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan11(f) => {
@@ -1485,8 +4096,35 @@ impl DeviceBuilder {
                     VulkanPhysicalDeviceFeature2::PhysicalDeviceVulkan13(f) => {
                         device_create_info = device_create_info.push_next(f)
                     }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixKHR(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceCooperativeMatrixNV(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceAccelerationStructureKHR(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceRayTracingPipelineKHR(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceMeshShaderEXT(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRendering(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceDynamicRenderingLocalRead(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
+                    VulkanPhysicalDeviceFeature2::PhysicalDeviceFragmentShadingRateKHR(f) => {
+                        device_create_info = device_create_info.push_next(f)
+                    }
                 }
             }
+        } else {
+            device_create_info =
+                device_create_info.enabled_features(&self.physical_device.features);
         }
 
         let device = unsafe {
@@ -1498,28 +4136,127 @@ impl DeviceBuilder {
         }?;
 
         let instance = self.instance;
-        let physical_device = self.physical_device;
+        let mut physical_device = self.physical_device;
+        if features2_enabled {
+            for node in physical_device.requested_features_chain.nodes.iter_mut() {
+                node.clear_next();
+            }
+        } else {
+            physical_device.requested_features_chain = GenericFeatureChain::new();
+        }
 
         let surface = physical_device.surface;
         let allocation_callbacks = self.allocation_callbacks;
+        let allocator = self.allocator.take();
 
-        Ok(Device {
+        let mut device = Device {
             instance,
             device,
             surface,
             physical_device,
             allocation_callbacks,
-        })
+            allocator,
+            queues: Mutex::new(None),
+            queue_counts,
+            device_fault_enabled,
+            device_fault_vendor_binary: fault_features.device_fault_vendor_binary == vk::TRUE,
+            memory_priority_enabled,
+            pageable_device_local_memory_enabled,
+            memory_budget_enabled,
+            present_id_enabled,
+            present_wait_enabled,
+            display_timing_enabled,
+            low_latency_enabled,
+            queue_audit: QueueAudit::default(),
+        };
+        let queues = device.resolve_queues();
+        *device.queues.get_mut().unwrap() = Some(queues);
+        device.instance.live_objects.track(
+            vk::ObjectType::DEVICE,
+            device.device.handle().as_raw() as u64,
+        );
+
+        Ok(device)
     }
 }
 
-#[derive(Debug)]
-pub struct Device {
-    instance: Arc<Instance>,
+/// `(family index, priorities)` of every queue family for [`DeviceBuilder::build`], validated
+/// against the families of the physical device.
+fn queue_descriptions(
+    families: &[vk::QueueFamilyProperties],
+    queue_priorities: &[(u32, Vec<f32>)],
+) -> crate::Result<Vec<(usize, Vec<f32>)>> {
+    for (index, priorities) in queue_priorities {
+        let Some(family) = families.get(*index as usize) else {
+            return Err(crate::ConfigError::InvalidQueueFamilyOverride {
+                index: *index,
+                count: families.len() as u32,
+            }
+            .into());
+        };
+        if priorities.is_empty() || priorities.len() > family.queue_count as usize {
+            return Err(crate::ConfigError::InvalidQueueCount {
+                family: *index,
+                requested: priorities.len() as u32,
+                available: family.queue_count,
+            }
+            .into());
+        }
+        if !priorities
+            .iter()
+            .all(|priority| (0.0..=1.0).contains(priority))
+        {
+            return Err(crate::ConfigError::InvalidQueuePriority { family: *index }.into());
+        }
+    }
+
+    Ok((0..families.len())
+        .map(|index| {
+            let priorities = queue_priorities
+                .iter()
+                .find(|(family, _)| *family as usize == index)
+                .map_or_else(|| vec![1.0], |(_, priorities)| priorities.clone());
+            (index, priorities)
+        })
+        .collect())
+}
+
+#[derive(Debug)]
+pub struct Device {
+    instance: Arc<Instance>,
     device: vulkanalia::Device,
     physical_device: PhysicalDevice,
     surface: Option<vk::SurfaceKHR>,
     allocation_callbacks: Option<AllocationCallbacks>,
+    allocator: Option<TrackedAllocationCallbacks>,
+    queues: Mutex<Option<DeviceQueues>>,
+    /// Number of queues created in each queue family.
+    queue_counts: Vec<u32>,
+    device_fault_enabled: bool,
+    device_fault_vendor_binary: bool,
+    memory_priority_enabled: bool,
+    pageable_device_local_memory_enabled: bool,
+    memory_budget_enabled: bool,
+    present_id_enabled: bool,
+    present_wait_enabled: bool,
+    display_timing_enabled: bool,
+    low_latency_enabled: bool,
+    queue_audit: QueueAudit,
+}
+
+/// Queue family index and queue handle of every [`QueueType`], resolved once per device.
+///
+/// `None` means the device has no such queue. The dedicated queues are only set when the device
+/// has a family with compute but not graphics or transfer (or the other way around).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeviceQueues {
+    pub present: Option<(usize, vk::Queue)>,
+    pub graphics: Option<(usize, vk::Queue)>,
+    pub compute: Option<(usize, vk::Queue)>,
+    pub transfer: Option<(usize, vk::Queue)>,
+    pub dedicated_compute: Option<(usize, vk::Queue)>,
+    pub dedicated_transfer: Option<(usize, vk::Queue)>,
+    pub sparse_binding: Option<(usize, vk::Queue)>,
 }
 
 #[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Ord)]
@@ -1528,6 +4265,73 @@ pub enum QueueType {
     Graphics,
     Compute,
     Transfer,
+    /// First queue family with `vk::QueueFlags::SPARSE_BINDING`, for binding sparse memory.
+    SparseBinding,
+}
+
+/// Sharing configuration for a buffer or image that is accessed from several queue families.
+///
+/// When every queue resolves to the same family the resource stays `EXCLUSIVE`, otherwise it
+/// is shared `CONCURRENT` between the distinct families.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceSharing {
+    pub sharing_mode: vk::SharingMode,
+    pub queue_family_indices: Vec<u32>,
+}
+
+impl ResourceSharing {
+    pub(crate) fn from_families(families: impl IntoIterator<Item = u32>) -> Self {
+        let mut queue_family_indices = Vec::new();
+        for family in families {
+            if !queue_family_indices.contains(&family) {
+                queue_family_indices.push(family);
+            }
+        }
+
+        let sharing_mode = if queue_family_indices.len() > 1 {
+            vk::SharingMode::CONCURRENT
+        } else {
+            vk::SharingMode::EXCLUSIVE
+        };
+
+        Self {
+            sharing_mode,
+            queue_family_indices,
+        }
+    }
+
+    /// Queue family indices to pass to the create info. Empty for `EXCLUSIVE` sharing.
+    pub fn create_info_indices(&self) -> &[u32] {
+        if self.sharing_mode == vk::SharingMode::CONCURRENT {
+            &self.queue_family_indices
+        } else {
+            &[]
+        }
+    }
+
+    /// Start a `vk::BufferCreateInfo` with the sharing mode and queue family indices filled in.
+    pub fn buffer_create_info(&self) -> vk::BufferCreateInfoBuilder<'_> {
+        vk::BufferCreateInfo::builder()
+            .sharing_mode(self.sharing_mode)
+            .queue_family_indices(self.create_info_indices())
+    }
+
+    /// Start a `vk::ImageCreateInfo` with the sharing mode and queue family indices filled in.
+    pub fn image_create_info(&self) -> vk::ImageCreateInfoBuilder<'_> {
+        vk::ImageCreateInfo::builder()
+            .sharing_mode(self.sharing_mode)
+            .queue_family_indices(self.create_info_indices())
+    }
+}
+
+/// A release/acquire pair needed to move an `EXCLUSIVE` resource between queue families.
+///
+/// Record a release barrier with these indices on the source queue and a matching acquire
+/// barrier on the destination queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFamilyOwnershipTransfer {
+    pub src_queue_family_index: u32,
+    pub dst_queue_family_index: u32,
 }
 
 impl Device {
@@ -1539,39 +4343,186 @@ impl Device {
         &self.physical_device
     }
 
-    pub fn get_queue(&self, queue: QueueType) -> crate::Result<(usize, vk::Queue)> {
-        let index = match queue {
-            QueueType::Present => get_present_queue_index(
+    /// Core features the device was created with.
+    pub fn enabled_features(&self) -> vk::PhysicalDeviceFeatures {
+        self.physical_device.features
+    }
+
+    /// Feature2 structs the device was created with, e.g. to pick renderer code paths based on
+    /// the desired features that turned out to be supported.
+    pub fn enabled_extension_features(&self) -> &[VulkanPhysicalDeviceFeature2] {
+        &self.physical_device.requested_features_chain
+    }
+
+    /// Device extensions passed to `vkCreateDevice`, including the ones added by the bootstrap
+    /// (swapchain, portability subset, ...).
+    pub fn enabled_extensions(&self) -> &BTreeSet<vk::ExtensionName> {
+        &self.physical_device.extensions_to_enable
+    }
+
+    /// Whether `extension` was enabled when creating the device.
+    pub fn is_extension_enabled(&self, extension: &vk::ExtensionName) -> bool {
+        self.physical_device
+            .extensions_to_enable
+            .contains(extension)
+    }
+
+    /// Whether `queue_family` can present to `surface`, e.g. a surface created for another
+    /// window after the device.
+    pub fn supports_present(
+        &self,
+        surface: vk::SurfaceKHR,
+        queue_family: u32,
+    ) -> crate::Result<bool> {
+        Ok(unsafe {
+            self.instance
+                .instance
+                .get_physical_device_surface_support_khr(
+                    self.physical_device.physical_device,
+                    queue_family,
+                    surface,
+                )
+        }?)
+    }
+
+    /// Buffer, linear and optimal tiling features the physical device supports for `format`.
+    pub fn format_properties(&self, format: vk::Format) -> vk::FormatProperties {
+        self.physical_device
+            .format_properties(&self.instance, format)
+    }
+
+    pub(crate) fn allocation_callbacks(&self) -> Option<&AllocationCallbacks> {
+        self.allocation_callbacks.as_ref()
+    }
+
+    /// The allocator set with [`DeviceBuilder::allocator`].
+    pub fn allocator(&self) -> Option<&TrackedAllocationCallbacks> {
+        self.allocator.as_ref()
+    }
+
+    pub(crate) fn live_objects(&self) -> &LiveObjects {
+        &self.instance.live_objects
+    }
+
+    fn resolve_queues(&self) -> DeviceQueues {
+        let queue_families = &self.physical_device.queue_families;
+        let queue = |index: Option<usize>| {
+            index.map(|index| {
+                (index, unsafe {
+                    self.device.get_device_queue(index as _, 0)
+                })
+            })
+        };
+
+        DeviceQueues {
+            present: queue(get_present_queue_index(
                 &self.instance.instance,
                 self.physical_device.physical_device,
                 self.surface,
-                &self.physical_device.queue_families,
-            )
-            .ok_or(crate::QueueError::PresentUnavailable),
-            QueueType::Graphics => get_first_queue_index(
-                &self.physical_device.queue_families,
+                queue_families,
+            )),
+            graphics: queue(get_first_queue_index(
+                queue_families,
                 vk::QueueFlags::GRAPHICS,
-            )
-            .ok_or(crate::QueueError::GraphicsUnavailable),
-            QueueType::Compute => get_separate_queue_index(
-                &self.physical_device.queue_families,
+            )),
+            compute: queue(get_separate_queue_index(
+                queue_families,
                 vk::QueueFlags::COMPUTE,
                 vk::QueueFlags::TRANSFER,
-            )
-            .ok_or(crate::QueueError::ComputeUnavailable),
-            QueueType::Transfer => get_separate_queue_index(
-                &self.physical_device.queue_families,
+            )),
+            transfer: queue(get_separate_queue_index(
+                queue_families,
                 vk::QueueFlags::TRANSFER,
                 vk::QueueFlags::COMPUTE,
-            )
-            .ok_or(crate::QueueError::TransferUnavailable),
+            )),
+            dedicated_compute: queue(get_dedicated_queue_index(
+                queue_families,
+                vk::QueueFlags::COMPUTE,
+                vk::QueueFlags::TRANSFER,
+            )),
+            dedicated_transfer: queue(get_dedicated_queue_index(
+                queue_families,
+                vk::QueueFlags::TRANSFER,
+                vk::QueueFlags::COMPUTE,
+            )),
+            sparse_binding: queue(get_first_queue_index(
+                queue_families,
+                vk::QueueFlags::SPARSE_BINDING,
+            )),
+        }
+    }
+
+    /// Every queue of the device at once, see [`DeviceQueues`].
+    pub fn queues(&self) -> DeviceQueues {
+        *self
+            .queues
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| self.resolve_queues())
+    }
+
+    /// Family index and handle of the queue used for `queue`.
+    ///
+    /// Queues are resolved when the device is built, so this is a cheap lookup. Call
+    /// [`Device::invalidate_queues`] to resolve them again.
+    pub fn get_queue(&self, queue: QueueType) -> crate::Result<(usize, vk::Queue)> {
+        let mut queues = self.queues.lock().unwrap();
+        let queues = queues.get_or_insert_with(|| self.resolve_queues());
+
+        let resolved = match queue {
+            QueueType::Present => queues.present.ok_or(crate::QueueError::PresentUnavailable),
+            QueueType::Graphics => queues
+                .graphics
+                .ok_or(crate::QueueError::GraphicsUnavailable),
+            QueueType::Compute => queues.compute.ok_or(crate::QueueError::ComputeUnavailable),
+            QueueType::Transfer => queues
+                .transfer
+                .ok_or(crate::QueueError::TransferUnavailable),
+            QueueType::SparseBinding => queues
+                .sparse_binding
+                .ok_or(crate::QueueError::SparseBindingUnavailable),
         }?;
 
-        Ok((index, unsafe {
-            self.device.get_device_queue(index as _, 0)
+        Ok(resolved)
+    }
+
+    /// Queue `index` within the family used for `queue`, for families created with more than one
+    /// queue (see [`DeviceBuilder::queue_priorities`]). Index 0 is the queue returned by
+    /// [`Device::get_queue`].
+    pub fn get_queue_at(&self, queue: QueueType, index: u32) -> crate::Result<(usize, vk::Queue)> {
+        let (family_index, first) = self.get_queue(queue)?;
+        if index == 0 {
+            return Ok((family_index, first));
+        }
+
+        if index
+            >= self
+                .queue_counts
+                .get(family_index)
+                .copied()
+                .unwrap_or_default()
+        {
+            return Err(crate::ConfigError::QueueIndexOutOfBounds.into());
+        }
+
+        Ok((family_index, unsafe {
+            self.device.get_device_queue(family_index as _, index)
         }))
     }
 
+    /// Drop the resolved queues so the next [`Device::get_queue`] scans the queue families
+    /// again. Use [`Device::set_surface`] when the surface was replaced.
+    pub fn invalidate_queues(&self) {
+        *self.queues.lock().unwrap() = None;
+    }
+
+    /// Resolve the present queue against `surface` from now on, e.g. after the window was
+    /// recreated. `None` leaves the device without a present queue.
+    pub fn set_surface(&mut self, surface: Option<vk::SurfaceKHR>) {
+        self.surface = surface;
+        *self.queues.get_mut().unwrap() = None;
+    }
+
     pub fn get_dedicated_queue(&self, queue: QueueType) -> crate::Result<vk::Queue> {
         let index = match queue {
             QueueType::Compute => get_dedicated_queue_index(
@@ -1586,7 +4537,7 @@ impl Device {
                 vk::QueueFlags::COMPUTE,
             )
             .ok_or(crate::QueueError::TransferUnavailable),
-            _ => return Err(crate::QueueError::InvalidQueueFamilyIndex.into()),
+            _ => return Err(crate::ConfigError::InvalidQueueFamilyIndex.into()),
         }?;
 
         let info = vk::DeviceQueueInfo2::builder()
@@ -1596,7 +4547,516 @@ impl Device {
         Ok(unsafe { self.device.get_device_queue2(&info) })
     }
 
+    /// Resolve the queue families used by `queues` and return the sharing configuration for a
+    /// resource touched by all of them.
+    pub fn resource_sharing(&self, queues: &[QueueType]) -> crate::Result<ResourceSharing> {
+        let families = queues
+            .iter()
+            .map(|queue| self.get_queue(queue.clone()).map(|(index, _)| index as u32))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(ResourceSharing::from_families(families))
+    }
+
+    /// Keep a resource `EXCLUSIVE` and return the ownership transfers required when it is used
+    /// by `queues` in the given order. Consecutive uses on the same family need no transfer.
+    pub fn ownership_transfers(
+        &self,
+        queues: &[QueueType],
+    ) -> crate::Result<Vec<QueueFamilyOwnershipTransfer>> {
+        let families = queues
+            .iter()
+            .map(|queue| self.get_queue(queue.clone()).map(|(index, _)| index as u32))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(families
+            .windows(2)
+            .filter(|pair| pair[0] != pair[1])
+            .map(|pair| QueueFamilyOwnershipTransfer {
+                src_queue_family_index: pair[0],
+                dst_queue_family_index: pair[1],
+            })
+            .collect())
+    }
+
+    /// Attach a debug name to `handle`. Does nothing when `VK_EXT_debug_utils` is not enabled.
+    pub fn set_object_name<H: vk::Handle<Repr = u64>>(
+        &self,
+        handle: H,
+        name: &str,
+    ) -> crate::Result<()> {
+        self.instance
+            .live_objects
+            .set_name(H::TYPE, handle.as_raw(), name);
+        if !self.instance.debug_utils_enabled || name.is_empty() {
+            return Ok(());
+        }
+
+        let name = CString::new(name).unwrap_or_default();
+        let name_info = vk::DebugUtilsObjectNameInfoEXT::builder()
+            .object_type(H::TYPE)
+            .object_handle(handle.as_raw())
+            .object_name(name.as_bytes_with_nul());
+
+        unsafe {
+            self.instance
+                .instance
+                .set_debug_utils_object_name_ext(self.device.handle(), &name_info)
+        }?;
+
+        Ok(())
+    }
+
+    /// Create a shader module from SPIR-V words and give it a debug name.
+    ///
+    /// Failures are reported as [`crate::CreateError`] carrying the module name.
+    pub fn create_shader_module(
+        &self,
+        name: &str,
+        code: &[u32],
+    ) -> crate::Result<vk::ShaderModule> {
+        let create_info = vk::ShaderModuleCreateInfo::builder()
+            .code_size(std::mem::size_of_val(code))
+            .code(code);
+
+        let shader_module = unsafe {
+            self.device
+                .create_shader_module(&create_info, self.allocation_callbacks.as_ref())
+        }
+        .map_err(|code_err| crate::CreateError {
+            kind: vk::ObjectType::SHADER_MODULE,
+            name: name.to_string(),
+            details: format!("{} bytes of SPIR-V", std::mem::size_of_val(code)),
+            code: code_err,
+        })?;
+
+        if let Err(e) = self.set_object_name(shader_module, name) {
+            unsafe {
+                self.device
+                    .destroy_shader_module(shader_module, self.allocation_callbacks.as_ref())
+            };
+            return Err(e);
+        }
+
+        Ok(shader_module)
+    }
+
+    /// Create an object named `name[index]` for each index, destroying the created ones again
+    /// when one fails. The objects are tracked for the leak report until they are destroyed with
+    /// [`Device::destroy_named`].
+    fn create_named<H: vk::Handle<Repr = u64> + Copy>(
+        &self,
+        name: &str,
+        indices: Range<usize>,
+        details: impl Fn(usize) -> String,
+        mut create: impl FnMut(usize) -> vulkanalia::VkResult<H>,
+        destroy: impl Fn(H),
+    ) -> crate::Result<Vec<H>> {
+        let mut handles = Vec::with_capacity(indices.len());
+
+        for index in indices {
+            let name = format!("{name}[{index}]");
+            let result = create(index)
+                .map_err(|code| {
+                    crate::CreateError {
+                        kind: H::TYPE,
+                        name: name.clone(),
+                        details: details(index),
+                        code,
+                    }
+                    .into()
+                })
+                .and_then(|handle| {
+                    handles.push(handle);
+                    self.live_objects().track(H::TYPE, handle.as_raw());
+                    self.set_object_name(handle, &name)
+                });
+
+            if let Err(e) = result {
+                for handle in handles {
+                    self.live_objects().untrack(H::TYPE, handle.as_raw());
+                    destroy(handle);
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(handles)
+    }
+
+    /// Current budget and usage of each memory heap. `None` unless `VK_EXT_memory_budget` was
+    /// enabled, see [`DeviceBuilder::enable_memory_budget`].
+    pub fn memory_budget(&self) -> Option<Vec<MemoryHeapBudget>> {
+        if !self.memory_budget_enabled {
+            return None;
+        }
+        self.physical_device.memory_budget(&self.instance)
+    }
+
+    /// Whether `VK_EXT_memory_priority` was enabled, see [`DeviceBuilder::enable_memory_priority`].
+    pub fn memory_priority_enabled(&self) -> bool {
+        self.memory_priority_enabled
+    }
+
+    /// Whether `VK_KHR_present_id` was enabled, see [`DeviceBuilder::enable_present_id`].
+    pub fn present_id_enabled(&self) -> bool {
+        self.present_id_enabled
+    }
+
+    /// Whether `VK_KHR_present_wait` was enabled, see [`DeviceBuilder::enable_present_wait`].
+    pub fn present_wait_enabled(&self) -> bool {
+        self.present_wait_enabled
+    }
+
+    /// Whether `VK_GOOGLE_display_timing` was enabled, see
+    /// [`DeviceBuilder::enable_display_timing`].
+    pub fn display_timing_enabled(&self) -> bool {
+        self.display_timing_enabled
+    }
+
+    /// Whether `VK_NV_low_latency2` was enabled, see [`DeviceBuilder::enable_low_latency`].
+    pub fn low_latency_enabled(&self) -> bool {
+        self.low_latency_enabled
+    }
+
+    /// Allocate device memory with a priority between 0.0 (first to be evicted) and 1.0. The
+    /// priority is only passed on when `VK_EXT_memory_priority` is enabled.
+    pub fn allocate_memory_with_priority(
+        &self,
+        allocation_size: vk::DeviceSize,
+        memory_type_index: u32,
+        priority: f32,
+    ) -> crate::Result<vk::DeviceMemory> {
+        let mut priority_info =
+            vk::MemoryPriorityAllocateInfoEXT::builder().priority(priority.clamp(0.0, 1.0));
+        let mut allocate_info = vk::MemoryAllocateInfo::builder()
+            .allocation_size(allocation_size)
+            .memory_type_index(memory_type_index);
+        if self.memory_priority_enabled {
+            allocate_info = allocate_info.push_next(&mut priority_info);
+        }
+
+        Ok(unsafe {
+            self.device
+                .allocate_memory(&allocate_info, self.allocation_callbacks.as_ref())
+        }?)
+    }
+
+    /// Change the priority of an allocation after the fact. Only has an effect when
+    /// `VK_EXT_pageable_device_local_memory` is enabled.
+    pub fn set_memory_priority(&self, memory: vk::DeviceMemory, priority: f32) {
+        if self.pageable_device_local_memory_enabled {
+            unsafe {
+                self.device
+                    .set_device_memory_priority_ext(memory, priority.clamp(0.0, 1.0))
+            };
+        }
+    }
+
+    /// Create `count` binary semaphores named `name[0]`, `name[1]`, ... Destroy them with
+    /// [`Device::destroy_semaphores`].
+    pub fn create_semaphores(&self, name: &str, count: usize) -> crate::Result<Vec<vk::Semaphore>> {
+        self.create_indexed_semaphores(name, 0..count)
+    }
+
+    /// [`Device::create_semaphores`] numbering the semaphores from `indices.start`, for pools
+    /// growing one batch at a time.
+    pub(crate) fn create_indexed_semaphores(
+        &self,
+        name: &str,
+        indices: Range<usize>,
+    ) -> crate::Result<Vec<vk::Semaphore>> {
+        let create_info = vk::SemaphoreCreateInfo::default();
+
+        self.create_named(
+            name,
+            indices,
+            |_| "binary".to_string(),
+            |_| unsafe {
+                self.device
+                    .create_semaphore(&create_info, self.allocation_callbacks.as_ref())
+            },
+            |semaphore| unsafe {
+                self.device
+                    .destroy_semaphore(semaphore, self.allocation_callbacks.as_ref())
+            },
+        )
+    }
+
+    /// Create `count` fences named `name[0]`, `name[1]`, ..., optionally already signaled so the
+    /// first wait of a frame loop returns right away. Destroy them with [`Device::destroy_fences`].
+    pub fn create_fences(
+        &self,
+        name: &str,
+        count: usize,
+        signaled: bool,
+    ) -> crate::Result<Vec<vk::Fence>> {
+        self.create_indexed_fences(name, 0..count, signaled)
+    }
+
+    /// [`Device::create_fences`] numbering the fences from `indices.start`, for pools growing
+    /// one batch at a time.
+    pub(crate) fn create_indexed_fences(
+        &self,
+        name: &str,
+        indices: Range<usize>,
+        signaled: bool,
+    ) -> crate::Result<Vec<vk::Fence>> {
+        let flags = if signaled {
+            vk::FenceCreateFlags::SIGNALED
+        } else {
+            vk::FenceCreateFlags::empty()
+        };
+        let create_info = vk::FenceCreateInfo::builder().flags(flags);
+
+        self.create_named(
+            name,
+            indices,
+            |_| format!("{flags:?}"),
+            |_| unsafe {
+                self.device
+                    .create_fence(&create_info, self.allocation_callbacks.as_ref())
+            },
+            |fence| unsafe {
+                self.device
+                    .destroy_fence(fence, self.allocation_callbacks.as_ref())
+            },
+        )
+    }
+
+    /// Create a view of each image in `images` named `name[0]`, `name[1]`, ...
+    ///
+    /// `options` is used as the create info of every view with its `image` replaced. Destroy the
+    /// views with [`Device::destroy_image_views`].
+    pub fn create_image_views(
+        &self,
+        name: &str,
+        images: &[vk::Image],
+        options: &vk::ImageViewCreateInfo,
+    ) -> crate::Result<Vec<vk::ImageView>> {
+        self.create_named(
+            name,
+            0..images.len(),
+            |_| format!("{:?} {:?}", options.view_type, options.format),
+            |index| {
+                let create_info = vk::ImageViewCreateInfo {
+                    image: images[index],
+                    ..*options
+                };
+                unsafe {
+                    self.device
+                        .create_image_view(&create_info, self.allocation_callbacks.as_ref())
+                }
+            },
+            |view| unsafe {
+                self.device
+                    .destroy_image_view(view, self.allocation_callbacks.as_ref())
+            },
+        )
+    }
+
+    /// Destroy semaphores created with [`Device::create_semaphores`].
+    pub fn destroy_semaphores(&self, semaphores: &[vk::Semaphore]) {
+        self.destroy_named(semaphores, |semaphore| unsafe {
+            self.device
+                .destroy_semaphore(semaphore, self.allocation_callbacks.as_ref())
+        });
+    }
+
+    /// Destroy fences created with [`Device::create_fences`].
+    pub fn destroy_fences(&self, fences: &[vk::Fence]) {
+        self.destroy_named(fences, |fence| unsafe {
+            self.device
+                .destroy_fence(fence, self.allocation_callbacks.as_ref())
+        });
+    }
+
+    /// Destroy views created with [`Device::create_image_views`].
+    pub fn destroy_image_views(&self, views: &[vk::ImageView]) {
+        self.destroy_named(views, |view| unsafe {
+            self.device
+                .destroy_image_view(view, self.allocation_callbacks.as_ref())
+        });
+    }
+
+    /// Stop tracking and destroy objects created by [`Device::create_named`].
+    fn destroy_named<H: vk::Handle<Repr = u64> + Copy>(&self, handles: &[H], destroy: impl Fn(H)) {
+        for &handle in handles {
+            self.live_objects().untrack(H::TYPE, handle.as_raw());
+            destroy(handle);
+        }
+    }
+
+    fn debug_label(name: &str, color: [f32; 4]) -> (CString, vk::DebugUtilsLabelEXT) {
+        let name = CString::new(name).unwrap_or_default();
+        let label = vk::DebugUtilsLabelEXT {
+            label_name: name.as_ptr(),
+            color,
+            ..Default::default()
+        };
+        (name, label)
+    }
+
+    /// Summarize the instance, device and queues that were created, for bug reports and
+    /// telemetry. Use [`crate::Swapchain::creation_summary`] to include the swapchain.
+    pub fn creation_summary(&self) -> crate::CreationSummary {
+        let properties = &self.physical_device.properties;
+
+        let queue_families = [
+            QueueType::Present,
+            QueueType::Graphics,
+            QueueType::Compute,
+            QueueType::Transfer,
+            QueueType::SparseBinding,
+        ]
+        .into_iter()
+        .filter_map(|queue| {
+            let (family, _) = self.get_queue(queue.clone()).ok()?;
+            Some((queue, family as u32))
+        })
+        .collect();
+
+        crate::CreationSummary {
+            device_name: self.physical_device.name.clone(),
+            device_type: properties.device_type,
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            driver_version: properties.driver_version,
+            driver: self.physical_device.driver.clone(),
+            device_api_version: Version::from(properties.api_version),
+            instance_api_version: self.instance.api_version,
+            instance_extensions: extension_names(&self.instance.enabled_extensions),
+            layers: extension_names(&self.instance.enabled_layers),
+            device_extensions: extension_names(&self.physical_device.extensions_to_enable),
+            queue_families,
+            swapchain: None,
+        }
+    }
+
+    /// Query `VK_EXT_device_fault` for the cause of a device loss.
+    ///
+    /// Returns `None` when the extension was not enabled with
+    /// [`DeviceBuilder::enable_device_fault`] or isn't supported by the device.
+    pub fn fault_info(&self) -> crate::Result<Option<crate::DeviceFault>> {
+        if !self.device_fault_enabled {
+            return Ok(None);
+        }
+
+        let mut counts = vk::DeviceFaultCountsEXT::default();
+        unsafe { self.device.get_device_fault_info_ext(&mut counts, None) }?;
+        if !self.device_fault_vendor_binary {
+            counts.vendor_binary_size = 0;
+        }
+
+        let mut addresses =
+            vec![vk::DeviceFaultAddressInfoEXT::default(); counts.address_info_count as usize];
+        let mut vendor_infos =
+            vec![vk::DeviceFaultVendorInfoEXT::default(); counts.vendor_info_count as usize];
+        let mut vendor_binary = vec![0u8; counts.vendor_binary_size as usize];
+        let mut info = vk::DeviceFaultInfoEXT {
+            address_infos: addresses.as_mut_ptr(),
+            vendor_infos: vendor_infos.as_mut_ptr(),
+            vendor_binary_data: if vendor_binary.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                vendor_binary.as_mut_ptr().cast()
+            },
+            ..Default::default()
+        };
+        unsafe {
+            self.device
+                .get_device_fault_info_ext(&mut counts, Some(&mut info))
+        }?;
+
+        addresses.truncate(counts.address_info_count as usize);
+        vendor_infos.truncate(counts.vendor_info_count as usize);
+        vendor_binary.truncate(counts.vendor_binary_size as usize);
+
+        Ok(Some(crate::DeviceFault {
+            description: info.description.to_string_lossy().into_owned(),
+            addresses,
+            vendor_infos: vendor_infos
+                .into_iter()
+                .map(|vendor_info| crate::DeviceFaultVendorInfo {
+                    description: vendor_info.description.to_string_lossy().into_owned(),
+                    fault_code: vendor_info.vendor_fault_code,
+                    fault_data: vendor_info.vendor_fault_data,
+                })
+                .collect(),
+            vendor_binary,
+        }))
+    }
+
+    /// Mark `queue` as in use by the current thread until the returned guard is dropped.
+    ///
+    /// With the `thread_audit` feature this panics when another thread is inside an access of
+    /// the same queue, catching unsynchronized submissions that otherwise show up as sporadic
+    /// device loss. The crate guards its own submissions and presents, wrap yours to have them
+    /// checked too. Does nothing without the feature.
+    pub fn audit_queue(&self, queue: vk::Queue) -> QueueAccess<'_> {
+        self.queue_audit.enter(queue);
+
+        QueueAccess {
+            device: self,
+            queue,
+        }
+    }
+
+    /// Open a debug label region on `queue` that is closed when the returned guard is dropped.
+    /// Does nothing when `VK_EXT_debug_utils` is not enabled.
+    pub fn queue_label_scope(
+        &self,
+        queue: vk::Queue,
+        name: &str,
+        color: [f32; 4],
+    ) -> QueueLabelScope<'_> {
+        let enabled = self.instance.debug_utils_enabled;
+        if enabled {
+            let (_name, label) = Self::debug_label(name, color);
+            unsafe {
+                self.instance
+                    .instance
+                    .queue_begin_debug_utils_label_ext(queue, &label)
+            };
+        }
+
+        QueueLabelScope {
+            device: self,
+            queue,
+            enabled,
+        }
+    }
+
+    /// Open a debug label region in `command_buffer` that is closed when the returned guard is
+    /// dropped. The command buffer must still be recording at that point. Does nothing when
+    /// `VK_EXT_debug_utils` is not enabled.
+    pub fn cmd_label_scope(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        name: &str,
+        color: [f32; 4],
+    ) -> CmdLabelScope<'_> {
+        let enabled = self.instance.debug_utils_enabled;
+        if enabled {
+            let (_name, label) = Self::debug_label(name, color);
+            unsafe {
+                self.instance
+                    .instance
+                    .cmd_begin_debug_utils_label_ext(command_buffer, &label)
+            };
+        }
+
+        CmdLabelScope {
+            device: self,
+            command_buffer,
+            enabled,
+        }
+    }
+
     pub fn destroy(&self) {
+        self.instance
+            .live_objects
+            .untrack(vk::ObjectType::DEVICE, self.device.handle().as_raw() as u64);
         unsafe {
             self.device
                 .destroy_device(self.allocation_callbacks.as_ref());
@@ -1604,6 +5064,65 @@ impl Device {
     }
 }
 
+/// Access to a queue checked by the `thread_audit` feature, ended when dropped. See
+/// [`Device::audit_queue`].
+#[derive(Debug)]
+#[must_use = "the access ends when the guard is dropped"]
+pub struct QueueAccess<'a> {
+    device: &'a Device,
+    queue: vk::Queue,
+}
+
+impl Drop for QueueAccess<'_> {
+    fn drop(&mut self) {
+        self.device.queue_audit.leave(self.queue);
+    }
+}
+
+/// Debug label region on a queue, ended when dropped. See [`Device::queue_label_scope`].
+#[derive(Debug)]
+#[must_use = "the label region ends when the scope is dropped"]
+pub struct QueueLabelScope<'a> {
+    device: &'a Device,
+    queue: vk::Queue,
+    enabled: bool,
+}
+
+impl Drop for QueueLabelScope<'_> {
+    fn drop(&mut self) {
+        if self.enabled {
+            unsafe {
+                self.device
+                    .instance
+                    .instance
+                    .queue_end_debug_utils_label_ext(self.queue)
+            };
+        }
+    }
+}
+
+/// Debug label region in a command buffer, ended when dropped. See [`Device::cmd_label_scope`].
+#[derive(Debug)]
+#[must_use = "the label region ends when the scope is dropped"]
+pub struct CmdLabelScope<'a> {
+    device: &'a Device,
+    command_buffer: vk::CommandBuffer,
+    enabled: bool,
+}
+
+impl Drop for CmdLabelScope<'_> {
+    fn drop(&mut self) {
+        if self.enabled {
+            unsafe {
+                self.device
+                    .instance
+                    .instance
+                    .cmd_end_debug_utils_label_ext(self.command_buffer)
+            };
+        }
+    }
+}
+
 impl AsRef<vulkanalia::Device> for Device {
     fn as_ref(&self) -> &vulkanalia::Device {
         &self.device
@@ -1617,3 +5136,437 @@ impl Deref for Device {
         &self.device
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(
+        name: &str,
+        device_type: vk::PhysicalDeviceType,
+        vendor_id: u32,
+        suitable: Suitable,
+    ) -> PhysicalDevice {
+        PhysicalDevice {
+            name: name.to_string(),
+            properties: vk::PhysicalDeviceProperties {
+                device_type,
+                vendor_id,
+                ..Default::default()
+            },
+            suitable,
+            ..Default::default()
+        }
+    }
+
+    /// A headless device on whatever driver the loader finds (lavapipe in CI), or `None` when
+    /// there is no Vulkan implementation and the test can't run.
+    fn software_device(
+        configure: impl FnOnce(&PhysicalDevice, DeviceBuilder) -> DeviceBuilder,
+    ) -> Option<(Arc<Instance>, Device)> {
+        let instance = crate::InstanceBuilder::new(None)
+            .headless(true)
+            .build()
+            .ok()?;
+        let physical_device = PhysicalDeviceSelector::compute_only(instance.clone())
+            .allow_software_rasterizer(true)
+            .select()
+            .ok()?;
+        let builder = DeviceBuilder::new(physical_device.clone(), instance.clone());
+        let device = configure(&physical_device, builder).build().unwrap();
+        Some((instance, device))
+    }
+
+    fn ranked_names(criteria: &SelectionCriteria, mut devices: Vec<PhysicalDevice>) -> Vec<String> {
+        devices.sort_by_key(|device| criteria.rank(device));
+        devices.into_iter().map(|device| device.name).collect()
+    }
+
+    #[test]
+    fn power_preference_ranks_after_suitability_and_type() {
+        let devices = vec![
+            device(
+                "intel igpu",
+                vk::PhysicalDeviceType::INTEGRATED_GPU,
+                0x8086,
+                Suitable::Yes,
+            ),
+            device(
+                "amd igpu",
+                vk::PhysicalDeviceType::INTEGRATED_GPU,
+                0x1002,
+                Suitable::Yes,
+            ),
+            device(
+                "nvidia dgpu",
+                vk::PhysicalDeviceType::DISCRETE_GPU,
+                0x10DE,
+                Suitable::Yes,
+            ),
+            device(
+                "partial dgpu",
+                vk::PhysicalDeviceType::DISCRETE_GPU,
+                0x10DE,
+                Suitable::Partial,
+            ),
+        ];
+
+        let low_power = SelectionCriteria {
+            power_preference: PowerPreference::LowPower,
+            preferred_device_type: PreferredDeviceType::Integrated,
+            ..Default::default()
+        };
+        assert_eq!(
+            ranked_names(&low_power, devices.clone()),
+            ["intel igpu", "amd igpu", "nvidia dgpu", "partial dgpu"]
+        );
+
+        // An explicit device type still wins over the power preference.
+        let low_power_discrete = SelectionCriteria {
+            preferred_device_type: PreferredDeviceType::Discrete,
+            ..low_power
+        };
+        assert_eq!(
+            ranked_names(&low_power_discrete, devices.clone()),
+            ["nvidia dgpu", "intel igpu", "amd igpu", "partial dgpu"]
+        );
+
+        let high_performance = SelectionCriteria {
+            power_preference: PowerPreference::HighPerformance,
+            preferred_device_type: PreferredDeviceType::Integrated,
+            ..Default::default()
+        };
+        assert_eq!(
+            ranked_names(&high_performance, devices),
+            ["amd igpu", "intel igpu", "nvidia dgpu", "partial dgpu"]
+        );
+    }
+
+    #[test]
+    fn preferred_device_first_then_ranked_fallbacks() {
+        let id = |byte| PhysicalDeviceId {
+            uuid: [byte; vk::UUID_SIZE],
+            luid: None,
+        };
+        let mut devices = vec![
+            device(
+                "intel igpu",
+                vk::PhysicalDeviceType::INTEGRATED_GPU,
+                0x8086,
+                Suitable::Yes,
+            ),
+            device(
+                "amd igpu",
+                vk::PhysicalDeviceType::INTEGRATED_GPU,
+                0x1002,
+                Suitable::Yes,
+            ),
+            device(
+                "nvidia dgpu",
+                vk::PhysicalDeviceType::DISCRETE_GPU,
+                0x10DE,
+                Suitable::Yes,
+            ),
+        ];
+        for (index, device) in devices.iter_mut().enumerate() {
+            device.device_id = Some(id(index as u8));
+        }
+
+        let criteria = SelectionCriteria {
+            power_preference: PowerPreference::HighPerformance,
+            preferred_device_type: PreferredDeviceType::Integrated,
+            preferred_device_id: Some(id(0)),
+            ..Default::default()
+        };
+        criteria.sort_devices(&mut devices);
+        assert_eq!(
+            devices.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(),
+            ["intel igpu", "amd igpu", "nvidia dgpu"]
+        );
+
+        let criteria = SelectionCriteria {
+            preferred_device_id: Some(id(2)),
+            ..criteria
+        };
+        criteria.sort_devices(&mut devices);
+        assert_eq!(
+            devices.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(),
+            ["nvidia dgpu", "amd igpu", "intel igpu"]
+        );
+    }
+
+    fn shading_rate_support() -> FragmentShadingRateSupport {
+        FragmentShadingRateSupport {
+            min_attachment_texel_size: vk::Extent2D {
+                width: 8,
+                height: 8,
+            },
+            max_attachment_texel_size: vk::Extent2D {
+                width: 32,
+                height: 32,
+            },
+            max_attachment_texel_size_aspect_ratio: 2,
+            attachment_fragment_shading_rate: true,
+            ..Default::default()
+        }
+    }
+
+    fn extent(width: u32, height: u32) -> vk::Extent2D {
+        vk::Extent2D { width, height }
+    }
+
+    #[test]
+    fn shading_rate_texel_size_within_limits() {
+        let support = shading_rate_support();
+        assert!(support.supports_attachment_texel_size(extent(8, 8)));
+        assert!(support.supports_attachment_texel_size(extent(32, 32)));
+        assert!(!support.supports_attachment_texel_size(extent(4, 8)));
+        assert!(!support.supports_attachment_texel_size(extent(64, 32)));
+    }
+
+    #[test]
+    fn shading_rate_texel_size_powers_of_two() {
+        let support = shading_rate_support();
+        assert!(support.supports_attachment_texel_size(extent(16, 16)));
+        assert!(!support.supports_attachment_texel_size(extent(12, 16)));
+        assert!(!support.supports_attachment_texel_size(extent(16, 24)));
+    }
+
+    #[test]
+    fn shading_rate_texel_size_aspect_ratio() {
+        let support = shading_rate_support();
+        assert!(support.supports_attachment_texel_size(extent(16, 8)));
+        assert!(support.supports_attachment_texel_size(extent(8, 16)));
+        assert!(!support.supports_attachment_texel_size(extent(32, 8)));
+        assert!(!support.supports_attachment_texel_size(extent(8, 32)));
+    }
+
+    #[test]
+    fn shading_rate_texel_size_needs_attachment_feature() {
+        let support = FragmentShadingRateSupport {
+            attachment_fragment_shading_rate: false,
+            ..shading_rate_support()
+        };
+        assert!(!support.supports_attachment_texel_size(extent(8, 8)));
+    }
+
+    #[test]
+    fn glob_leading_and_trailing_star() {
+        assert!(glob_matches("AMD Radeon RX 7*", "AMD Radeon RX 7900 XTX"));
+        assert!(glob_matches("*RTX 4090", "NVIDIA GeForce RTX 4090"));
+        assert!(glob_matches("*GeForce*", "NVIDIA GeForce RTX 4090"));
+        assert!(glob_matches("*", ""));
+    }
+
+    #[test]
+    fn glob_question_mark_matches_one_character() {
+        assert!(glob_matches("RTX 40?0", "RTX 4090"));
+        assert!(!glob_matches("RTX 40?0", "RTX 400"));
+        assert!(!glob_matches("?", ""));
+    }
+
+    #[test]
+    fn glob_consecutive_stars() {
+        assert!(glob_matches("AMD**7900", "AMD Radeon RX 7900"));
+        assert!(glob_matches("**", "anything"));
+        assert!(glob_matches("a*?*b", "axb"));
+        assert!(!glob_matches("a*?*b", "ab"));
+    }
+
+    #[test]
+    fn glob_no_match() {
+        assert!(!glob_matches("AMD*", "NVIDIA GeForce RTX 4090"));
+        assert!(!glob_matches("*7900", "AMD Radeon RX 7900 XTX"));
+        assert!(!glob_matches("RTX 4090", "RTX 4080"));
+    }
+
+    #[test]
+    fn glob_empty_pattern_matches_only_empty_name() {
+        assert!(glob_matches("", ""));
+        assert!(!glob_matches("", "RTX 4090"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn name_filter_rejects_invalid_regex() {
+        let criteria = SelectionCriteria {
+            name: "RX 7(".to_string(),
+            name_match: NameMatch::Regex,
+            ..Default::default()
+        };
+        assert!(matches!(
+            NameFilter::new(&criteria),
+            Err(crate::Error::Config(crate::ConfigError::InvalidNameRegex(
+                _
+            )))
+        ));
+
+        let criteria = SelectionCriteria {
+            name: "RX 7[89]00".to_string(),
+            name_match: NameMatch::Regex,
+            ..Default::default()
+        };
+        let filter = NameFilter::new(&criteria).unwrap();
+        assert!(filter.matches("AMD Radeon RX 7900 XTX"));
+        assert!(!filter.matches("AMD Radeon RX 6900 XT"));
+    }
+
+    #[test]
+    fn queue_descriptions_default_to_one_queue() {
+        let families = [
+            vk::QueueFamilyProperties {
+                queue_count: 4,
+                ..Default::default()
+            },
+            vk::QueueFamilyProperties {
+                queue_count: 1,
+                ..Default::default()
+            },
+        ];
+
+        let descriptions = queue_descriptions(&families, &[(0, vec![1.0, 0.5])]).unwrap();
+        assert_eq!(descriptions, [(0, vec![1.0, 0.5]), (1, vec![1.0])]);
+
+        assert!(matches!(
+            queue_descriptions(&families, &[(2, vec![1.0])]),
+            Err(crate::Error::Config(
+                crate::ConfigError::InvalidQueueFamilyOverride { index: 2, count: 2 }
+            ))
+        ));
+        assert!(matches!(
+            queue_descriptions(&families, &[(1, vec![1.0, 1.0])]),
+            Err(crate::Error::Config(
+                crate::ConfigError::InvalidQueueCount {
+                    family: 1,
+                    requested: 2,
+                    available: 1,
+                }
+            ))
+        ));
+        assert!(matches!(
+            queue_descriptions(&families, &[(0, vec![])]),
+            Err(crate::Error::Config(
+                crate::ConfigError::InvalidQueueCount { requested: 0, .. }
+            ))
+        ));
+        assert!(matches!(
+            queue_descriptions(&families, &[(0, vec![1.5])]),
+            Err(crate::Error::Config(
+                crate::ConfigError::InvalidQueuePriority { family: 0 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn get_queue_at_returns_extra_queues() {
+        let mut family = None;
+        let Some((instance, device)) = software_device(|physical_device, builder| {
+            let index = physical_device
+                .queue_families()
+                .iter()
+                .position(|properties| {
+                    properties.queue_flags.contains(vk::QueueFlags::COMPUTE)
+                        && properties.queue_count >= 2
+                });
+            family = index;
+            match index {
+                Some(index) => builder.queue_priorities(index as u32, vec![1.0, 0.5]),
+                None => builder,
+            }
+        }) else {
+            return;
+        };
+
+        if let Some(family) = family {
+            let (compute_family, first) = device.get_queue(QueueType::Compute).unwrap();
+            if compute_family == family {
+                let (second_family, second) = device.get_queue_at(QueueType::Compute, 1).unwrap();
+                assert_eq!(second_family, family);
+                assert_ne!(first, second);
+                assert!(matches!(
+                    device.get_queue_at(QueueType::Compute, 2),
+                    Err(crate::Error::Config(
+                        crate::ConfigError::QueueIndexOutOfBounds
+                    ))
+                ));
+            }
+        }
+
+        device.destroy();
+        instance.destroy();
+    }
+
+    #[test]
+    fn set_surface_resolves_present_again() {
+        use vulkanalia::vk::{
+            ExtHeadlessSurfaceExtensionInstanceCommands, KhrSurfaceExtensionInstanceCommands,
+        };
+
+        let Ok(instance) = crate::InstanceBuilder::new(None)
+            .headless(true)
+            .enable_extension(vk::EXT_HEADLESS_SURFACE_EXTENSION.name)
+            .build()
+        else {
+            return;
+        };
+        let Ok(physical_device) = PhysicalDeviceSelector::compute_only(instance.clone())
+            .allow_software_rasterizer(true)
+            .select()
+        else {
+            instance.destroy();
+            return;
+        };
+        let mut device = DeviceBuilder::new(physical_device, instance.clone())
+            .build()
+            .unwrap();
+        assert!(matches!(
+            device.get_queue(QueueType::Present),
+            Err(crate::Error::Queue(crate::QueueError::PresentUnavailable))
+        ));
+
+        let surface = unsafe {
+            instance
+                .instance
+                .create_headless_surface_ext(&vk::HeadlessSurfaceCreateInfoEXT::default(), None)
+        }
+        .unwrap();
+        device.set_surface(Some(surface));
+        assert!(device.get_queue(QueueType::Present).is_ok());
+
+        device.set_surface(None);
+        assert!(device.get_queue(QueueType::Present).is_err());
+
+        device.destroy();
+        unsafe { instance.instance.destroy_surface_khr(surface, None) };
+        instance.destroy();
+    }
+
+    #[test]
+    fn sync_pool_recycles_and_tracks_objects() {
+        let Some((instance, device)) = software_device(|_, builder| builder) else {
+            return;
+        };
+        let device = Arc::new(device);
+
+        let mut pool = device.create_sync_pool("frame");
+        let semaphore = pool.semaphore().unwrap();
+        let fence = pool.fence().unwrap();
+        assert_ne!(pool.semaphore().unwrap(), semaphore);
+        assert_eq!(pool.in_use(), (2, 1));
+        let report = device.live_objects().report().unwrap();
+        assert!(report.contains("frame semaphore[1]"));
+        assert!(report.contains("frame fence[0]"));
+
+        pool.reset().unwrap();
+        assert_eq!(pool.in_use(), (0, 0));
+        assert_eq!(pool.fence().unwrap(), fence);
+
+        pool.destroy();
+        assert_eq!(pool.in_use(), (0, 0));
+        let report = device.live_objects().report().unwrap_or_default();
+        assert!(!report.contains("frame"));
+
+        device.destroy();
+        instance.destroy();
+    }
+}
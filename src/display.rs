@@ -0,0 +1,129 @@
+use std::ffi::CStr;
+use vulkanalia::vk::{self, Handle, HasBuilder, InstanceV1_0, KhrDisplayExtensionInstanceCommands};
+
+/// A display attached to a physical device, as reported by `VK_KHR_display`.
+#[derive(Debug, Clone)]
+pub struct DisplayInfo {
+    pub physical_device: vk::PhysicalDevice,
+    pub display: vk::DisplayKHR,
+    pub name: String,
+    pub physical_resolution: vk::Extent2D,
+    pub modes: Vec<vk::DisplayModePropertiesKHR>,
+}
+
+/// Which display and mode to present to when rendering without a window system.
+///
+/// `display_index` indexes the displays of all physical devices, in the order returned by
+/// [`Instance::displays`](crate::Instance::displays). Without a `mode_index` the mode with the
+/// largest visible region is used, preferring the highest refresh rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplaySelection {
+    pub display_index: usize,
+    pub mode_index: Option<usize>,
+}
+
+pub(crate) fn enumerate_displays(
+    instance: &vulkanalia::Instance,
+) -> crate::Result<Vec<DisplayInfo>> {
+    let mut displays = vec![];
+
+    for physical_device in unsafe { instance.enumerate_physical_devices() }? {
+        let properties =
+            unsafe { instance.get_physical_device_display_properties_khr(physical_device) }?;
+
+        for properties in properties {
+            let name = if properties.display_name.is_null() {
+                String::new()
+            } else {
+                unsafe { CStr::from_ptr(properties.display_name) }
+                    .to_string_lossy()
+                    .into_owned()
+            };
+            let modes = unsafe {
+                instance.get_display_mode_properties_khr(physical_device, properties.display)
+            }?;
+
+            displays.push(DisplayInfo {
+                physical_device,
+                display: properties.display,
+                name,
+                physical_resolution: properties.physical_resolution,
+                modes,
+            });
+        }
+    }
+
+    Ok(displays)
+}
+
+pub(crate) fn create_display_surface(
+    instance: &vulkanalia::Instance,
+    selection: DisplaySelection,
+    allocation_callbacks: Option<&vk::AllocationCallbacks>,
+) -> crate::Result<vk::SurfaceKHR> {
+    let displays = enumerate_displays(instance)?;
+    let Some(display) = displays.get(selection.display_index) else {
+        return Err(crate::InstanceError::DisplayNotFound(selection.display_index).into());
+    };
+
+    let mode = match selection.mode_index {
+        Some(index) => display.modes.get(index),
+        None => display.modes.iter().max_by_key(|mode| {
+            let region = mode.parameters.visible_region;
+            (
+                region.width as u64 * region.height as u64,
+                mode.parameters.refresh_rate,
+            )
+        }),
+    }
+    .ok_or(crate::InstanceError::DisplayModeNotFound)?;
+
+    let planes = unsafe {
+        instance.get_physical_device_display_plane_properties_khr(display.physical_device)
+    }?;
+    let plane = (0..planes.len() as u32)
+        .find(|&plane_index| {
+            let current = planes[plane_index as usize].current_display;
+            (current.is_null() || current == display.display)
+                && unsafe {
+                    instance.get_display_plane_supported_displays_khr(
+                        display.physical_device,
+                        plane_index,
+                    )
+                }
+                .is_ok_and(|supported| supported.contains(&display.display))
+        })
+        .ok_or(crate::InstanceError::NoCompatibleDisplayPlane)?;
+
+    let capabilities = unsafe {
+        instance.get_display_plane_capabilities_khr(
+            display.physical_device,
+            mode.display_mode,
+            plane,
+        )
+    }?;
+    let alpha_mode = [
+        vk::DisplayPlaneAlphaFlagsKHR::OPAQUE,
+        vk::DisplayPlaneAlphaFlagsKHR::GLOBAL,
+        vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL,
+        vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL_PREMULTIPLIED,
+    ]
+    .into_iter()
+    .find(|alpha| capabilities.supported_alpha.contains(*alpha))
+    .unwrap_or(vk::DisplayPlaneAlphaFlagsKHR::OPAQUE);
+
+    let create_info = vk::DisplaySurfaceCreateInfoKHR::builder()
+        .display_mode(mode.display_mode)
+        .plane_index(plane)
+        .plane_stack_index(planes[plane as usize].current_stack_index)
+        .transform(vk::SurfaceTransformFlagsKHR::IDENTITY)
+        .global_alpha(1.0)
+        .alpha_mode(alpha_mode)
+        .image_extent(mode.parameters.visible_region);
+
+    let surface =
+        unsafe { instance.create_display_plane_surface_khr(&create_info, allocation_callbacks) }?;
+
+    Ok(surface)
+}
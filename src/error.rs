@@ -17,6 +17,101 @@ pub enum Error {
     Vulkan(#[from] vulkanalia::vk::Result),
     #[error("Vulkan error: {0}")]
     VulkanErr(#[from] vk::ErrorCode),
+    #[error("Creation error: {0}")]
+    Create(#[from] CreateError),
+    #[error("Configuration error: {0}")]
+    Config(#[from] ConfigError),
+    #[error("{0}")]
+    DeviceFault(#[from] DeviceFault),
+    #[error("No memory type with {flags:?} in type bits {type_bits:#b}")]
+    NoSuitableMemoryType {
+        type_bits: u32,
+        flags: vk::MemoryPropertyFlags,
+    },
+}
+
+/// Whether an error is a bug in how the crate was used or a limitation of the system it runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The caller used the API wrong (conflicting options, missing surface, invalid queue
+    /// family). Report these as bugs.
+    Config,
+    /// The driver, hardware or window system can't satisfy the request. Surface these to the
+    /// end user.
+    Environment,
+}
+
+impl Error {
+    /// Classify the error as a configuration bug or an environment failure.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Config(_) => ErrorCategory::Config,
+            _ => ErrorCategory::Environment,
+        }
+    }
+
+    /// Split the error into the configuration bug, or the environment failure on the error side.
+    pub fn into_config(self) -> std::result::Result<ConfigError, EnvironmentError> {
+        match self {
+            Error::Config(error) => Ok(error),
+            error => Err(EnvironmentError(error)),
+        }
+    }
+}
+
+/// Errors caused by using the builders wrong, independent of the system they run on.
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum ConfigError {
+    #[error("Conflicting options: {0}")]
+    ConflictingOptions(String),
+    #[error("Queue family override {index} is out of range, the device has {count} families")]
+    InvalidQueueFamilyOverride { index: u32, count: u32 },
+    #[error("{requested} queues requested in queue family {family}, which has {available}")]
+    InvalidQueueCount {
+        family: u32,
+        requested: u32,
+        available: u32,
+    },
+    #[error("Queue priorities of queue family {family} must be between 0.0 and 1.0")]
+    InvalidQueuePriority { family: u32 },
+    #[error("Invalid device name regular expression: {0}")]
+    InvalidNameRegex(String),
+    #[error("Image {0} was presented without being acquired")]
+    ImageNotAcquired(u32),
+    #[error("No surface provided")]
+    NoSurfaceProvided,
+    #[error("Surface handle not provided")]
+    SurfaceHandleNotProvided,
+    #[error("Queue index out of bounds")]
+    QueueIndexOutOfBounds,
+    #[error("Image index {index} out of bounds, there are {count} images")]
+    ImageIndexOutOfBounds { index: u32, count: u32 },
+    #[error("Invalid queue family index")]
+    InvalidQueueFamilyIndex,
+}
+
+/// An [`Error`](enum@Error) caused by the driver, hardware or window system rather than by how
+/// the crate was used, see [`Error::into_config`].
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct EnvironmentError(Error);
+
+impl EnvironmentError {
+    pub fn into_inner(self) -> Error {
+        self.0
+    }
+}
+
+impl AsRef<Error> for EnvironmentError {
+    fn as_ref(&self) -> &Error {
+        &self.0
+    }
+}
+
+impl From<EnvironmentError> for Error {
+    fn from(error: EnvironmentError) -> Self {
+        error.0
+    }
 }
 
 #[derive(Debug, PartialOrd, PartialEq, Eq, Ord, Error)]
@@ -37,18 +132,28 @@ pub enum InstanceError {
     FailedCreateInstance,
     #[error("Failed to create debug messenger")]
     FailedCreateDebugMessenger,
-    #[error("Failed to find requested layers: {0:#?}")]
+    #[error("Failed to open debug output: {0}")]
+    FailedOpenDebugOutput(String),
+    #[error("Failed to find requested layers: {}", join_names(.0))]
     RequestedLayersNotPresent(Vec<vk::ExtensionName>),
-    #[error("Failed to find requested extensions: {0:#?}")]
-    RequestedExtensionsNotPresent(Vec<vk::ExtensionName>),
-    #[error("Failed to find windowing extensions: {0:#?}")]
-    WindowingExtensionsNotPresent(Vec<vk::ExtensionName>),
+    #[error("Failed to find requested extensions: {}", join_names(.0))]
+    RequestedExtensionsNotPresent(Vec<MissingExtension>),
+    #[error("Failed to find windowing extensions: {}", join_names(.0))]
+    WindowingExtensionsNotPresent(Vec<MissingExtension>),
+    #[error("Display {0} not found")]
+    DisplayNotFound(usize),
+    #[error("Display mode not found")]
+    DisplayModeNotFound,
+    #[error("No display plane can present to the selected display")]
+    NoCompatibleDisplayPlane,
+    #[error("Window handle unavailable: {0}")]
+    WindowHandleUnavailable(String),
+    #[error("Failed to query the window's instance extensions: {0}")]
+    WindowExtensionsUnavailable(String),
 }
 
 #[derive(Debug, PartialOrd, PartialEq, Eq, Ord, Error)]
 pub enum PhysicalDeviceError {
-    #[error("No surface provided")]
-    NoSurfaceProvided,
     #[error("Failed to enumerate physical devices")]
     FailedToEnumeratePhysicalDevices,
     #[error("No physical devices found")]
@@ -67,12 +172,104 @@ pub enum QueueError {
     ComputeUnavailable,
     #[error("Transfer unavailable")]
     TransferUnavailable,
-    #[error("Queue index out of bounds")]
-    QueueIndexOutOfBounds,
-    #[error("Invalid queue family index")]
-    InvalidQueueFamilyIndex,
+    #[error("Sparse binding unavailable")]
+    SparseBindingUnavailable,
+}
+
+/// An instance extension that is not available, see [`InstanceError::RequestedExtensionsNotPresent`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MissingExtension {
+    pub name: vk::ExtensionName,
+    /// Installed layers that expose the extension but were not enabled.
+    pub provided_by: Vec<vk::ExtensionName>,
+}
+
+impl std::fmt::Display for MissingExtension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.provided_by.is_empty() {
+            write!(
+                f,
+                " (provided by layer {}, which is not enabled)",
+                join_names(&self.provided_by)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn join_names(names: &[impl std::fmt::Display]) -> String {
+    names
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Context attached to a failed object creation so logs identify exactly which object failed.
+#[derive(Debug, PartialEq, Eq, Error)]
+#[error("Failed to create {kind:?} \"{name}\" ({details}): {code}")]
+pub struct CreateError {
+    /// Kind of object that was being created.
+    pub kind: vk::ObjectType,
+    /// Debug name given to the object.
+    pub name: String,
+    /// Short summary of the creation parameters.
+    pub details: String,
+    /// Error code returned by the driver.
+    pub code: vk::ErrorCode,
 }
 
+/// Vendor specific fault record reported by `VK_EXT_device_fault`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceFaultVendorInfo {
+    pub description: String,
+    pub fault_code: u64,
+    pub fault_data: u64,
+}
+
+/// Post-mortem information about a lost device, see [`crate::Device::fault_info`].
+///
+/// Displays as a multi-line report meant for logs. The vendor binary is only retrieved when
+/// the device supports `deviceFaultVendorBinary`; it is meant for vendor tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceFault {
+    pub description: String,
+    pub addresses: Vec<vk::DeviceFaultAddressInfoEXT>,
+    pub vendor_infos: Vec<DeviceFaultVendorInfo>,
+    pub vendor_binary: Vec<u8>,
+}
+
+impl std::fmt::Display for DeviceFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Device fault: {}", self.description)?;
+        for address in &self.addresses {
+            write!(
+                f,
+                "\n  {:?} at {:#x} (precision {:#x})",
+                address.address_type, address.reported_address, address.address_precision
+            )?;
+        }
+        for vendor_info in &self.vendor_infos {
+            write!(
+                f,
+                "\n  vendor fault {:#x} (data {:#x}): {}",
+                vendor_info.fault_code, vendor_info.fault_data, vendor_info.description
+            )?;
+        }
+        if !self.vendor_binary.is_empty() {
+            write!(
+                f,
+                "\n  {} bytes of vendor binary data",
+                self.vendor_binary.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DeviceFault {}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct FormatError {
     pub available: Vec<vk::SurfaceFormatKHR>,
@@ -81,12 +278,12 @@ pub struct FormatError {
 
 #[derive(Debug, PartialEq, Eq, Error)]
 pub enum SwapchainError {
-    #[error("Surface handle not provided")]
-    SurfaceHandleNotProvided,
+    #[error("Queue family {queue_family} can't present to the surface")]
+    PresentNotSupported { queue_family: u32 },
     #[error("Failed query surface support details")]
     FailedQuerySurfaceSupportDetails,
-    #[error("Failed to create swapchain")]
-    FailedCreateSwapchain,
+    #[error("Failed to create swapchain: {0}")]
+    FailedCreateSwapchain(vk::ErrorCode),
     #[error("Failed to get swapchain images")]
     FailedGetSwapchainImages,
     #[error("Failed to create swapchain image views")]
@@ -95,8 +292,48 @@ pub enum SwapchainError {
     RequiredMinImageCountTooLow,
     #[error("Required usage not supported")]
     RequiredUsageNotSupported,
+    #[error("{requested} image array layers requested but the surface supports at most {max}")]
+    ImageArrayLayersNotSupported { requested: u32, max: u32 },
+    #[error("Pre-transform {0:?} not supported by the surface")]
+    PreTransformNotSupported(vk::SurfaceTransformFlagsKHR),
+    #[error("Composite alpha {0:?} not supported by the surface")]
+    CompositeAlphaNotSupported(vk::CompositeAlphaFlagsKHR),
     #[error("No suitable desired format")]
     NoSuitableDesiredFormat(FormatError),
+    #[error("Present mode {0:?} not supported by the surface")]
+    PresentModeNotSupported(vk::PresentModeKHR),
+    #[error("Swapchain configuration not supported by the surface: {explanation} ({code})")]
+    IncompatibleConfiguration {
+        explanation: String,
+        code: vk::ErrorCode,
+    },
+    /// The surface has a zero width or height, usually because the window is minimized. Skip
+    /// rendering and build again once the window has a size.
+    #[error("Surface has a zero-sized extent")]
+    ZeroExtent,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_config_splits_by_category() {
+        let config = Error::from(ConfigError::NoSurfaceProvided);
+        assert_eq!(config.category(), ErrorCategory::Config);
+        assert_eq!(
+            config.into_config().unwrap(),
+            ConfigError::NoSurfaceProvided
+        );
+
+        let environment = Error::from(QueueError::GraphicsUnavailable);
+        assert_eq!(environment.category(), ErrorCategory::Environment);
+        let environment = environment.into_config().unwrap_err();
+        assert!(matches!(
+            environment.into_inner(),
+            Error::Queue(QueueError::GraphicsUnavailable)
+        ));
+    }
+}
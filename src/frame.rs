@@ -0,0 +1,78 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// A pooled subsystem whose per-frame allocations can be recycled once the GPU has finished
+/// with the frame (descriptor allocators, command pools, sync object pools...).
+pub trait Resettable: Send {
+    /// Recycle everything handed out since the last reset.
+    fn reset(&mut self) -> crate::Result<()>;
+}
+
+impl<F> Resettable for F
+where
+    F: FnMut() -> crate::Result<()> + Send,
+{
+    fn reset(&mut self) -> crate::Result<()> {
+        self()
+    }
+}
+
+/// Per-frame lifecycle hook collection.
+///
+/// Keep one `FrameContext` per frame in flight, attach the pooled subsystems used by that frame
+/// and call [`FrameContext::reset`] after waiting on the frame's fence. Subsystems are shared
+/// with the frame, so they stay usable after attaching them:
+///
+/// ``` no_run
+/// # fn run(device: std::sync::Arc<vulkanalia_bootstrap::Device>) -> vulkanalia_bootstrap::Result<()> {
+/// use std::sync::{Arc, Mutex};
+/// use vulkanalia_bootstrap::FrameContext;
+///
+/// let sync_pool = Arc::new(Mutex::new(device.create_sync_pool("frame 0")));
+/// let mut frame = FrameContext::new();
+/// frame.attach(sync_pool.clone());
+///
+/// let _image_available = sync_pool.lock().unwrap().semaphore()?;
+/// // Submit, present and wait on the frame's fence, then:
+/// frame.reset()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct FrameContext {
+    resettables: Vec<Arc<Mutex<dyn Resettable>>>,
+}
+
+impl Debug for FrameContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameContext")
+            .field("resettables", &self.resettables.len())
+            .finish()
+    }
+}
+
+impl FrameContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a subsystem to be reset together with this frame. The caller keeps its own
+    /// handle to keep using the subsystem during the frame.
+    pub fn attach(&mut self, resettable: Arc<Mutex<dyn Resettable>>) {
+        self.resettables.push(resettable);
+    }
+
+    /// Reset every attached subsystem in the order they were attached.
+    /// Stops at, and returns, the first error.
+    #[cfg_attr(feature = "enable_tracing", tracing::instrument(skip(self)))]
+    pub fn reset(&mut self) -> crate::Result<()> {
+        for resettable in &self.resettables {
+            resettable.lock().unwrap().reset()?;
+        }
+
+        #[cfg(feature = "enable_tracing")]
+        tracing::trace!(count = self.resettables.len(), "Reset frame resources");
+
+        Ok(())
+    }
+}
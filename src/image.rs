@@ -0,0 +1,512 @@
+use crate::Device;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, Handle, HasBuilder};
+
+/// Description of an image for [`Device::create_image`].
+#[derive(Debug, Clone, Copy)]
+pub struct ImageDesc {
+    pub format: vk::Format,
+    /// A depth larger than 1 creates a 3D image, which can't have more than one array layer.
+    pub extent: vk::Extent3D,
+    pub usage: vk::ImageUsageFlags,
+    /// Number of mip levels, 0 for the full chain down to 1x1.
+    pub mip_levels: u32,
+    pub array_layers: u32,
+    pub samples: vk::SampleCountFlags,
+    pub memory_flags: vk::MemoryPropertyFlags,
+    /// See [`Device::allocate_memory_with_priority`].
+    pub priority: f32,
+    /// Add the usage and check the format support needed by [`Device::cmd_generate_mips`].
+    pub generate_mips: bool,
+}
+
+impl ImageDesc {
+    /// A single-layer, single-sample 2D image with one mip level in device local memory.
+    pub fn new_2d(format: vk::Format, extent: vk::Extent2D, usage: vk::ImageUsageFlags) -> Self {
+        Self {
+            format,
+            extent: vk::Extent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            },
+            usage,
+            mip_levels: 1,
+            array_layers: 1,
+            samples: vk::SampleCountFlags::_1,
+            memory_flags: vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            priority: 0.5,
+            generate_mips: false,
+        }
+    }
+
+    /// Allocate the full mip chain and prepare the image for [`Device::cmd_generate_mips`].
+    pub fn with_mips(mut self) -> Self {
+        self.mip_levels = 0;
+        self.generate_mips = true;
+        self
+    }
+
+    pub fn array_layers(mut self, array_layers: u32) -> Self {
+        self.array_layers = array_layers;
+        self
+    }
+
+    pub fn samples(mut self, samples: vk::SampleCountFlags) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    pub fn memory_flags(mut self, memory_flags: vk::MemoryPropertyFlags) -> Self {
+        self.memory_flags = memory_flags;
+        self
+    }
+
+    pub fn priority(mut self, priority: f32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Type of the image and its view.
+    fn types(&self) -> crate::Result<(vk::ImageType, vk::ImageViewType)> {
+        match (self.extent.depth > 1, self.array_layers > 1) {
+            (true, true) => Err(crate::ConfigError::ConflictingOptions(format!(
+                "a 3D image (depth {}) can't have {} array layers",
+                self.extent.depth, self.array_layers
+            ))
+            .into()),
+            (true, false) => Ok((vk::ImageType::_3D, vk::ImageViewType::_3D)),
+            (false, true) => Ok((vk::ImageType::_2D, vk::ImageViewType::_2D_ARRAY)),
+            (false, false) => Ok((vk::ImageType::_2D, vk::ImageViewType::_2D)),
+        }
+    }
+
+    fn resolved_mip_levels(&self) -> u32 {
+        match self.mip_levels {
+            0 => {
+                let largest = self
+                    .extent
+                    .width
+                    .max(self.extent.height)
+                    .max(self.extent.depth)
+                    .max(1);
+                u32::BITS - largest.leading_zeros()
+            }
+            levels => levels,
+        }
+    }
+}
+
+/// An image with its bound memory and a view over all mip levels and layers, created by
+/// [`Device::create_image`].
+#[derive(Debug, Clone, Copy)]
+pub struct AllocatedImage {
+    pub image: vk::Image,
+    pub memory: vk::DeviceMemory,
+    pub view: vk::ImageView,
+    pub format: vk::Format,
+    pub extent: vk::Extent3D,
+    pub mip_levels: u32,
+    pub array_layers: u32,
+    pub aspect: vk::ImageAspectFlags,
+}
+
+impl AllocatedImage {
+    /// Destroy the view and image and free the memory. The GPU must no longer use them.
+    pub fn destroy(&self, device: &Device) {
+        let live_objects = device.live_objects();
+        live_objects.untrack(vk::ObjectType::IMAGE_VIEW, self.view.as_raw());
+        live_objects.untrack(vk::ObjectType::IMAGE, self.image.as_raw());
+
+        let allocation_callbacks = device.allocation_callbacks();
+        unsafe {
+            device.destroy_image_view(self.view, allocation_callbacks);
+            device
+                .device()
+                .destroy_image(self.image, allocation_callbacks);
+            device.free_memory(self.memory, allocation_callbacks);
+        }
+    }
+}
+
+/// Aspects covered by `format`.
+fn format_aspect(format: vk::Format) -> vk::ImageAspectFlags {
+    match format {
+        vk::Format::D16_UNORM | vk::Format::X8_D24_UNORM_PACK32 | vk::Format::D32_SFLOAT => {
+            vk::ImageAspectFlags::DEPTH
+        }
+        vk::Format::S8_UINT => vk::ImageAspectFlags::STENCIL,
+        vk::Format::D16_UNORM_S8_UINT
+        | vk::Format::D24_UNORM_S8_UINT
+        | vk::Format::D32_SFLOAT_S8_UINT => {
+            vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL
+        }
+        _ => vk::ImageAspectFlags::COLOR,
+    }
+}
+
+/// Optimal tiling features `format` needs for `usage`, plus blitting with linear filtering when
+/// generating mips.
+fn required_format_features(
+    usage: vk::ImageUsageFlags,
+    generate_mips: bool,
+) -> vk::FormatFeatureFlags {
+    let mut features = vk::FormatFeatureFlags::empty();
+    for (image_usage, feature) in [
+        (
+            vk::ImageUsageFlags::SAMPLED,
+            vk::FormatFeatureFlags::SAMPLED_IMAGE,
+        ),
+        (
+            vk::ImageUsageFlags::STORAGE,
+            vk::FormatFeatureFlags::STORAGE_IMAGE,
+        ),
+        (
+            vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            vk::FormatFeatureFlags::COLOR_ATTACHMENT,
+        ),
+        (
+            vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+            vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT,
+        ),
+    ] {
+        if usage.contains(image_usage) {
+            features |= feature;
+        }
+    }
+    if generate_mips {
+        features |= vk::FormatFeatureFlags::BLIT_SRC
+            | vk::FormatFeatureFlags::BLIT_DST
+            | vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR;
+    }
+
+    features
+}
+
+impl Device {
+    /// Create an image, allocate and bind memory for it and create a view over all of it.
+    ///
+    /// Fails with `VK_ERROR_FORMAT_NOT_SUPPORTED` when the format lacks the optimal tiling
+    /// features the usage (and mip generation) needs, with [`crate::Error::NoSuitableMemoryType`]
+    /// when no memory type has `memory_flags`, and with `ConfigError::ConflictingOptions` for a
+    /// 3D image with more than one array layer.
+    pub fn create_image(&self, name: &str, desc: &ImageDesc) -> crate::Result<AllocatedImage> {
+        let create_error = |details: String, code| crate::CreateError {
+            kind: vk::ObjectType::IMAGE,
+            name: name.to_string(),
+            details,
+            code,
+        };
+
+        let (image_type, view_type) = desc.types()?;
+        let mut usage = desc.usage;
+        if desc.generate_mips {
+            usage |= vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::TRANSFER_DST;
+        }
+
+        let required = required_format_features(usage, desc.generate_mips);
+        let supported = self.format_properties(desc.format).optimal_tiling_features;
+        if !supported.contains(required) {
+            return Err(create_error(
+                format!(
+                    "{:?} lacks {:?} with optimal tiling",
+                    desc.format,
+                    required & !supported
+                ),
+                vk::ErrorCode::FORMAT_NOT_SUPPORTED,
+            )
+            .into());
+        }
+
+        let mip_levels = desc.resolved_mip_levels();
+        let aspect = format_aspect(desc.format);
+        let details = format!(
+            "{:?} {}x{}x{}, {mip_levels} mips, {} layers, {usage:?}",
+            desc.format,
+            desc.extent.width,
+            desc.extent.height,
+            desc.extent.depth,
+            desc.array_layers
+        );
+        let allocation_callbacks = self.allocation_callbacks();
+
+        let image_info = vk::ImageCreateInfo::builder()
+            .image_type(image_type)
+            .format(desc.format)
+            .extent(desc.extent)
+            .mip_levels(mip_levels)
+            .array_layers(desc.array_layers)
+            .samples(desc.samples)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+        let image = unsafe {
+            self.device()
+                .create_image(&image_info, allocation_callbacks)
+        }
+        .map_err(|code| create_error(details.clone(), code))?;
+
+        let requirements = unsafe { self.get_image_memory_requirements(image) };
+        let memory = self
+            .physical_device()
+            .memory_type_index(requirements.memory_type_bits, desc.memory_flags)
+            .ok_or(crate::Error::NoSuitableMemoryType {
+                type_bits: requirements.memory_type_bits,
+                flags: desc.memory_flags,
+            })
+            .and_then(|memory_type_index| {
+                self.allocate_memory_with_priority(
+                    requirements.size,
+                    memory_type_index,
+                    desc.priority,
+                )
+            });
+        let memory = match memory {
+            Ok(memory) => memory,
+            Err(e) => {
+                unsafe { self.device().destroy_image(image, allocation_callbacks) };
+                return Err(e);
+            }
+        };
+
+        let view_info = vk::ImageViewCreateInfo::builder()
+            .image(image)
+            .view_type(view_type)
+            .format(desc.format)
+            .subresource_range(
+                vk::ImageSubresourceRange::builder()
+                    .aspect_mask(aspect)
+                    .level_count(mip_levels)
+                    .layer_count(desc.array_layers),
+            );
+        let view = unsafe { self.bind_image_memory(image, memory, 0) }
+            .and_then(|_| unsafe { self.create_image_view(&view_info, allocation_callbacks) })
+            .map_err(|code| create_error(details, code));
+        let view = match view {
+            Ok(view) => view,
+            Err(e) => {
+                unsafe {
+                    self.device().destroy_image(image, allocation_callbacks);
+                    self.free_memory(memory, allocation_callbacks);
+                }
+                return Err(e.into());
+            }
+        };
+
+        let allocated = AllocatedImage {
+            image,
+            memory,
+            view,
+            format: desc.format,
+            extent: desc.extent,
+            mip_levels,
+            array_layers: desc.array_layers,
+            aspect,
+        };
+        let live_objects = self.live_objects();
+        live_objects.track(vk::ObjectType::IMAGE, image.as_raw());
+        live_objects.track(vk::ObjectType::IMAGE_VIEW, view.as_raw());
+        let named = self
+            .set_object_name(image, name)
+            .and_then(|_| self.set_object_name(view, name));
+        if let Err(e) = named {
+            allocated.destroy(self);
+            return Err(e);
+        }
+
+        Ok(allocated)
+    }
+
+    /// Record blits that fill every mip level of `image` from level 0, created with
+    /// [`ImageDesc::with_mips`].
+    ///
+    /// All levels must be in `TRANSFER_DST_OPTIMAL` with level 0 written, e.g. right after a
+    /// buffer to image copy. Afterwards all levels are in `SHADER_READ_ONLY_OPTIMAL`.
+    pub fn cmd_generate_mips(&self, command_buffer: vk::CommandBuffer, image: &AllocatedImage) {
+        let barrier = |level: u32, old_layout, new_layout, src_access, dst_access| {
+            vk::ImageMemoryBarrier::builder()
+                .src_access_mask(src_access)
+                .dst_access_mask(dst_access)
+                .old_layout(old_layout)
+                .new_layout(new_layout)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image.image)
+                .subresource_range(
+                    vk::ImageSubresourceRange::builder()
+                        .aspect_mask(image.aspect)
+                        .base_mip_level(level)
+                        .level_count(1)
+                        .layer_count(image.array_layers),
+                )
+                .build()
+        };
+        let to_shader_read = |level: u32, old_layout, src_access| {
+            let barrier = barrier(
+                level,
+                old_layout,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                src_access,
+                vk::AccessFlags::SHADER_READ,
+            );
+            unsafe {
+                self.cmd_pipeline_barrier(
+                    command_buffer,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::PipelineStageFlags::ALL_COMMANDS,
+                    vk::DependencyFlags::empty(),
+                    &[] as &[vk::MemoryBarrier],
+                    &[] as &[vk::BufferMemoryBarrier],
+                    &[barrier],
+                )
+            };
+        };
+        let half = |value: u32| (value / 2).max(1);
+
+        let mut extent = image.extent;
+        for level in 1..image.mip_levels {
+            let to_source = barrier(
+                level - 1,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                vk::AccessFlags::TRANSFER_WRITE,
+                vk::AccessFlags::TRANSFER_READ,
+            );
+            unsafe {
+                self.cmd_pipeline_barrier(
+                    command_buffer,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::DependencyFlags::empty(),
+                    &[] as &[vk::MemoryBarrier],
+                    &[] as &[vk::BufferMemoryBarrier],
+                    &[to_source],
+                )
+            };
+
+            let next = vk::Extent3D {
+                width: half(extent.width),
+                height: half(extent.height),
+                depth: half(extent.depth),
+            };
+            let subresource = |level: u32| {
+                vk::ImageSubresourceLayers::builder()
+                    .aspect_mask(image.aspect)
+                    .mip_level(level)
+                    .layer_count(image.array_layers)
+                    .build()
+            };
+            let corner = |extent: vk::Extent3D| vk::Offset3D {
+                x: extent.width as i32,
+                y: extent.height as i32,
+                z: extent.depth as i32,
+            };
+            let blit = vk::ImageBlit::builder()
+                .src_subresource(subresource(level - 1))
+                .src_offsets([vk::Offset3D::default(), corner(extent)])
+                .dst_subresource(subresource(level))
+                .dst_offsets([vk::Offset3D::default(), corner(next)]);
+            unsafe {
+                self.cmd_blit_image(
+                    command_buffer,
+                    image.image,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    image.image,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[blit],
+                    vk::Filter::LINEAR,
+                )
+            };
+
+            to_shader_read(
+                level - 1,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                vk::AccessFlags::TRANSFER_READ,
+            );
+            extent = next;
+        }
+
+        to_shader_read(
+            image.mip_levels - 1,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            vk::AccessFlags::TRANSFER_WRITE,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_mip_chain_ends_at_one_texel() {
+        let usage = vk::ImageUsageFlags::SAMPLED;
+        let extent = |width, height| vk::Extent2D { width, height };
+        let levels = |desc: ImageDesc| desc.with_mips().resolved_mip_levels();
+
+        assert_eq!(
+            levels(ImageDesc::new_2d(
+                vk::Format::R8G8B8A8_UNORM,
+                extent(1, 1),
+                usage
+            )),
+            1
+        );
+        assert_eq!(
+            levels(ImageDesc::new_2d(
+                vk::Format::R8G8B8A8_UNORM,
+                extent(256, 256),
+                usage
+            )),
+            9
+        );
+        assert_eq!(
+            levels(ImageDesc::new_2d(
+                vk::Format::R8G8B8A8_UNORM,
+                extent(300, 17),
+                usage
+            )),
+            9
+        );
+    }
+
+    #[test]
+    fn image_types_follow_depth_and_layers() {
+        let desc = ImageDesc::new_2d(
+            vk::Format::R8G8B8A8_UNORM,
+            vk::Extent2D {
+                width: 16,
+                height: 16,
+            },
+            vk::ImageUsageFlags::SAMPLED,
+        );
+        let volume = ImageDesc {
+            extent: vk::Extent3D {
+                width: 16,
+                height: 16,
+                depth: 4,
+            },
+            ..desc
+        };
+
+        assert_eq!(
+            desc.types().unwrap(),
+            (vk::ImageType::_2D, vk::ImageViewType::_2D)
+        );
+        assert_eq!(
+            desc.array_layers(6).types().unwrap(),
+            (vk::ImageType::_2D, vk::ImageViewType::_2D_ARRAY)
+        );
+        assert_eq!(
+            volume.types().unwrap(),
+            (vk::ImageType::_3D, vk::ImageViewType::_3D)
+        );
+        assert!(matches!(
+            volume.array_layers(2).types(),
+            Err(crate::Error::Config(
+                crate::ConfigError::ConflictingOptions(_)
+            ))
+        ));
+    }
+}
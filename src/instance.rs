@@ -1,12 +1,23 @@
-use crate::system_info::{DEBUG_UTILS_EXT_NAME, SystemInfo, VALIDATION_LAYER_NAME};
-use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+use crate::allocator::TrackedAllocationCallbacks;
+use crate::debug_output::{DebugOutput, DebugPrintfCallback, DebugSink, is_debug_printf};
+use crate::display::{self, DisplayInfo, DisplaySelection};
+use crate::leak::LiveObjects;
+use crate::moltenvk::{self, MoltenVkConfig};
+use crate::system_info::{
+    API_DUMP_LAYER_NAME, CAPTURE_LAYER_NAME, DEBUG_UTILS_EXT_NAME, SystemInfo,
+    VALIDATION_LAYER_NAME,
+};
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, RawDisplayHandle,
+    RawWindowHandle, WindowHandle,
+};
 use std::borrow::Cow;
 use std::ffi;
 use std::ffi::c_void;
 use std::fmt::Debug;
 use std::sync::Arc;
 use vulkanalia::vk::{
-    self, EntryV1_1, ExtDebugUtilsExtensionInstanceCommands, HasBuilder, InstanceV1_0,
+    self, ExtDebugUtilsExtensionInstanceCommands, Handle, HasBuilder, InstanceV1_0,
     KhrSurfaceExtensionInstanceCommands,
 };
 use vulkanalia::vk::{AllocationCallbacks, DebugUtilsMessengerEXT};
@@ -15,11 +26,44 @@ use vulkanalia::{Version, window as vk_window};
 pub trait WindowTraits: HasDisplayHandle + HasWindowHandle + Debug {}
 impl<T> WindowTraits for T where T: HasDisplayHandle + HasWindowHandle + Debug {}
 
+/// A window given as raw display and window handles, for windowing libraries that don't
+/// implement the raw-window-handle 0.6 traits.
+///
+/// Libraries still on raw-window-handle 0.5 expose handle structs with the same fields, so the
+/// 0.6 values can be built from them. Pass it to [`InstanceBuilder::new`] like a window.
+#[derive(Debug, Clone, Copy)]
+pub struct RawWindow {
+    display: RawDisplayHandle,
+    window: RawWindowHandle,
+}
+
+impl RawWindow {
+    /// # Safety
+    ///
+    /// Both handles must be valid and stay valid until the surface created for them is
+    /// destroyed.
+    pub unsafe fn new(display: RawDisplayHandle, window: RawWindowHandle) -> Self {
+        Self { display, window }
+    }
+}
+
+impl HasDisplayHandle for RawWindow {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Ok(unsafe { DisplayHandle::borrow_raw(self.display) })
+    }
+}
+
+impl HasWindowHandle for RawWindow {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        Ok(unsafe { WindowHandle::borrow_raw(self.window) })
+    }
+}
+
 unsafe extern "system" fn vulkan_debug_callback(
     message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
     message_type: vk::DebugUtilsMessageTypeFlagsEXT,
     p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
-    _user_data: *mut std::os::raw::c_void,
+    user_data: *mut std::os::raw::c_void,
 ) -> vk::Bool32 {
     unsafe {
         let callback_data = *p_callback_data;
@@ -37,15 +81,36 @@ unsafe extern "system" fn vulkan_debug_callback(
             ffi::CStr::from_ptr(callback_data.message).to_string_lossy()
         };
 
-        println!(
-            "{message_severity:?}:\n{message_type:?} [{message_id_name} ({message_id_number})] : {message}\n",
-        );
+        match (user_data as *const DebugSink).as_ref() {
+            Some(sink) => {
+                if is_debug_printf(&message_id_name) {
+                    if sink.route_printf(&message) {
+                        return vk::FALSE;
+                    }
+                } else if !sink.severity.contains(message_severity) {
+                    return vk::FALSE;
+                }
+
+                if let Some(count) = sink.repeat_count(message_id_number, &message) {
+                    let note = sink.repeat_note(count);
+                    sink.write_message(
+                        message_severity,
+                        &format!(
+                            "{message_type:?} [{message_id_name} ({message_id_number})] : {message}{note}\n"
+                        ),
+                    );
+                }
+            }
+            None => println!(
+                "{message_severity:?}:\n{message_type:?} [{message_id_name} ({message_id_number})] : {message}\n",
+            ),
+        }
 
         vk::FALSE
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct DebugUserData(*mut c_void);
 
 impl Default for DebugUserData {
@@ -67,7 +132,96 @@ impl DebugUserData {
     }
 }
 
-#[derive(Debug)]
+/// What [`InstanceBuilder::build`] would create, returned by [`InstanceBuilder::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstancePlan {
+    /// Highest instance version supported by the loader, `1.0.0` unless a version was requested.
+    pub instance_version: Version,
+    /// Version passed in `vk::ApplicationInfo`.
+    pub api_version: Version,
+    /// Layers that would be enabled, including the ones providing requested extensions.
+    pub layers: Vec<vk::ExtensionName>,
+    pub extensions: Vec<vk::ExtensionName>,
+    pub flags: vk::InstanceCreateFlags,
+    /// Whether a debug messenger would be created.
+    pub debug_messenger: bool,
+    /// Whether a window or display surface would be created.
+    pub surface: bool,
+    properties2_ext_enabled: bool,
+    portability_enumeration_enabled: bool,
+    surface_capabilities2_enabled: bool,
+    surface_protected_capabilities_enabled: bool,
+    surface_maintenance1_enabled: bool,
+    layer_settings_enabled: bool,
+}
+
+/// Plain-data snapshot of the [`InstanceBuilder`] settings.
+///
+/// Useful for capturing configuration programmatically (editors, scripting) independently of
+/// the fluent API. The window, debug callback, user data and allocation callbacks are not part
+/// of the config and are left untouched by [`InstanceBuilder::with_config`].
+///
+/// With the `serde` feature it can be loaded from a config file; missing fields keep their
+/// defaults, versions are written as `"major.minor.patch"` and flags as raw bits.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct InstanceConfig {
+    pub app_name: String,
+    pub engine_name: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::version"))]
+    pub application_version: Version,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::version"))]
+    pub engine_version: Version,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::version"))]
+    pub minimum_instance_version: Version,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::version"))]
+    pub required_instance_version: Version,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::config_serde::extension_names")
+    )]
+    pub layers: Vec<vk::ExtensionName>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::config_serde::extension_names")
+    )]
+    pub extensions: Vec<vk::ExtensionName>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::flags"))]
+    pub flags: vk::InstanceCreateFlags,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::flags"))]
+    pub debug_message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::flags"))]
+    pub debug_message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::enums"))]
+    pub disabled_validation_checks: Vec<vk::ValidationCheckEXT>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::enums"))]
+    pub enabled_validation_features: Vec<vk::ValidationFeatureEnableEXT>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::config_serde::enums"))]
+    pub disabled_validation_features: Vec<vk::ValidationFeatureDisableEXT>,
+    pub request_validation_layers: bool,
+    pub enable_validation_layers: bool,
+    pub headless: bool,
+    pub surface_capabilities2: bool,
+    pub display: Option<DisplaySelection>,
+    pub enumerate_portability: bool,
+    pub moltenvk: Option<MoltenVkConfig>,
+    pub debug_printf: bool,
+    pub debug_printf_buffer_size: Option<u32>,
+    pub api_dump_layer: bool,
+    pub capture_layer: bool,
+}
+
+impl Default for InstanceConfig {
+    fn default() -> Self {
+        InstanceBuilder::new(None).to_config()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct InstanceBuilder {
     // VkApplicationInfo
     app_name: String,
@@ -87,6 +241,10 @@ pub struct InstanceBuilder {
     debug_message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
     debug_message_type: vk::DebugUtilsMessageTypeFlagsEXT,
     debug_user_data: DebugUserData,
+    debug_output: DebugOutput,
+    builtin_debug_messenger: bool,
+    colored_debug_output: bool,
+    debug_message_repeat_limit: u32,
 
     // validation checks
     disabled_validation_checks: Vec<vk::ValidationCheckEXT>,
@@ -94,14 +252,26 @@ pub struct InstanceBuilder {
     disabled_validation_features: Vec<vk::ValidationFeatureDisableEXT>,
 
     allocation_callbacks: Option<vk::AllocationCallbacks>,
+    allocator: Option<TrackedAllocationCallbacks>,
 
     request_validation_layers: bool,
     enable_validation_layers: bool,
     // TODO: make typesafe
     use_debug_messenger: bool,
     headless_context: bool,
-
+    surface_capabilities2: bool,
+    display: Option<DisplaySelection>,
+    enumerate_portability: bool,
+    moltenvk: Option<MoltenVkConfig>,
+    debug_printf: bool,
+    debug_printf_buffer_size: Option<u32>,
+    debug_printf_callback: Option<DebugPrintfCallback>,
+    api_dump_layer: bool,
+    capture_layer: bool,
+
+    system_info: Option<SystemInfo>,
     window: Option<Arc<dyn WindowTraits>>,
+    window_extensions: Option<Vec<vk::ExtensionName>>,
 }
 
 impl InstanceBuilder {
@@ -123,18 +293,95 @@ impl InstanceBuilder {
                 | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
                 | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
             debug_user_data: Default::default(),
+            debug_output: DebugOutput::default(),
+            builtin_debug_messenger: false,
+            colored_debug_output: true,
+            debug_message_repeat_limit: 5,
             disabled_validation_checks: vec![],
             enabled_validation_features: vec![],
             disabled_validation_features: vec![],
             allocation_callbacks: None,
+            allocator: None,
             request_validation_layers: false,
             enable_validation_layers: false,
             use_debug_messenger: false,
             headless_context: false,
+            surface_capabilities2: false,
+            display: None,
+            enumerate_portability: cfg!(feature = "portability"),
+            moltenvk: None,
+            debug_printf: false,
+            debug_printf_buffer_size: None,
+            debug_printf_callback: None,
+            api_dump_layer: false,
+            capture_layer: false,
+            system_info: None,
             window,
+            window_extensions: None,
         }
     }
 
+    /// Capture the current plain-data settings of this builder.
+    pub fn to_config(&self) -> InstanceConfig {
+        InstanceConfig {
+            app_name: self.app_name.clone(),
+            engine_name: self.engine_name.clone(),
+            application_version: self.application_version,
+            engine_version: self.engine_version,
+            minimum_instance_version: self.minimum_instance_version,
+            required_instance_version: self.required_instance_version,
+            layers: self.layers.clone(),
+            extensions: self.extensions.clone(),
+            flags: self.flags,
+            debug_message_severity: self.debug_message_severity,
+            debug_message_type: self.debug_message_type,
+            disabled_validation_checks: self.disabled_validation_checks.clone(),
+            enabled_validation_features: self.enabled_validation_features.clone(),
+            disabled_validation_features: self.disabled_validation_features.clone(),
+            request_validation_layers: self.request_validation_layers,
+            enable_validation_layers: self.enable_validation_layers,
+            headless: self.headless_context,
+            surface_capabilities2: self.surface_capabilities2,
+            display: self.display,
+            enumerate_portability: self.enumerate_portability,
+            moltenvk: self.moltenvk.clone(),
+            debug_printf: self.debug_printf,
+            debug_printf_buffer_size: self.debug_printf_buffer_size,
+            api_dump_layer: self.api_dump_layer,
+            capture_layer: self.capture_layer,
+        }
+    }
+
+    /// Replace the plain-data settings of this builder with `config`.
+    pub fn with_config(mut self, config: InstanceConfig) -> Self {
+        self.app_name = config.app_name;
+        self.engine_name = config.engine_name;
+        self.application_version = config.application_version;
+        self.engine_version = config.engine_version;
+        self.minimum_instance_version = config.minimum_instance_version;
+        self.required_instance_version = config.required_instance_version;
+        self.layers = config.layers;
+        self.extensions = config.extensions;
+        self.flags = config.flags;
+        self.debug_message_severity = config.debug_message_severity;
+        self.debug_message_type = config.debug_message_type;
+        self.disabled_validation_checks = config.disabled_validation_checks;
+        self.enabled_validation_features = config.enabled_validation_features;
+        self.disabled_validation_features = config.disabled_validation_features;
+        self.request_validation_layers = config.request_validation_layers;
+        self.enable_validation_layers = config.enable_validation_layers;
+        self.headless_context = config.headless;
+        self.surface_capabilities2 = config.surface_capabilities2;
+        self.display = config.display;
+        self.enumerate_portability = config.enumerate_portability;
+        self.moltenvk = config.moltenvk;
+        self.debug_printf = config.debug_printf;
+        self.debug_printf_buffer_size = config.debug_printf_buffer_size;
+        self.api_dump_layer = config.api_dump_layer;
+        self.capture_layer = config.capture_layer;
+        self
+    }
+
     /// Set the application name that will be passed to Vulkan via VkApplicationInfo.
     pub fn app_name(mut self, app_name: impl Into<String>) -> Self {
         self.app_name = app_name.into();
@@ -173,16 +420,30 @@ impl InstanceBuilder {
 
     /// Enable the given instance layer for creation (e.g. validation layers).
     pub fn enable_layer(mut self, layer: vk::ExtensionName) -> Self {
-        self.layers.push(layer.into());
+        self.layers.push(layer);
         self
     }
 
-    /// Enable the given Vulkan instance extension for creation.
+    /// Enable the given Vulkan instance extension for creation. When only a layer exposes the
+    /// extension, that layer is enabled too.
     pub fn enable_extension(mut self, extension: vk::ExtensionName) -> Self {
         self.extensions.push(extension);
         self
     }
 
+    /// Use these surface extensions for the window instead of deriving them from its
+    /// raw-window-handle platform.
+    ///
+    /// Windowing libraries like SDL report the extensions their window needs themselves
+    /// (`SDL_Vulkan_GetInstanceExtensions`), which can differ from what the handle type implies,
+    /// for example when SDL runs through XWayland. Convert the names with
+    /// `vk::ExtensionName::from_bytes` and pass them here; missing extensions still fail with
+    /// `InstanceError::WindowingExtensionsNotPresent`.
+    pub fn window_extensions(mut self, extensions: Vec<vk::ExtensionName>) -> Self {
+        self.window_extensions = Some(extensions);
+        self
+    }
+
     /// Explicitly enable or disable validation layers.
     pub fn enable_validation_layers(mut self, enable: bool) -> Self {
         self.enable_validation_layers = enable;
@@ -195,16 +456,40 @@ impl InstanceBuilder {
         self
     }
 
-    /// Use the default debug messenger which prints messages to stdout.
+    /// Use the default debug messenger which writes messages to the
+    /// [`debug_output`](Self::debug_output), stdout unless configured otherwise.
     pub fn use_default_debug_messenger(mut self) -> Self {
         self.use_debug_messenger = true;
+        self.builtin_debug_messenger = true;
         self.debug_callback = Some(vulkan_debug_callback);
         self
     }
 
+    /// Set where the default debug messenger writes its messages.
+    pub fn debug_output(mut self, output: DebugOutput) -> Self {
+        self.debug_output = output;
+        self
+    }
+
+    /// Color the severity of default debug messenger messages when writing to a terminal.
+    /// Enabled by default.
+    pub fn colored_debug_output(mut self, colored: bool) -> Self {
+        self.colored_debug_output = colored;
+        self
+    }
+
+    /// Report an identical message from the built-in debug messengers this many times, then
+    /// only at every power of two with a repeat counter. Zero reports every message. Defaults
+    /// to 5.
+    pub fn debug_message_repeat_limit(mut self, limit: u32) -> Self {
+        self.debug_message_repeat_limit = limit;
+        self
+    }
+
     #[cfg(feature = "enable_tracing")]
     pub fn use_default_tracing_messenger(mut self) -> Self {
         self.use_debug_messenger = true;
+        self.builtin_debug_messenger = true;
         self.debug_callback = Some(crate::tracing::vulkan_tracing_callback);
         self
     }
@@ -215,6 +500,7 @@ impl InstanceBuilder {
         callback: vk::PFN_vkDebugUtilsMessengerCallbackEXT,
     ) -> Self {
         self.use_debug_messenger = true;
+        self.builtin_debug_messenger = false;
         self.debug_callback = callback;
         self
     }
@@ -231,6 +517,113 @@ impl InstanceBuilder {
         self
     }
 
+    /// Enumerate portability (non-conformant, e.g. MoltenVK) drivers by enabling
+    /// `VK_KHR_portability_enumeration` and `ENUMERATE_PORTABILITY_KHR` when the loader supports
+    /// it. Defaults to true when the `portability` feature is enabled.
+    pub fn enumerate_portability(mut self, enumerate: bool) -> Self {
+        self.enumerate_portability = enumerate;
+        self
+    }
+
+    /// Enable shader `debugPrintfEXT` output.
+    ///
+    /// Requests the validation layers, enables the `DEBUG_PRINTF` validation feature and adds
+    /// `INFO` to the debug messenger severities so printf messages reach it. The built-in
+    /// messengers send them to the [`debug_printf_callback`](Self::debug_printf_callback) when
+    /// set, to the `vulkanalia_bootstrap::debug_printf` tracing target for the tracing
+    /// messenger, and to the debug output otherwise. Other `INFO` messages stay filtered unless
+    /// asked for.
+    pub fn enable_debug_printf(mut self) -> Self {
+        self.debug_printf = true;
+        self
+    }
+
+    /// Size in bytes of the buffer the validation layer collects printf output in per draw or
+    /// dispatch. Output that doesn't fit is dropped by the layer.
+    pub fn debug_printf_buffer_size(mut self, bytes: u32) -> Self {
+        self.debug_printf_buffer_size = Some(bytes);
+        self
+    }
+
+    /// Receive shader debug printf messages from the built-in debug messengers.
+    pub fn debug_printf_callback(
+        mut self,
+        callback: impl Fn(&str) + Send + Sync + 'static,
+    ) -> Self {
+        self.debug_printf_callback = Some(DebugPrintfCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Enable `VK_LAYER_LUNARG_api_dump` when it is installed, to print every Vulkan call.
+    /// Logs a warning and continues without it otherwise.
+    pub fn enable_api_dump_layer(mut self) -> Self {
+        self.api_dump_layer = true;
+        self
+    }
+
+    /// Enable `VK_LAYER_LUNARG_gfxreconstruct` when it is installed, to capture the Vulkan calls
+    /// for replay. Logs a warning and continues without it otherwise.
+    pub fn enable_capture_layer(mut self) -> Self {
+        self.capture_layer = true;
+        self
+    }
+
+    /// Reuse layers and extensions enumerated earlier, e.g. from [`Instance::system_info`],
+    /// instead of querying them again in [`InstanceBuilder::build`].
+    pub fn system_info(mut self, system_info: SystemInfo) -> Self {
+        self.system_info = Some(system_info);
+        self
+    }
+
+    /// Configure MoltenVK before the instance is created.
+    ///
+    /// The settings are passed through `VK_EXT_layer_settings` when the loader exposes it and
+    /// are skipped otherwise. See [`MoltenVkConfig`] for the available settings.
+    pub fn moltenvk_config(mut self, config: MoltenVkConfig) -> Self {
+        self.moltenvk = Some(config);
+        self
+    }
+
+    /// Present directly to a display through `VK_KHR_display` instead of a window, for systems
+    /// without a window system. The display surface becomes the instance surface, so device
+    /// selection and swapchain creation work as with a window. Building fails with
+    /// `ConfigError::ConflictingOptions` when combined with a window or `headless(true)`.
+    pub fn direct_to_display(mut self, selection: DisplaySelection) -> Self {
+        self.display = Some(selection);
+        self
+    }
+
+    /// Query surfaces through `VK_KHR_get_surface_capabilities2` when the loader supports it.
+    ///
+    /// Also enables `VK_KHR_surface_protected_capabilities` and `VK_KHR/EXT_surface_maintenance1`
+    /// when available, so [`crate::SurfaceSupport`] reports protected presentation support and
+    /// per-present-mode image counts. Falls back to the legacy queries otherwise.
+    pub fn use_surface_capabilities2(mut self, enable: bool) -> Self {
+        self.surface_capabilities2 = enable;
+        self
+    }
+
+    /// Allocate host memory for the instance, its surface and debug messenger with `allocator`.
+    /// The instance keeps the allocator alive until it is dropped.
+    pub fn allocator(mut self, allocator: TrackedAllocationCallbacks) -> Self {
+        self.allocation_callbacks = Some(allocator.callbacks());
+        self.allocator = Some(allocator);
+        self
+    }
+
+    /// Set the flags passed in `vk::InstanceCreateInfo`. `ENUMERATE_PORTABILITY_KHR` is still
+    /// added when portability enumeration is enabled.
+    pub fn flags(mut self, flags: vk::InstanceCreateFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Add instance create flags to the ones already set.
+    pub fn add_flags(mut self, flags: vk::InstanceCreateFlags) -> Self {
+        self.flags |= flags;
+        self
+    }
+
     /// Set the severity flags for the debug messenger (e.g. WARNING | ERROR).
     pub fn debug_messenger_severity(
         mut self,
@@ -264,20 +657,31 @@ impl InstanceBuilder {
         self
     }
 
-    #[cfg_attr(feature = "enable_tracing", tracing::instrument(skip(self)))]
-    /// Build and return an `Instance` according to the configured options.
-    ///
-    /// Performs validation of available layers/extensions and creates the Vulkan instance
-    /// and optional debug messenger and surface.
-    pub fn build(self) -> crate::Result<Arc<Instance>> {
-        let system_info = SystemInfo::get_system_info()?;
+    /// Run the availability checks of [`InstanceBuilder::build`] (instance version, layers and
+    /// extensions) and return what it would create, without creating anything.
+    pub fn plan(&self) -> crate::Result<InstancePlan> {
+        let system_info = match &self.system_info {
+            Some(system_info) => system_info,
+            None => &SystemInfo::get_system_info()?,
+        };
+
+        self.resolve_plan(system_info)
+    }
+
+    fn resolve_plan(&self, system_info: &SystemInfo) -> crate::Result<InstancePlan> {
+        if self.display.is_some() && (self.window.is_some() || self.headless_context) {
+            return Err(crate::ConfigError::ConflictingOptions(
+                "direct_to_display can't be combined with a window or a headless context"
+                    .to_string(),
+            )
+            .into());
+        }
 
         let instance_version = {
             if self.minimum_instance_version > Version::V1_0_0
                 || self.required_instance_version > Version::V1_0_0
             {
-                let version = unsafe { system_info.entry.enumerate_instance_version() }
-                    .map_or(Version::V1_0_0, Version::from);
+                let version = Version::from(system_info.instance_api_version);
 
                 if version < self.minimum_instance_version
                     || (self.minimum_instance_version == Version::V1_0_0
@@ -327,47 +731,37 @@ impl InstanceBuilder {
             tracing::info!("api_version: {}", api_version);
         }
 
-        let app_name = self.app_name;
-        let engine_name = self.engine_name;
+        let mut enabled_extensions: Vec<vk::ExtensionName> = vec![];
+        let mut enabled_layers: Vec<vk::ExtensionName> = vec![];
 
-        let app_info = vk::ApplicationInfo {
-            application_name: app_name.as_bytes().as_ptr() as _,
-            application_version: self.application_version.into(),
-            engine_name: engine_name.as_bytes().as_ptr() as _,
-            engine_version: self.engine_version.into(),
-            api_version: api_version.into(),
-            ..Default::default()
-        };
+        enabled_layers.extend_from_slice(&self.layers);
 
-        #[cfg(feature = "enable_tracing")]
+        if self.enable_validation_layers
+            || ((self.request_validation_layers || self.debug_printf)
+                && system_info.validation_layers_available)
         {
-            tracing::info!("Creating vkInstance with application info...");
-            tracing::debug!(
-                r#"
-Application info: {{
-    name: {:?},
-    version: {}.{}.{},
-    engine_name: {:?},
-    engine_version: {}.{}.{},
-    api_version: {}.{}.{},
-}}
-            "#,
-                app_name,
-                self.application_version.major,
-                self.application_version.minor,
-                self.application_version.patch,
-                engine_name,
-                self.engine_version.major,
-                self.engine_version.minor,
-                self.engine_version.patch,
-                api_version.major,
-                api_version.minor,
-                api_version.patch,
-            )
+            enabled_layers.push(VALIDATION_LAYER_NAME)
+        };
+
+        for (requested, layer) in [
+            (self.api_dump_layer, API_DUMP_LAYER_NAME),
+            (self.capture_layer, CAPTURE_LAYER_NAME),
+        ] {
+            if !requested || enabled_layers.contains(&layer) {
+                continue;
+            }
+            if system_info.is_layer_available(layer)? {
+                enabled_layers.push(layer);
+            } else {
+                #[cfg(feature = "enable_tracing")]
+                tracing::warn!("{} is not installed, continuing without it", layer);
+            }
         }
 
-        let mut enabled_extensions: Vec<vk::ExtensionName> = vec![];
-        let mut enabled_layers: Vec<vk::ExtensionName> = vec![];
+        let missing_layers = system_info.missing_layers(&enabled_layers);
+        if !missing_layers.is_empty() {
+            return Err(crate::InstanceError::RequestedLayersNotPresent(missing_layers).into());
+        };
 
         enabled_extensions.extend_from_slice(self.extensions.as_slice());
 
@@ -386,63 +780,202 @@ Application info: {{
             enabled_extensions.push(vk::KHR_GET_PHYSICAL_DEVICE_PROPERTIES2_EXTENSION.name);
         }
 
-        #[cfg(feature = "portability")]
-        let portability_enumeration_support =
-            system_info.is_extension_available(&vk::KHR_PORTABILITY_ENUMERATION_EXTENSION.name)?;
-        #[cfg(feature = "portability")]
-        if portability_enumeration_support {
+        let portability_enumeration_enabled = self.enumerate_portability
+            && system_info
+                .is_extension_available(&vk::KHR_PORTABILITY_ENUMERATION_EXTENSION.name)?;
+        if portability_enumeration_enabled {
             enabled_extensions.push(vk::KHR_PORTABILITY_ENUMERATION_EXTENSION.name);
         }
 
-        if !self.headless_context {
-            if let Some(window) = self.window.clone() {
-                let surface_extensions: Vec<vk::ExtensionName> =
-                    vk_window::get_required_instance_extensions(window.as_ref())
-                        .into_iter()
-                        .map(|ext| **ext)
-                        .collect();
-
-                if !system_info.are_extensions_available(&surface_extensions)? {
-                    return Err(crate::InstanceError::WindowingExtensionsNotPresent(
-                        surface_extensions,
-                    )
-                    .into());
-                };
-
-                enabled_extensions.extend_from_slice(&surface_extensions);
+        if !self.headless_context
+            && let Some(window) = self.window.clone()
+        {
+            let surface_extensions: Vec<vk::ExtensionName> = match &self.window_extensions {
+                Some(extensions) => extensions.clone(),
+                None => vk_window::get_required_instance_extensions(window.as_ref())
+                    .iter()
+                    .map(|ext| **ext)
+                    .collect(),
+            };
+
+            system_info.enable_providing_layers(&surface_extensions, &mut enabled_layers);
+            let missing = system_info.missing_extensions(&surface_extensions, &enabled_layers);
+            if !missing.is_empty() {
+                return Err(crate::InstanceError::WindowingExtensionsNotPresent(missing).into());
+            };
+
+            enabled_extensions.extend_from_slice(&surface_extensions);
+        }
+
+        let use_display = self.display.is_some();
+        if use_display {
+            for extension in [
+                vk::KHR_SURFACE_EXTENSION.name,
+                vk::KHR_DISPLAY_EXTENSION.name,
+            ] {
+                if !enabled_extensions.contains(&extension) {
+                    enabled_extensions.push(extension);
+                }
             }
         }
 
+        let mut surface_capabilities2_enabled = false;
+        let mut surface_protected_capabilities_enabled = false;
+        let mut surface_maintenance1_enabled = false;
+        if self.surface_capabilities2
+            && !self.headless_context
+            && (self.window.is_some() || use_display)
+        {
+            surface_capabilities2_enabled = system_info
+                .is_extension_available(&vk::KHR_GET_SURFACE_CAPABILITIES2_EXTENSION.name)?;
+        }
+        if surface_capabilities2_enabled {
+            enabled_extensions.push(vk::KHR_GET_SURFACE_CAPABILITIES2_EXTENSION.name);
+
+            if system_info
+                .is_extension_available(&vk::KHR_SURFACE_PROTECTED_CAPABILITIES_EXTENSION.name)?
+            {
+                enabled_extensions.push(vk::KHR_SURFACE_PROTECTED_CAPABILITIES_EXTENSION.name);
+                surface_protected_capabilities_enabled = true;
+            }
+
+            for maintenance1 in [
+                vk::KHR_SURFACE_MAINTENANCE1_EXTENSION.name,
+                vk::EXT_SURFACE_MAINTENANCE1_EXTENSION.name,
+            ] {
+                if system_info.is_extension_available(&maintenance1)? {
+                    enabled_extensions.push(maintenance1);
+                    surface_maintenance1_enabled = true;
+                    break;
+                }
+            }
+        }
+
+        let moltenvk_settings = self
+            .moltenvk
+            .as_ref()
+            .map(MoltenVkConfig::settings)
+            .unwrap_or_default();
+        let layer_settings_enabled = !moltenvk_settings.is_empty()
+            && system_info.is_extension_available(&vk::EXT_LAYER_SETTINGS_EXTENSION.name)?;
+        if layer_settings_enabled {
+            enabled_extensions.push(vk::EXT_LAYER_SETTINGS_EXTENSION.name);
+        } else if !moltenvk_settings.is_empty() {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!(
+                "VK_EXT_layer_settings is not available, the MoltenVK configuration is ignored"
+            );
+        }
+
         #[cfg(feature = "enable_tracing")]
         tracing::trace!(?enabled_extensions);
 
-        let all_extensions_supported = system_info.are_extensions_available(&enabled_extensions)?;
-        if !all_extensions_supported {
+        // Extensions only a layer exposes (e.g. VK_EXT_validation_features) need that layer.
+        system_info.enable_providing_layers(&enabled_extensions, &mut enabled_layers);
+        let missing_extensions =
+            system_info.missing_extensions(&enabled_extensions, &enabled_layers);
+        if !missing_extensions.is_empty() {
             return Err(
-                crate::InstanceError::RequestedExtensionsNotPresent(enabled_extensions).into(),
+                crate::InstanceError::RequestedExtensionsNotPresent(missing_extensions).into(),
             );
         };
 
-        enabled_layers.extend_from_slice(&self.layers);
-
-        if self.enable_validation_layers
-            || (self.request_validation_layers && system_info.validation_layers_available)
-        {
-            enabled_layers.push(VALIDATION_LAYER_NAME)
+        let instance_create_flags = if portability_enumeration_enabled {
+            self.flags | vk::InstanceCreateFlags::ENUMERATE_PORTABILITY_KHR
+        } else {
+            self.flags
         };
 
-        let all_layers_supported = system_info.are_layers_available(self.layers)?;
+        Ok(InstancePlan {
+            instance_version,
+            api_version,
+            layers: enabled_layers,
+            extensions: enabled_extensions,
+            flags: instance_create_flags,
+            debug_messenger: self.use_debug_messenger,
+            surface: (self.window.is_some() && !self.headless_context) || use_display,
+            properties2_ext_enabled,
+            portability_enumeration_enabled,
+            surface_capabilities2_enabled,
+            surface_protected_capabilities_enabled,
+            surface_maintenance1_enabled,
+            layer_settings_enabled,
+        })
+    }
 
-        if !all_layers_supported {
-            return Err(crate::InstanceError::RequestedLayersNotPresent(enabled_layers).into());
+    #[cfg_attr(feature = "enable_tracing", tracing::instrument(skip(self)))]
+    /// Build and return an `Instance` according to the configured options.
+    ///
+    /// Performs validation of available layers/extensions and creates the Vulkan instance
+    /// and optional debug messenger and surface.
+    pub fn build(mut self) -> crate::Result<Arc<Instance>> {
+        let system_info = match self.system_info.take() {
+            Some(system_info) => system_info,
+            None => SystemInfo::get_system_info()?,
         };
 
-        let instance_create_flags = if cfg!(feature = "portability") {
-            self.flags | vk::InstanceCreateFlags::ENUMERATE_PORTABILITY_KHR
-        } else {
-            self.flags
+        let InstancePlan {
+            instance_version,
+            api_version,
+            layers: enabled_layers,
+            extensions: enabled_extensions,
+            flags: instance_create_flags,
+            properties2_ext_enabled,
+            portability_enumeration_enabled,
+            surface_capabilities2_enabled,
+            surface_protected_capabilities_enabled,
+            surface_maintenance1_enabled,
+            layer_settings_enabled,
+            ..
+        } = self.resolve_plan(&system_info)?;
+        let use_display = self.display.is_some();
+        let moltenvk_settings = self
+            .moltenvk
+            .as_ref()
+            .map(MoltenVkConfig::settings)
+            .unwrap_or_default();
+
+        let app_name = self.app_name;
+        let engine_name = self.engine_name;
+
+        let app_info = vk::ApplicationInfo {
+            application_name: app_name.as_bytes().as_ptr() as _,
+            application_version: self.application_version.into(),
+            engine_name: engine_name.as_bytes().as_ptr() as _,
+            engine_version: self.engine_version.into(),
+            api_version: api_version.into(),
+            ..Default::default()
         };
 
+        #[cfg(feature = "enable_tracing")]
+        {
+            tracing::info!("Creating vkInstance with application info...");
+            tracing::debug!(
+                r#"
+Application info: {{
+    name: {:?},
+    version: {}.{}.{},
+    engine_name: {:?},
+    engine_version: {}.{}.{},
+    api_version: {}.{}.{},
+}}
+            "#,
+                app_name,
+                self.application_version.major,
+                self.application_version.minor,
+                self.application_version.patch,
+                engine_name,
+                self.engine_version.major,
+                self.engine_version.minor,
+                self.engine_version.patch,
+                api_version.major,
+                api_version.minor,
+                api_version.patch,
+            )
+        }
+
+        let debug_utils_enabled = enabled_extensions.contains(&DEBUG_UTILS_EXT_NAME);
+
         let enabled_extension_ptr = enabled_extensions
             .iter()
             .map(|e| e.as_ptr())
@@ -459,16 +992,45 @@ Application info: {{
             .enabled_extension_names(&enabled_extension_ptr)
             .enabled_layer_names(&enabled_layers_ptr);
 
+        let mut enabled_validation_features = self.enabled_validation_features.clone();
+        if self.debug_printf
+            && !enabled_validation_features.contains(&vk::ValidationFeatureEnableEXT::DEBUG_PRINTF)
+        {
+            enabled_validation_features.push(vk::ValidationFeatureEnableEXT::DEBUG_PRINTF);
+        }
+
         let mut features = vk::ValidationFeaturesEXT::builder()
             .disabled_validation_features(&self.disabled_validation_features)
-            .enabled_validation_features(&self.enabled_validation_features);
+            .enabled_validation_features(&enabled_validation_features);
 
-        if !self.enabled_validation_features.is_empty()
-            || !self.disabled_validation_features.is_empty()
+        if !enabled_validation_features.is_empty() || !self.disabled_validation_features.is_empty()
         {
             instance_create_info = instance_create_info.push_next(&mut features);
         };
 
+        let mut layer_settings = if layer_settings_enabled {
+            moltenvk::layer_settings(&moltenvk_settings)
+        } else {
+            vec![]
+        };
+        // The validation layer reads its settings from the chain even when
+        // VK_EXT_layer_settings isn't exposed by the loader.
+        let printf_buffer_size = self.debug_printf_buffer_size.unwrap_or_default();
+        if self.debug_printf && self.debug_printf_buffer_size.is_some() {
+            layer_settings.push(vk::LayerSettingEXT {
+                layer_name: VALIDATION_LAYER_NAME.as_ptr(),
+                setting_name: c"printf_buffer_size".as_ptr(),
+                type_: vk::LayerSettingTypeEXT::UINT32,
+                value_count: 1,
+                values: &printf_buffer_size as *const u32 as _,
+            });
+        }
+        let mut layer_settings_info =
+            vk::LayerSettingsCreateInfoEXT::builder().settings(&layer_settings);
+        if !layer_settings.is_empty() {
+            instance_create_info = instance_create_info.push_next(&mut layer_settings_info);
+        }
+
         let mut checks = vk::ValidationFlagsEXT::builder();
         if !self.disabled_validation_checks.is_empty() {
             checks = checks.disabled_validation_checks(&self.disabled_validation_checks);
@@ -476,6 +1038,26 @@ Application info: {{
             instance_create_info = instance_create_info.push_next(&mut checks);
         };
 
+        let debug_message_severity = if self.debug_printf {
+            self.debug_message_severity | vk::DebugUtilsMessageSeverityFlagsEXT::INFO
+        } else {
+            self.debug_message_severity
+        };
+
+        let mut debug_sink = if self.use_debug_messenger && self.builtin_debug_messenger {
+            let mut sink = DebugSink::new(
+                self.debug_output,
+                self.colored_debug_output,
+                self.debug_message_repeat_limit,
+            )
+            .map_err(|e| crate::InstanceError::FailedOpenDebugOutput(e.to_string()))?;
+            sink.severity = self.debug_message_severity;
+            sink.printf_callback = self.debug_printf_callback.clone();
+            Some(Box::new(sink))
+        } else {
+            None
+        };
+
         let instance = unsafe {
             system_info
                 .entry
@@ -491,10 +1073,13 @@ Application info: {{
 
         if self.use_debug_messenger {
             let messenger_create_info = vk::DebugUtilsMessengerCreateInfoEXT::builder()
-                .message_severity(self.debug_message_severity)
+                .message_severity(debug_message_severity)
                 .message_type(self.debug_message_type)
-                .user_callback(self.debug_callback)
-                .user_data(&mut debug_user_data);
+                .user_callback(self.debug_callback);
+            let messenger_create_info = match debug_sink.as_deref_mut() {
+                Some(sink) => messenger_create_info.user_data(sink),
+                None => messenger_create_info.user_data(&mut debug_user_data),
+            };
 
             #[cfg(feature = "enable_tracing")]
             tracing::trace!(?self.debug_callback, "Using debug messenger");
@@ -512,17 +1097,47 @@ Application info: {{
             });
             #[cfg(feature = "enable_tracing")]
             tracing::info!("Created vkSurfaceKhr")
+        } else if use_display && let Some(selection) = self.display {
+            surface = Some(display::create_display_surface(
+                &instance,
+                selection,
+                self.allocation_callbacks.as_ref(),
+            )?);
+            #[cfg(feature = "enable_tracing")]
+            tracing::info!("Created display vkSurfaceKhr")
         };
 
+        let live_objects = LiveObjects::default();
+        if let Some(messenger) = debug_messenger {
+            live_objects.track(
+                vk::ObjectType::DEBUG_UTILS_MESSENGER_EXT,
+                messenger.as_raw(),
+            );
+        }
+
         Ok(Arc::new(Instance {
             instance,
             surface,
+            enabled_extensions,
+            enabled_layers,
             allocation_callbacks: self.allocation_callbacks,
+            allocator: self.allocator,
             instance_version,
             api_version,
             properties2_ext_enabled,
+            debug_utils_enabled,
+            portability_enumeration_enabled,
+            surface_capabilities2_enabled,
+            surface_protected_capabilities_enabled,
+            surface_maintenance1_enabled,
+            metal_objects_requested: self
+                .moltenvk
+                .as_ref()
+                .is_some_and(|config| config.enable_metal_objects),
             debug_messenger,
-            _system_info: system_info,
+            _debug_sink: debug_sink,
+            live_objects,
+            system_info,
         }))
     }
 }
@@ -531,16 +1146,79 @@ Application info: {{
 pub struct Instance {
     pub(crate) instance: vulkanalia::Instance,
     pub(crate) allocation_callbacks: Option<AllocationCallbacks>,
+    allocator: Option<TrackedAllocationCallbacks>,
     pub(crate) surface: Option<vk::SurfaceKHR>,
+    pub(crate) enabled_extensions: Vec<vk::ExtensionName>,
+    pub(crate) enabled_layers: Vec<vk::ExtensionName>,
     pub(crate) instance_version: Version,
     pub api_version: Version,
     pub(crate) properties2_ext_enabled: bool,
+    pub(crate) debug_utils_enabled: bool,
+    pub(crate) portability_enumeration_enabled: bool,
+    pub(crate) surface_capabilities2_enabled: bool,
+    pub(crate) surface_protected_capabilities_enabled: bool,
+    pub(crate) surface_maintenance1_enabled: bool,
+    pub(crate) metal_objects_requested: bool,
     pub(crate) debug_messenger: Option<DebugUtilsMessengerEXT>,
-    _system_info: SystemInfo,
+    _debug_sink: Option<Box<DebugSink>>,
+    pub(crate) live_objects: LiveObjects,
+    pub(crate) system_info: SystemInfo,
 }
 
 impl Instance {
+    /// Layers and extensions of the system the instance was created on. Pass it to
+    /// [`InstanceBuilder::system_info`] to create further instances without querying again.
+    pub fn system_info(&self) -> &SystemInfo {
+        &self.system_info
+    }
+
+    /// Instance extensions passed to `vkCreateInstance`: the requested ones plus those added for
+    /// windowing, debug utils, portability and the other builder options.
+    pub fn enabled_extensions(&self) -> &[vk::ExtensionName] {
+        &self.enabled_extensions
+    }
+
+    /// Layers passed to `vkCreateInstance`, including the validation layer and the layers enabled
+    /// for the extensions they provide.
+    pub fn enabled_layers(&self) -> &[vk::ExtensionName] {
+        &self.enabled_layers
+    }
+
+    /// Whether `extension` was enabled when creating the instance.
+    pub fn is_extension_enabled(&self, extension: &vk::ExtensionName) -> bool {
+        self.enabled_extensions.contains(extension)
+    }
+
+    /// Displays and their modes on every physical device. Requires an instance built with
+    /// [`InstanceBuilder::direct_to_display`] (or with `VK_KHR_display` enabled manually).
+    pub fn displays(&self) -> crate::Result<Vec<DisplayInfo>> {
+        display::enumerate_displays(&self.instance)
+    }
+
+    /// The allocator set with [`InstanceBuilder::allocator`].
+    pub fn allocator(&self) -> Option<&TrackedAllocationCallbacks> {
+        self.allocator.as_ref()
+    }
+
+    /// Destroy the instance. In debug builds, objects created through the crate that are still
+    /// alive are reported first, with their creation backtraces when the `leak_backtraces`
+    /// feature is enabled. Allocations of the instance allocator still alive afterwards are
+    /// reported too.
     pub fn destroy(&self) {
+        // The messenger belongs to the instance and is destroyed with it below.
+        if let Some(debug_messenger) = self.debug_messenger {
+            self.live_objects.untrack(
+                vk::ObjectType::DEBUG_UTILS_MESSENGER_EXT,
+                debug_messenger.as_raw(),
+            );
+        }
+        if let Some(report) = self.live_objects.report() {
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!("{report}");
+            #[cfg(not(feature = "enable_tracing"))]
+            eprintln!("{report}");
+        }
+
         unsafe {
             if let Some(debug_messenger) = self.debug_messenger {
                 self.instance.destroy_debug_utils_messenger_ext(
@@ -555,6 +1233,19 @@ impl Instance {
             self.instance
                 .destroy_instance(self.allocation_callbacks.as_ref());
         }
+
+        if let Some(allocator) = &self.allocator
+            && allocator.live_allocations() > 0
+        {
+            let report = format!(
+                "{} host allocation(s) still alive when the instance was destroyed",
+                allocator.live_allocations()
+            );
+            #[cfg(feature = "enable_tracing")]
+            tracing::warn!("{report}");
+            #[cfg(not(feature = "enable_tracing"))]
+            eprintln!("{report}");
+        }
     }
 }
 
@@ -566,7 +1257,21 @@ impl AsRef<vulkanalia::Instance> for Instance {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
     fn compiles() {}
+
+    #[test]
+    fn config_round_trip() {
+        let config = InstanceConfig {
+            app_name: "app".to_string(),
+            request_validation_layers: true,
+            extensions: vec![DEBUG_UTILS_EXT_NAME],
+            ..Default::default()
+        };
+
+        let builder = InstanceBuilder::new(None).with_config(config.clone());
+        assert_eq!(builder.to_config(), config);
+    }
 }
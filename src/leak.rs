@@ -0,0 +1,108 @@
+use std::fmt::Write;
+use std::sync::Mutex;
+use vulkanalia::vk;
+
+#[derive(Debug)]
+struct LiveObject {
+    object_type: vk::ObjectType,
+    handle: u64,
+    name: String,
+    #[cfg(feature = "leak_backtraces")]
+    backtrace: std::backtrace::Backtrace,
+}
+
+/// Objects created by the crate that are still alive, reported when the instance is destroyed.
+///
+/// Tracking only happens in debug builds. Creation backtraces are captured with the
+/// `leak_backtraces` feature.
+#[derive(Debug, Default)]
+pub(crate) struct LiveObjects {
+    objects: Mutex<Vec<LiveObject>>,
+}
+
+impl LiveObjects {
+    pub(crate) fn track(&self, object_type: vk::ObjectType, handle: u64) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        if let Ok(mut objects) = self.objects.lock() {
+            objects.push(LiveObject {
+                object_type,
+                handle,
+                name: String::new(),
+                #[cfg(feature = "leak_backtraces")]
+                backtrace: std::backtrace::Backtrace::force_capture(),
+            });
+        }
+    }
+
+    pub(crate) fn untrack(&self, object_type: vk::ObjectType, handle: u64) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        if let Ok(mut objects) = self.objects.lock() {
+            objects.retain(|object| object.object_type != object_type || object.handle != handle);
+        }
+    }
+
+    pub(crate) fn set_name(&self, object_type: vk::ObjectType, handle: u64, name: &str) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        if let Ok(mut objects) = self.objects.lock()
+            && let Some(object) = objects
+                .iter_mut()
+                .find(|object| object.object_type == object_type && object.handle == handle)
+        {
+            object.name = name.to_string();
+        }
+    }
+
+    /// Describe every object that is still alive, or `None` when nothing leaked.
+    pub(crate) fn report(&self) -> Option<String> {
+        let objects = self.objects.lock().ok()?;
+        if objects.is_empty() {
+            return None;
+        }
+
+        let mut report = format!(
+            "{} object(s) still alive when the instance was destroyed:",
+            objects.len()
+        );
+        for object in objects.iter() {
+            let _ = write!(
+                report,
+                "\n  {:?} {:#x} \"{}\"",
+                object.object_type, object.handle, object.name
+            );
+            #[cfg(feature = "leak_backtraces")]
+            let _ = write!(report, "\n    created at:\n{}", object.backtrace);
+        }
+
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_live_objects() {
+        let live_objects = LiveObjects::default();
+        live_objects.track(vk::ObjectType::SWAPCHAIN_KHR, 1);
+        live_objects.track(vk::ObjectType::IMAGE_VIEW, 2);
+        live_objects.set_name(vk::ObjectType::IMAGE_VIEW, 2, "backbuffer");
+        live_objects.untrack(vk::ObjectType::SWAPCHAIN_KHR, 1);
+
+        let report = live_objects.report().unwrap();
+        assert!(report.starts_with("1 object(s)"));
+        assert!(report.contains("IMAGE_VIEW 0x2 \"backbuffer\""));
+
+        live_objects.untrack(vk::ObjectType::IMAGE_VIEW, 2);
+        assert!(live_objects.report().is_none());
+    }
+}
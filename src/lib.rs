@@ -9,12 +9,18 @@
 //! It tries to not be in the way as much as possible in the rest of your Vulkan application.   
 //!
 //! ``` no_run
+//! # use std::sync::Arc;
+//! # use vulkanalia::vk::DeviceV1_0;
+//! # use vulkanalia_bootstrap::{
+//! #     DeviceBuilder, InstanceBuilder, PhysicalDeviceSelector, PreferredDeviceType, QueueType,
+//! #     SwapchainBuilder,
+//! # };
 //! fn main() -> anyhow::Result<()> {
 //!    let instance = InstanceBuilder::new(None)
 //!        .app_name("Example Vulkan Application")
 //!        .engine_name("Example Vulkan Engine")
 //!        .request_validation_layers(true)
-//!        .use_default_tracing_messenger()
+//!        .use_default_debug_messenger()
 //!        .build()?;
 //!
 //!    let physical_device = PhysicalDeviceSelector::new(instance.clone())
@@ -38,20 +44,79 @@
 //!    swapchain.destroy();
 //!    device.destroy();
 //!    instance.destroy();
+//!
+//!    Ok(())
 //!}
 //! ```
 
+mod allocator;
+mod command;
+#[cfg(feature = "serde")]
+mod config_serde;
+mod debug_output;
+mod descriptor;
 mod device;
+mod display;
 mod error;
+mod frame;
+mod image;
 mod instance;
+mod leak;
+mod moltenvk;
+mod offscreen;
+mod readback;
+#[cfg(feature = "sdl2")]
+mod sdl2_window;
+mod staging;
+#[cfg(feature = "starter")]
+mod starter;
+mod stream;
+mod summary;
 mod swapchain;
+mod sync_pool;
 mod system_info;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod thread_audit;
+mod timeline;
 #[cfg(feature = "enable_tracing")]
 mod tracing;
+mod upload;
+#[cfg(feature = "winit")]
+mod winit_resize;
 
+pub use allocator::{Allocator, SystemAllocator, TrackedAllocationCallbacks};
+pub use command::CommandManager;
+pub use debug_output::DebugOutput;
+pub use descriptor::{DescriptorAllocator, PoolSizeRatio};
 pub use device::{
-    Device, DeviceBuilder, PhysicalDevice, PhysicalDeviceSelector, PreferredDeviceType, QueueType,
+    CmdLabelScope, ComputeCapabilities, DepthStencilResolveSupport, DescriptorIndexingLimits,
+    Device, DeviceBuilder, DeviceConfig, DevicePlan, DeviceQueues, DriverInfo,
+    FragmentShadingRateSupport, LimitRequirement, MemoryHeapBudget, NameMatch, PhysicalDevice,
+    PhysicalDeviceId, PhysicalDeviceSelector, PowerPreference, PreferredDeviceType,
+    PropertiesRequest, QueueAccess, QueueFamilyOwnershipTransfer, QueueLabelScope, QueueType,
+    RankedPhysicalDevice, ResourceSharing, SubgroupCapabilities, VulkanPhysicalDeviceFeature2,
 };
+pub use display::{DisplayInfo, DisplaySelection};
 pub use error::*;
-pub use instance::{Instance, InstanceBuilder};
-pub use swapchain::{Swapchain, SwapchainBuilder};
+pub use frame::{FrameContext, Resettable};
+pub use image::{AllocatedImage, ImageDesc};
+pub use instance::{Instance, InstanceBuilder, InstanceConfig, InstancePlan, RawWindow};
+pub use moltenvk::MoltenVkConfig;
+pub use offscreen::OffscreenTarget;
+pub use readback::{remove_row_padding, srgb_to_linear, swizzle_bgra_rgba, to_rgba8};
+pub use staging::StagingBelt;
+#[cfg(feature = "starter")]
+pub use starter::TriangleStarter;
+pub use stream::{StreamFrame, StreamTarget};
+pub use summary::{CreationSummary, SwapchainSummary};
+pub use swapchain::{
+    BufferMode, PresentModeImageCount, SurfaceSupport, Swapchain, SwapchainBuilder,
+    SwapchainCandidate, SwapchainConfig, SwapchainImage, SwapchainPlan,
+};
+pub use sync_pool::SyncPool;
+pub use system_info::SystemInfo;
+pub use timeline::TimelineSemaphore;
+pub use upload::UploadScheduler;
+#[cfg(feature = "winit")]
+pub use winit_resize::WinitSwapchain;
@@ -0,0 +1,69 @@
+use std::ffi::CStr;
+use vulkanalia::vk;
+
+const MOLTENVK_LAYER_NAME: &CStr = c"MoltenVK";
+
+/// MoltenVK settings applied when the instance is created.
+///
+/// Settings left at `None` keep MoltenVK's default (or the value of the matching `MVK_CONFIG_*`
+/// environment variable). They are passed through `VK_EXT_layer_settings`, which MoltenVK
+/// exposes since 1.2.7; on other drivers the settings are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct MoltenVkConfig {
+    /// `MVK_CONFIG_USE_METAL_ARGUMENT_BUFFERS`: back descriptor sets with Metal argument buffers.
+    pub use_metal_argument_buffers: Option<bool>,
+    /// `MVK_CONFIG_SYNCHRONOUS_QUEUE_SUBMITS`: block `vkQueueSubmit` until the Metal command
+    /// buffers are committed.
+    pub synchronous_queue_submits: Option<bool>,
+    /// `MVK_CONFIG_RESUME_LOST_DEVICE`: keep using the device after `VK_ERROR_DEVICE_LOST`.
+    pub resume_lost_device: Option<bool>,
+    /// `MVK_CONFIG_SHOULD_MAXIMIZE_CONCURRENT_COMPILATION`: compile shaders on as many threads
+    /// as the system allows.
+    pub maximize_concurrent_compilation: Option<bool>,
+    /// Enable `VK_EXT_metal_objects` on the selected device when it supports it, to import and
+    /// export the underlying Metal objects.
+    pub enable_metal_objects: bool,
+}
+
+impl MoltenVkConfig {
+    /// Setting names and `VkBool32` values of every setting that was set.
+    pub(crate) fn settings(&self) -> Vec<(&'static CStr, vk::Bool32)> {
+        [
+            (
+                c"MVK_CONFIG_USE_METAL_ARGUMENT_BUFFERS",
+                self.use_metal_argument_buffers,
+            ),
+            (
+                c"MVK_CONFIG_SYNCHRONOUS_QUEUE_SUBMITS",
+                self.synchronous_queue_submits,
+            ),
+            (c"MVK_CONFIG_RESUME_LOST_DEVICE", self.resume_lost_device),
+            (
+                c"MVK_CONFIG_SHOULD_MAXIMIZE_CONCURRENT_COMPILATION",
+                self.maximize_concurrent_compilation,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value as vk::Bool32)))
+        .collect()
+    }
+}
+
+/// Layer settings for `settings`, which must outlive the returned structs.
+pub(crate) fn layer_settings(settings: &[(&'static CStr, vk::Bool32)]) -> Vec<vk::LayerSettingEXT> {
+    settings
+        .iter()
+        .map(|(name, value)| vk::LayerSettingEXT {
+            layer_name: MOLTENVK_LAYER_NAME.as_ptr(),
+            setting_name: name.as_ptr(),
+            type_: vk::LayerSettingTypeEXT::BOOL32,
+            value_count: 1,
+            values: value as *const vk::Bool32 as _,
+        })
+        .collect()
+}
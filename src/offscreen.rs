@@ -0,0 +1,186 @@
+use crate::device::QueueType;
+use crate::{AllocatedImage, Device, ImageDesc, SwapchainImage};
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, HasBuilder};
+
+/// Color images to render to without a surface, for headless rendering and tests.
+///
+/// Mirrors the acquire/present shape of a [`crate::Swapchain`] so render code can be shared:
+/// [`OffscreenTarget::acquire_next_image`] rotates through the images and waits until the
+/// work of the previous [`OffscreenTarget::present`] of that image finished, and
+/// [`OffscreenTarget::read_pixels`] replaces looking at the window.
+///
+/// Images must be in `vk::ImageLayout::TRANSFER_SRC_OPTIMAL` when presented, where a swapchain
+/// expects `PRESENT_SRC_KHR`.
+#[derive(Debug)]
+pub struct OffscreenTarget {
+    device: Arc<Device>,
+    queue: vk::Queue,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    images: Vec<AllocatedImage>,
+    fences: Vec<vk::Fence>,
+    next_image: usize,
+}
+
+impl OffscreenTarget {
+    /// Create `image_count` color images of the given format and extent. Presents are submitted
+    /// on the graphics queue.
+    pub fn new(
+        device: Arc<Device>,
+        format: vk::Format,
+        extent: vk::Extent2D,
+        image_count: u32,
+    ) -> crate::Result<Self> {
+        let (_, queue) = device.get_queue(QueueType::Graphics)?;
+        let mut target = Self {
+            device,
+            queue,
+            format,
+            extent,
+            images: Vec::with_capacity(image_count as _),
+            fences: Vec::with_capacity(image_count as _),
+            next_image: 0,
+        };
+
+        let desc = ImageDesc::new_2d(
+            format,
+            extent,
+            vk::ImageUsageFlags::COLOR_ATTACHMENT
+                | vk::ImageUsageFlags::TRANSFER_SRC
+                | vk::ImageUsageFlags::TRANSFER_DST,
+        )
+        .priority(1.0);
+        let fence_info = vk::FenceCreateInfo::builder().flags(vk::FenceCreateFlags::SIGNALED);
+        for index in 0..image_count.max(1) {
+            let created = target
+                .device
+                .create_image(&format!("offscreen target[{index}]"), &desc)
+                .and_then(|image| {
+                    target.images.push(image);
+                    let fence = unsafe {
+                        target
+                            .device
+                            .create_fence(&fence_info, target.device.allocation_callbacks())
+                    }?;
+                    target.fences.push(fence);
+                    Ok(())
+                });
+            if let Err(e) = created {
+                target.destroy();
+                return Err(e);
+            }
+        }
+
+        Ok(target)
+    }
+
+    pub fn format(&self) -> vk::Format {
+        self.format
+    }
+
+    pub fn extent(&self) -> vk::Extent2D {
+        self.extent
+    }
+
+    pub fn image_count(&self) -> u32 {
+        self.images.len() as u32
+    }
+
+    /// Images paired with their views, like [`crate::Swapchain::images`].
+    pub fn images(&self) -> Vec<SwapchainImage> {
+        self.images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| SwapchainImage {
+                index: index as u32,
+                image: image.image,
+                view: image.view,
+                format: self.format,
+            })
+            .collect()
+    }
+
+    /// Wait until the work presented with the next image finished and return its index.
+    pub fn acquire_next_image(&mut self) -> crate::Result<u32> {
+        let index = self.next_image;
+        unsafe {
+            self.device
+                .wait_for_fences(&[self.fences[index]], true, u64::MAX)
+        }?;
+        self.next_image = (index + 1) % self.images.len();
+
+        Ok(index as u32)
+    }
+
+    /// Mark `image_index` as done once `wait_semaphores` signal.
+    ///
+    /// The image must be in `vk::ImageLayout::TRANSFER_SRC_OPTIMAL`. Nothing is shown, the
+    /// semaphores are waited on so they can be reused like after a swapchain present.
+    pub fn present(
+        &mut self,
+        image_index: u32,
+        wait_semaphores: &[vk::Semaphore],
+    ) -> crate::Result<()> {
+        let fence = *self.fences.get(image_index as usize).ok_or(
+            crate::ConfigError::ImageIndexOutOfBounds {
+                index: image_index,
+                count: self.fences.len() as u32,
+            },
+        )?;
+
+        let wait_stages = vec![vk::PipelineStageFlags::ALL_COMMANDS; wait_semaphores.len()];
+        let submit_info = vk::SubmitInfo::builder()
+            .wait_semaphores(wait_semaphores)
+            .wait_dst_stage_mask(&wait_stages);
+        unsafe {
+            self.device.reset_fences(&[fence])?;
+            let _access = self.device.audit_queue(self.queue);
+            self.device
+                .queue_submit(self.queue, &[submit_info], fence)?;
+        }
+
+        Ok(())
+    }
+
+    /// Copy presented image `image_index` to the host as tightly packed RGBA8, see
+    /// [`crate::Swapchain::read_pixels`]. Waits for the presented work first.
+    pub fn read_pixels(&self, image_index: u32) -> crate::Result<Vec<u8>> {
+        let image = self.images.get(image_index as usize).ok_or(
+            crate::ConfigError::ImageIndexOutOfBounds {
+                index: image_index,
+                count: self.images.len() as u32,
+            },
+        )?;
+        unsafe {
+            self.device
+                .wait_for_fences(&[self.fences[image_index as usize]], true, u64::MAX)
+        }?;
+
+        crate::readback::read_image_rgba8(
+            &self.device,
+            image.image,
+            self.format,
+            self.extent,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            QueueType::Graphics,
+        )
+    }
+
+    /// Destroy the images and fences. Waits for presented work first.
+    pub fn destroy(&self) {
+        unsafe {
+            if !self.fences.is_empty() {
+                let _ = self.device.wait_for_fences(&self.fences, true, u64::MAX);
+            }
+            for &fence in &self.fences {
+                self.device
+                    .destroy_fence(fence, self.device.allocation_callbacks());
+            }
+        }
+        for image in &self.images {
+            image.destroy(&self.device);
+        }
+    }
+}
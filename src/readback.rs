@@ -0,0 +1,346 @@
+use crate::Device;
+use crate::device::QueueType;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, HasBuilder};
+
+/// Size in bytes of a single texel for the color formats that can be read back.
+pub(crate) fn format_texel_size(format: vk::Format) -> Option<u64> {
+    match format {
+        vk::Format::R8G8B8A8_UNORM
+        | vk::Format::R8G8B8A8_SRGB
+        | vk::Format::B8G8R8A8_UNORM
+        | vk::Format::B8G8R8A8_SRGB
+        | vk::Format::A2B10G10R10_UNORM_PACK32
+        | vk::Format::A2R10G10B10_UNORM_PACK32 => Some(4),
+        vk::Format::R16G16B16A16_SFLOAT => Some(8),
+        vk::Format::R32G32B32A32_SFLOAT => Some(16),
+        _ => None,
+    }
+}
+
+/// Whether [`to_rgba8`] swaps red and blue for `format`, `None` when it can't convert it.
+pub(crate) fn rgba8_swizzle(format: vk::Format) -> Option<bool> {
+    match format {
+        vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB => Some(false),
+        vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB => Some(true),
+        _ => None,
+    }
+}
+
+/// Swap the red and blue channels of 4-byte texels in place (BGRA <-> RGBA).
+pub fn swizzle_bgra_rgba(data: &mut [u8]) {
+    for texel in data.chunks_exact_mut(4) {
+        texel.swap(0, 2);
+    }
+}
+
+/// Decode sRGB-encoded RGBA8 texels to linear values in place. Alpha is left untouched.
+pub fn srgb_to_linear(data: &mut [u8]) {
+    let table: [u8; 256] = std::array::from_fn(|i| {
+        let c = i as f32 / 255.0;
+        let linear = if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        };
+        (linear * 255.0).round() as u8
+    });
+
+    for texel in data.chunks_exact_mut(4) {
+        for channel in &mut texel[..3] {
+            *channel = table[*channel as usize];
+        }
+    }
+}
+
+/// Copy `height` rows of `row_size` bytes out of a buffer whose rows are `row_pitch` bytes apart,
+/// producing tightly packed rows.
+///
+/// Returns `None` when `row_pitch` is smaller than `row_size` or `data` is too short to hold
+/// every row, including a truncated last row.
+pub fn remove_row_padding(
+    data: &[u8],
+    row_size: usize,
+    row_pitch: usize,
+    height: usize,
+) -> Option<Vec<u8>> {
+    if row_pitch < row_size {
+        return None;
+    }
+    if height == 0 {
+        return Some(Vec::new());
+    }
+
+    let required = row_pitch.checked_mul(height - 1)?.checked_add(row_size)?;
+    if data.len() < required {
+        return None;
+    }
+
+    if row_pitch == row_size {
+        return Some(data[..required].to_vec());
+    }
+
+    let mut packed = Vec::with_capacity(row_size * height);
+    for row in data.chunks(row_pitch).take(height) {
+        packed.extend_from_slice(&row[..row_size]);
+    }
+
+    Some(packed)
+}
+
+/// Convert a readback of a 8-bit RGBA/BGRA image into tightly packed RGBA8.
+///
+/// `row_pitch` is the distance in bytes between rows in `data`. sRGB formats keep their encoded
+/// values; run [`srgb_to_linear`] on the result when linear values are needed.
+/// Returns `None` for formats other than `R8G8B8A8_*` and `B8G8R8A8_*`, or when `data` doesn't
+/// fit the extent and row pitch (see [`remove_row_padding`]).
+pub fn to_rgba8(
+    data: &[u8],
+    format: vk::Format,
+    extent: vk::Extent2D,
+    row_pitch: usize,
+) -> Option<Vec<u8>> {
+    let swizzle = rgba8_swizzle(format)?;
+
+    let row_size = extent.width as usize * 4;
+    let mut rgba = remove_row_padding(data, row_size, row_pitch, extent.height as usize)?;
+    if swizzle {
+        swizzle_bgra_rgba(&mut rgba);
+    }
+
+    Some(rgba)
+}
+
+/// Objects created by [`read_image_rgba8`], null until created.
+#[derive(Default)]
+struct Readback {
+    command_pool: vk::CommandPool,
+    buffer: vk::Buffer,
+    memory: vk::DeviceMemory,
+    fence: vk::Fence,
+}
+
+/// Copy a single-layer color image in `layout` to the host as tightly packed RGBA8 on `queue`,
+/// leaving it in `layout`. Blocks until the copy finished.
+pub(crate) fn read_image_rgba8(
+    device: &Device,
+    image: vk::Image,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    layout: vk::ImageLayout,
+    queue: QueueType,
+) -> crate::Result<Vec<u8>> {
+    if rgba8_swizzle(format).is_none() {
+        return Err(crate::CreateError {
+            kind: vk::ObjectType::BUFFER,
+            name: "readback".to_string(),
+            details: format!("{format:?} cannot be read back as RGBA8"),
+            code: vk::ErrorCode::FORMAT_NOT_SUPPORTED,
+        }
+        .into());
+    }
+
+    let mut readback = Readback::default();
+    let result = copy_to_host(device, image, format, extent, layout, queue, &mut readback);
+
+    let allocation_callbacks = device.allocation_callbacks();
+    unsafe {
+        device.destroy_fence(readback.fence, allocation_callbacks);
+        device.destroy_command_pool(readback.command_pool, allocation_callbacks);
+        device.destroy_buffer(readback.buffer, allocation_callbacks);
+        device.free_memory(readback.memory, allocation_callbacks);
+    }
+
+    result
+}
+
+fn copy_to_host(
+    device: &Device,
+    image: vk::Image,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    layout: vk::ImageLayout,
+    queue: QueueType,
+    readback: &mut Readback,
+) -> crate::Result<Vec<u8>> {
+    let allocation_callbacks = device.allocation_callbacks();
+    let (queue_family_index, queue) = device.get_queue(queue)?;
+    let row_pitch = extent.width as usize * 4;
+    let size = (row_pitch * extent.height as usize) as vk::DeviceSize;
+
+    let buffer_info = vk::BufferCreateInfo::builder()
+        .size(size)
+        .usage(vk::BufferUsageFlags::TRANSFER_DST)
+        .sharing_mode(vk::SharingMode::EXCLUSIVE);
+    readback.buffer = unsafe { device.create_buffer(&buffer_info, allocation_callbacks) }?;
+
+    let requirements = unsafe { device.get_buffer_memory_requirements(readback.buffer) };
+    let flags = vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT;
+    let memory_type_index = device
+        .physical_device()
+        .memory_type_index(requirements.memory_type_bits, flags)
+        .ok_or(crate::Error::NoSuitableMemoryType {
+            type_bits: requirements.memory_type_bits,
+            flags,
+        })?;
+    let allocate_info = vk::MemoryAllocateInfo::builder()
+        .allocation_size(requirements.size)
+        .memory_type_index(memory_type_index);
+    readback.memory = unsafe { device.allocate_memory(&allocate_info, allocation_callbacks) }?;
+    unsafe { device.bind_buffer_memory(readback.buffer, readback.memory, 0) }?;
+
+    let command_pool_info = vk::CommandPoolCreateInfo::builder()
+        .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+        .queue_family_index(queue_family_index as u32);
+    readback.command_pool =
+        unsafe { device.create_command_pool(&command_pool_info, allocation_callbacks) }?;
+    let command_buffer_info = vk::CommandBufferAllocateInfo::builder()
+        .command_pool(readback.command_pool)
+        .level(vk::CommandBufferLevel::PRIMARY)
+        .command_buffer_count(1);
+    let command_buffer = unsafe { device.allocate_command_buffers(&command_buffer_info) }?[0];
+    readback.fence =
+        unsafe { device.create_fence(&vk::FenceCreateInfo::builder(), allocation_callbacks) }?;
+
+    let layout_barrier = |old_layout, new_layout, src_access, dst_access| {
+        vk::ImageMemoryBarrier::builder()
+            .src_access_mask(src_access)
+            .dst_access_mask(dst_access)
+            .old_layout(old_layout)
+            .new_layout(new_layout)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(
+                vk::ImageSubresourceRange::builder()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .level_count(1)
+                    .layer_count(1),
+            )
+            .build()
+    };
+
+    unsafe {
+        let begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+        device.begin_command_buffer(command_buffer, &begin_info)?;
+
+        let to_transfer = layout_barrier(
+            layout,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            vk::AccessFlags::MEMORY_WRITE,
+            vk::AccessFlags::TRANSFER_READ,
+        );
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            vk::PipelineStageFlags::ALL_COMMANDS,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::DependencyFlags::empty(),
+            &[] as &[vk::MemoryBarrier],
+            &[] as &[vk::BufferMemoryBarrier],
+            &[to_transfer],
+        );
+
+        let region = vk::BufferImageCopy::builder()
+            .image_subresource(
+                vk::ImageSubresourceLayers::builder()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .layer_count(1),
+            )
+            .image_extent(vk::Extent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            });
+        device.cmd_copy_image_to_buffer(
+            command_buffer,
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            readback.buffer,
+            &[region],
+        );
+
+        let to_present = layout_barrier(
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            layout,
+            vk::AccessFlags::TRANSFER_READ,
+            vk::AccessFlags::empty(),
+        );
+        let to_host = vk::BufferMemoryBarrier::builder()
+            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(vk::AccessFlags::HOST_READ)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .buffer(readback.buffer)
+            .size(vk::WHOLE_SIZE);
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::PipelineStageFlags::HOST | vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+            vk::DependencyFlags::empty(),
+            &[] as &[vk::MemoryBarrier],
+            &[to_host],
+            &[to_present],
+        );
+
+        device.end_command_buffer(command_buffer)?;
+
+        let command_buffers = [command_buffer];
+        let submit_info = vk::SubmitInfo::builder().command_buffers(&command_buffers);
+        {
+            let _access = device.audit_queue(queue);
+            device.queue_submit(queue, &[submit_info], readback.fence)?;
+        }
+        device.wait_for_fences(&[readback.fence], true, u64::MAX)?;
+
+        let data = device.map_memory(readback.memory, 0, size, vk::MemoryMapFlags::empty())?;
+        let bytes = std::slice::from_raw_parts(data as *const u8, size as usize);
+        let rgba = to_rgba8(bytes, format, extent, row_pitch);
+        device.unmap_memory(readback.memory);
+
+        Ok(rgba.ok_or(vk::ErrorCode::FORMAT_NOT_SUPPORTED)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bgra_padded_to_rgba8() {
+        // 1x2 image, rows padded to 8 bytes.
+        let data = [3, 2, 1, 4, 0, 0, 0, 0, 7, 6, 5, 8, 0, 0, 0, 0];
+        let extent = vk::Extent2D {
+            width: 1,
+            height: 2,
+        };
+
+        let rgba = to_rgba8(&data, vk::Format::B8G8R8A8_UNORM, extent, 8).unwrap();
+        assert_eq!(rgba, [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn row_padding_rejects_short_data() {
+        let data = [1, 2, 0, 3, 4, 0];
+        assert_eq!(remove_row_padding(&data, 2, 3, 2), Some(vec![1, 2, 3, 4]));
+        assert_eq!(
+            remove_row_padding(&data[..5], 2, 3, 2),
+            Some(vec![1, 2, 3, 4])
+        );
+        assert_eq!(remove_row_padding(&data[..4], 2, 3, 2), None);
+        assert_eq!(remove_row_padding(&data, 2, 3, 3), None);
+        assert_eq!(remove_row_padding(&data, 4, 3, 1), None);
+        assert_eq!(remove_row_padding(&data, 2, 2, 4), None);
+        assert_eq!(remove_row_padding(&[], 2, 3, 0), Some(vec![]));
+    }
+
+    #[test]
+    fn srgb_decode_keeps_endpoints_and_alpha() {
+        let mut data = [0, 255, 188, 128];
+        srgb_to_linear(&mut data);
+        assert_eq!(data[0], 0);
+        assert_eq!(data[1], 255);
+        assert_eq!(data[2], 128);
+        assert_eq!(data[3], 128);
+    }
+}
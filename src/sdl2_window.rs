@@ -0,0 +1,41 @@
+use crate::{InstanceBuilder, RawWindow};
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+use std::sync::Arc;
+use vulkanalia::vk;
+
+impl InstanceBuilder {
+    /// Start an instance for an SDL2 window.
+    ///
+    /// The surface is created from the window's raw handles, but the instance extensions are
+    /// the ones SDL reports (`SDL_Vulkan_GetInstanceExtensions`), which can differ from what the
+    /// handle type implies, for example when SDL runs through XWayland. The window must be
+    /// created with `WindowBuilder::vulkan`.
+    ///
+    /// # Safety
+    ///
+    /// `window` must stay alive until the instance, and the surface created for it, is
+    /// destroyed.
+    pub unsafe fn from_sdl2_window(window: &sdl2::video::Window) -> crate::Result<Self> {
+        let display = window
+            .display_handle()
+            .map_err(|error| crate::InstanceError::WindowHandleUnavailable(error.to_string()))?
+            .as_raw();
+        let handle = window
+            .window_handle()
+            .map_err(|error| crate::InstanceError::WindowHandleUnavailable(error.to_string()))?
+            .as_raw();
+        let extensions = window
+            .vulkan_instance_extensions()
+            .map_err(crate::InstanceError::WindowExtensionsUnavailable)?
+            .iter()
+            .map(|name| vk::ExtensionName::from_bytes(name.as_bytes()))
+            .collect();
+
+        let window = unsafe { RawWindow::new(display, handle) };
+        // SDL windows are not thread-safe either, the handles are only used to create the
+        // surface.
+        #[allow(clippy::arc_with_non_send_sync)]
+        let window = Arc::new(window);
+        Ok(Self::new(Some(window)).window_extensions(extensions))
+    }
+}
@@ -0,0 +1,206 @@
+use crate::Device;
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, HasBuilder};
+
+/// Default size of the buffers a [`StagingBelt`] suballocates from.
+const DEFAULT_CHUNK_SIZE: vk::DeviceSize = 4 * 1024 * 1024;
+
+/// A persistently mapped host-visible buffer the belt writes into.
+#[derive(Debug)]
+struct Chunk {
+    buffer: vk::Buffer,
+    memory: vk::DeviceMemory,
+    mapped: *mut u8,
+    size: vk::DeviceSize,
+    offset: vk::DeviceSize,
+}
+
+// The mapping is only written through `&mut StagingBelt`.
+unsafe impl Send for Chunk {}
+
+/// Suballocates staging memory for uploads from large, persistently mapped host-visible
+/// buffers.
+///
+/// [`StagingBelt::write`] copies data into the current chunk and returns where to copy it from.
+/// [`StagingBelt::finish`] closes the chunks written so far under a value, typically the
+/// timeline semaphore value of the submission reading them, and [`StagingBelt::recall`] reuses
+/// the chunks of every value that completed. Chunks are never freed before
+/// [`StagingBelt::destroy`].
+#[derive(Debug)]
+pub struct StagingBelt {
+    device: Arc<Device>,
+    chunk_size: vk::DeviceSize,
+    alignment: vk::DeviceSize,
+    memory_priority: f32,
+    active: Vec<Chunk>,
+    closed: Vec<(u64, Vec<Chunk>)>,
+    free: Vec<Chunk>,
+}
+
+impl StagingBelt {
+    pub fn new(device: Arc<Device>) -> Self {
+        let alignment = device
+            .physical_device()
+            .properties
+            .limits
+            .optimal_buffer_copy_offset_alignment
+            .max(16);
+
+        Self {
+            device,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            alignment,
+            memory_priority: 0.5,
+            active: vec![],
+            closed: vec![],
+            free: vec![],
+        }
+    }
+
+    /// Size of the chunks, writes larger than this get a chunk of their own. Defaults to 4 MiB.
+    pub fn chunk_size(mut self, size: vk::DeviceSize) -> Self {
+        self.chunk_size = size;
+        self
+    }
+
+    /// Priority of the chunk allocations, see [`Device::allocate_memory_with_priority`].
+    /// Defaults to 0.5, the priority Vulkan gives allocations without one.
+    pub fn memory_priority(mut self, priority: f32) -> Self {
+        self.memory_priority = priority;
+        self
+    }
+
+    /// Copy `data` into the belt and return the buffer and offset it was written at.
+    pub fn write(&mut self, data: &[u8]) -> crate::Result<(vk::Buffer, vk::DeviceSize)> {
+        let size = data.len() as vk::DeviceSize;
+        let alignment = self.alignment;
+        let fits = |chunk: &Chunk| chunk.offset.next_multiple_of(alignment) + size <= chunk.size;
+
+        if !self.active.last().is_some_and(fits) {
+            let chunk = match self.free.iter().position(fits) {
+                Some(index) => self.free.swap_remove(index),
+                None => self.create_chunk(size.max(self.chunk_size))?,
+            };
+            self.active.push(chunk);
+        }
+
+        let chunk = self.active.last_mut().unwrap();
+        let offset = chunk.offset.next_multiple_of(alignment);
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                chunk.mapped.add(offset as usize),
+                data.len(),
+            )
+        };
+        chunk.offset = offset + size;
+
+        Ok((chunk.buffer, offset))
+    }
+
+    fn create_chunk(&self, size: vk::DeviceSize) -> crate::Result<Chunk> {
+        let device = &self.device;
+        let allocation_callbacks = device.allocation_callbacks();
+
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(size)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        let buffer = unsafe { device.create_buffer(&buffer_info, allocation_callbacks) }?;
+
+        let requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
+        let flags = vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT;
+        let Some(memory_type_index) = device
+            .physical_device()
+            .memory_type_index(requirements.memory_type_bits, flags)
+        else {
+            unsafe { device.destroy_buffer(buffer, allocation_callbacks) };
+            return Err(crate::Error::NoSuitableMemoryType {
+                type_bits: requirements.memory_type_bits,
+                flags,
+            });
+        };
+        let memory = match device.allocate_memory_with_priority(
+            requirements.size,
+            memory_type_index,
+            self.memory_priority,
+        ) {
+            Ok(memory) => memory,
+            Err(e) => {
+                unsafe { device.destroy_buffer(buffer, allocation_callbacks) };
+                return Err(e);
+            }
+        };
+
+        let mapped = unsafe {
+            device
+                .bind_buffer_memory(buffer, memory, 0)
+                .and_then(|_| device.map_memory(memory, 0, size, vk::MemoryMapFlags::empty()))
+        };
+        match mapped {
+            Ok(mapped) => Ok(Chunk {
+                buffer,
+                memory,
+                mapped: mapped as *mut u8,
+                size,
+                offset: 0,
+            }),
+            Err(e) => {
+                unsafe {
+                    device.destroy_buffer(buffer, allocation_callbacks);
+                    device.free_memory(memory, allocation_callbacks);
+                }
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Close the chunks written since the last call. They are reused once `value` is passed to
+    /// [`StagingBelt::recall`].
+    pub fn finish(&mut self, value: u64) {
+        if !self.active.is_empty() {
+            self.closed.push((value, std::mem::take(&mut self.active)));
+        }
+    }
+
+    /// Reuse the chunks of every value up to and including `completed`.
+    pub fn recall(&mut self, completed: u64) {
+        let (recalled, closed) = std::mem::take(&mut self.closed)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(value, _)| *value <= completed);
+        self.closed = closed;
+
+        for mut chunk in recalled.into_iter().flat_map(|(_, chunks)| chunks) {
+            chunk.offset = 0;
+            self.free.push(chunk);
+        }
+    }
+
+    /// Reuse the chunks written since the last [`StagingBelt::finish`], when the work that would
+    /// have read them was never submitted.
+    pub fn discard(&mut self) {
+        for mut chunk in self.active.drain(..) {
+            chunk.offset = 0;
+            self.free.push(chunk);
+        }
+    }
+
+    /// Free every chunk. Work reading from the belt must have finished.
+    pub fn destroy(&mut self) {
+        let allocation_callbacks = self.device.allocation_callbacks();
+        let closed = self.closed.drain(..).flat_map(|(_, chunks)| chunks);
+        for chunk in self
+            .active
+            .drain(..)
+            .chain(closed)
+            .chain(self.free.drain(..))
+        {
+            unsafe {
+                self.device
+                    .destroy_buffer(chunk.buffer, allocation_callbacks);
+                self.device.free_memory(chunk.memory, allocation_callbacks);
+            }
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use crate::thread_audit::ThreadOwner;
+use crate::{Device, QueueType, Swapchain};
+use std::sync::Arc;
+use vulkanalia::bytecode::Bytecode;
+use vulkanalia::vk::{self, DeviceV1_0, Handle, HasBuilder, KhrSwapchainExtensionDeviceCommands};
+
+const VERTEX_SHADER: &[u8] = include_bytes!("../shaders/triangle.vert.spv");
+const FRAGMENT_SHADER: &[u8] = include_bytes!("../shaders/triangle.frag.spv");
+const FRAMES_IN_FLIGHT: usize = 2;
+
+/// Draws a single colored triangle into a swapchain, to check the whole bootstrap end-to-end.
+///
+/// Not meant for production: it uses a render pass with one color attachment, embedded shaders
+/// and waits for the previous use of a frame before recording it again. Create it after the
+/// swapchain and create a new one whenever the swapchain is recreated.
+///
+/// ``` no_run
+/// # fn run(device: std::sync::Arc<vulkanalia_bootstrap::Device>, swapchain: vulkanalia_bootstrap::Swapchain) -> vulkanalia_bootstrap::Result<()> {
+/// use vulkanalia_bootstrap::TriangleStarter;
+///
+/// let mut starter = TriangleStarter::new(device.clone(), &swapchain)?;
+/// let mut draw = starter.render_loop(&swapchain);
+/// for _ in 0..100 {
+///     draw()?;
+/// }
+/// drop(draw);
+///
+/// starter.destroy();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TriangleStarter {
+    device: Arc<Device>,
+    graphics_queue: vk::Queue,
+    render_pass: vk::RenderPass,
+    pipeline_layout: vk::PipelineLayout,
+    pipeline: vk::Pipeline,
+    framebuffers: Vec<vk::Framebuffer>,
+    extent: vk::Extent2D,
+    command_pool: vk::CommandPool,
+    pool_owner: ThreadOwner,
+    /// Per frame in flight.
+    command_buffers: Vec<vk::CommandBuffer>,
+    image_available: Vec<vk::Semaphore>,
+    in_flight: Vec<vk::Fence>,
+    /// One per swapchain image, a semaphore can only be reused once its present finished.
+    render_finished: Vec<vk::Semaphore>,
+    frame_index: usize,
+}
+
+impl TriangleStarter {
+    /// Create the pipeline, framebuffers and per-frame resources for `swapchain`.
+    pub fn new(device: Arc<Device>, swapchain: &Swapchain) -> crate::Result<Self> {
+        let (graphics_queue_index, graphics_queue) = device.get_queue(QueueType::Graphics)?;
+
+        let mut starter = Self {
+            device,
+            graphics_queue,
+            render_pass: vk::RenderPass::null(),
+            pipeline_layout: vk::PipelineLayout::null(),
+            pipeline: vk::Pipeline::null(),
+            framebuffers: vec![],
+            extent: swapchain.extent,
+            command_pool: vk::CommandPool::null(),
+            pool_owner: ThreadOwner::default(),
+            command_buffers: vec![],
+            image_available: vec![],
+            in_flight: vec![],
+            render_finished: vec![],
+            frame_index: 0,
+        };
+
+        // Everything created so far is released again when a later step fails.
+        if let Err(err) = starter.create_resources(swapchain, graphics_queue_index as u32) {
+            starter.destroy();
+            return Err(err);
+        }
+
+        Ok(starter)
+    }
+
+    fn create_resources(
+        &mut self,
+        swapchain: &Swapchain,
+        graphics_queue_index: u32,
+    ) -> crate::Result<()> {
+        let device = self.device.clone();
+
+        let attachments = [vk::AttachmentDescription::builder()
+            .format(swapchain.image_format)
+            .samples(vk::SampleCountFlags::_1)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+            .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .final_layout(vk::ImageLayout::PRESENT_SRC_KHR)];
+        let color_attachments = [vk::AttachmentReference::builder()
+            .attachment(0)
+            .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)];
+        let subpasses = [vk::SubpassDescription::builder()
+            .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+            .color_attachments(&color_attachments)];
+        let dependencies = [vk::SubpassDependency::builder()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags::empty())
+            .dst_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .dst_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)];
+        let render_pass_info = vk::RenderPassCreateInfo::builder()
+            .attachments(&attachments)
+            .subpasses(&subpasses)
+            .dependencies(&dependencies);
+        self.render_pass = unsafe { device.create_render_pass(&render_pass_info, None) }?;
+
+        self.pipeline_layout = unsafe {
+            device.create_pipeline_layout(&vk::PipelineLayoutCreateInfo::default(), None)
+        }?;
+        self.pipeline = self.create_pipeline()?;
+
+        for image in swapchain.images()? {
+            let views = [image.view];
+            let framebuffer_info = vk::FramebufferCreateInfo::builder()
+                .render_pass(self.render_pass)
+                .attachments(&views)
+                .width(self.extent.width)
+                .height(self.extent.height)
+                .layers(1);
+            self.framebuffers
+                .push(unsafe { device.create_framebuffer(&framebuffer_info, None) }?);
+        }
+        self.render_finished =
+            device.create_semaphores("starter render finished", self.framebuffers.len())?;
+
+        let command_pool_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+            .queue_family_index(graphics_queue_index);
+        self.command_pool = unsafe { device.create_command_pool(&command_pool_info, None) }?;
+
+        let allocate_info = vk::CommandBufferAllocateInfo::builder()
+            .command_pool(self.command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(FRAMES_IN_FLIGHT as u32);
+        self.command_buffers = unsafe { device.allocate_command_buffers(&allocate_info) }?;
+        self.image_available =
+            device.create_semaphores("starter image available", FRAMES_IN_FLIGHT)?;
+        self.in_flight = device.create_fences("starter in flight", FRAMES_IN_FLIGHT, true)?;
+
+        Ok(())
+    }
+
+    fn create_pipeline(&self) -> crate::Result<vk::Pipeline> {
+        let vertex = Bytecode::new(VERTEX_SHADER).expect("embedded SPIR-V is word aligned");
+        let fragment = Bytecode::new(FRAGMENT_SHADER).expect("embedded SPIR-V is word aligned");
+
+        let vertex_module = self
+            .device
+            .create_shader_module("starter triangle vertex", vertex.code())?;
+        let fragment_module = self
+            .device
+            .create_shader_module("starter triangle fragment", fragment.code())
+            .inspect_err(|_| unsafe { self.device.destroy_shader_module(vertex_module, None) })?;
+
+        let stages = [
+            vk::PipelineShaderStageCreateInfo::builder()
+                .stage(vk::ShaderStageFlags::VERTEX)
+                .module(vertex_module)
+                .name(b"main\0"),
+            vk::PipelineShaderStageCreateInfo::builder()
+                .stage(vk::ShaderStageFlags::FRAGMENT)
+                .module(fragment_module)
+                .name(b"main\0"),
+        ];
+        let vertex_input = vk::PipelineVertexInputStateCreateInfo::default();
+        let input_assembly = vk::PipelineInputAssemblyStateCreateInfo::builder()
+            .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
+        let viewport_state = vk::PipelineViewportStateCreateInfo::builder()
+            .viewport_count(1)
+            .scissor_count(1);
+        let rasterization = vk::PipelineRasterizationStateCreateInfo::builder()
+            .polygon_mode(vk::PolygonMode::FILL)
+            .cull_mode(vk::CullModeFlags::NONE)
+            .front_face(vk::FrontFace::CLOCKWISE)
+            .line_width(1.0);
+        let multisample = vk::PipelineMultisampleStateCreateInfo::builder()
+            .rasterization_samples(vk::SampleCountFlags::_1);
+        let blend_attachments = [vk::PipelineColorBlendAttachmentState::builder()
+            .color_write_mask(vk::ColorComponentFlags::all())];
+        let color_blend =
+            vk::PipelineColorBlendStateCreateInfo::builder().attachments(&blend_attachments);
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state =
+            vk::PipelineDynamicStateCreateInfo::builder().dynamic_states(&dynamic_states);
+
+        let pipeline_info = vk::GraphicsPipelineCreateInfo::builder()
+            .stages(&stages)
+            .vertex_input_state(&vertex_input)
+            .input_assembly_state(&input_assembly)
+            .viewport_state(&viewport_state)
+            .rasterization_state(&rasterization)
+            .multisample_state(&multisample)
+            .color_blend_state(&color_blend)
+            .dynamic_state(&dynamic_state)
+            .layout(self.pipeline_layout)
+            .render_pass(self.render_pass)
+            .subpass(0);
+
+        let pipeline = unsafe {
+            self.device
+                .create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_info], None)
+        };
+
+        unsafe {
+            self.device.destroy_shader_module(vertex_module, None);
+            self.device.destroy_shader_module(fragment_module, None);
+        }
+
+        Ok(pipeline?.0[0])
+    }
+
+    /// Acquire an image, draw the triangle into it and present it.
+    ///
+    /// Returns `true` when the swapchain is suboptimal. An out of date swapchain is reported as
+    /// `Error::VulkanErr(vk::ErrorCode::OUT_OF_DATE_KHR)`.
+    pub fn draw(&mut self, swapchain: &Swapchain) -> crate::Result<bool> {
+        self.pool_owner
+            .check(vk::ObjectType::COMMAND_POOL, self.command_pool.as_raw());
+        let command_buffer = self.command_buffers[self.frame_index];
+        let image_available = self.image_available[self.frame_index];
+        let in_flight = self.in_flight[self.frame_index];
+
+        unsafe { self.device.wait_for_fences(&[in_flight], true, u64::MAX) }?;
+
+        let (image_index, acquired) = unsafe {
+            self.device.acquire_next_image_khr(
+                *swapchain.as_ref(),
+                u64::MAX,
+                image_available,
+                vk::Fence::null(),
+            )
+        }?;
+
+        unsafe { self.device.reset_fences(&[in_flight]) }?;
+        self.record(command_buffer, image_index as usize)?;
+
+        let wait_semaphores = [image_available];
+        let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
+        let command_buffers = [command_buffer];
+        let signal_semaphores = [self.render_finished[image_index as usize]];
+        let submit_info = vk::SubmitInfo::builder()
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_stages)
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores);
+
+        {
+            let _access = self.device.audit_queue(self.graphics_queue);
+            unsafe {
+                self.device
+                    .queue_submit(self.graphics_queue, &[submit_info], in_flight)
+            }?;
+        }
+
+        self.frame_index = (self.frame_index + 1) % FRAMES_IN_FLIGHT;
+
+        let suboptimal = swapchain.present(image_index, &signal_semaphores)?;
+
+        Ok(suboptimal || acquired == vk::SuccessCode::SUBOPTIMAL_KHR)
+    }
+
+    fn record(&self, command_buffer: vk::CommandBuffer, image_index: usize) -> crate::Result<()> {
+        let device = &self.device;
+        let render_area = vk::Rect2D::builder().extent(self.extent).build();
+        let clear_values = [vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            },
+        }];
+        let render_pass_begin = vk::RenderPassBeginInfo::builder()
+            .render_pass(self.render_pass)
+            .framebuffer(self.framebuffers[image_index])
+            .render_area(render_area)
+            .clear_values(&clear_values);
+        let viewport = vk::Viewport::builder()
+            .width(self.extent.width as f32)
+            .height(self.extent.height as f32)
+            .max_depth(1.0);
+
+        unsafe {
+            device.reset_command_buffer(command_buffer, vk::CommandBufferResetFlags::empty())?;
+            device.begin_command_buffer(
+                command_buffer,
+                &vk::CommandBufferBeginInfo::builder()
+                    .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+            )?;
+            device.cmd_begin_render_pass(
+                command_buffer,
+                &render_pass_begin,
+                vk::SubpassContents::INLINE,
+            );
+            device.cmd_bind_pipeline(
+                command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.pipeline,
+            );
+            device.cmd_set_viewport(command_buffer, 0, &[viewport]);
+            device.cmd_set_scissor(command_buffer, 0, &[render_area]);
+            device.cmd_draw(command_buffer, 3, 1, 0, 0);
+            device.cmd_end_render_pass(command_buffer);
+            device.end_command_buffer(command_buffer)?;
+        }
+
+        Ok(())
+    }
+
+    /// A closure drawing one frame per call, for plugging into an event loop.
+    pub fn render_loop<'a>(
+        &'a mut self,
+        swapchain: &'a Swapchain,
+    ) -> impl FnMut() -> crate::Result<bool> + 'a {
+        move || self.draw(swapchain)
+    }
+
+    /// Wait for the device to go idle and destroy everything the starter created. The
+    /// swapchain image views stay cached on the swapchain.
+    pub fn destroy(&self) {
+        let _ = unsafe { self.device.device_wait_idle() };
+
+        self.device.destroy_fences(&self.in_flight);
+        self.device.destroy_semaphores(&self.image_available);
+        self.device.destroy_semaphores(&self.render_finished);
+        unsafe {
+            self.device.destroy_command_pool(self.command_pool, None);
+            for framebuffer in &self.framebuffers {
+                self.device.destroy_framebuffer(*framebuffer, None);
+            }
+            self.device.destroy_pipeline(self.pipeline, None);
+            self.device
+                .destroy_pipeline_layout(self.pipeline_layout, None);
+            self.device.destroy_render_pass(self.render_pass, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_shaders_are_spirv() {
+        for code in [VERTEX_SHADER, FRAGMENT_SHADER] {
+            let bytecode = Bytecode::new(code).unwrap();
+            assert_eq!(bytecode.code()[0], 0x0723_0203);
+        }
+    }
+}
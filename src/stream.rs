@@ -0,0 +1,461 @@
+use crate::Device;
+use crate::device::QueueType;
+use crate::readback::{self, format_texel_size};
+use crate::thread_audit::ThreadOwner;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, Handle, HasBuilder};
+
+/// A frame handed to the [`StreamTarget`] callback.
+///
+/// `data` is tightly packed, row by row, in `format`.
+#[derive(Debug)]
+pub struct StreamFrame<'a> {
+    pub image_index: u32,
+    pub frame_number: u64,
+    pub format: vk::Format,
+    pub extent: vk::Extent2D,
+    pub data: &'a [u8],
+}
+
+impl StreamFrame<'_> {
+    /// Convert the frame to tightly packed RGBA8, see [`to_rgba8`](crate::to_rgba8).
+    pub fn to_rgba8(&self) -> Option<Vec<u8>> {
+        readback::to_rgba8(
+            self.data,
+            self.format,
+            self.extent,
+            self.extent.width as usize * 4,
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct StreamSlot {
+    image: vk::Image,
+    image_memory: vk::DeviceMemory,
+    image_view: vk::ImageView,
+    buffer: vk::Buffer,
+    buffer_memory: vk::DeviceMemory,
+    command_buffer: vk::CommandBuffer,
+    fence: vk::Fence,
+    acquired: bool,
+    pending_frame: Option<u64>,
+}
+
+type FrameCallback = Box<dyn FnMut(StreamFrame<'_>) + Send>;
+
+/// Swapchain-less presentation target for remote streaming.
+///
+/// Mirrors the acquire/present shape of a [`crate::Swapchain`], but "presenting" copies the
+/// image into a host-visible buffer which is handed to a user callback (e.g. to feed a video
+/// encoder) once the copy has finished on the GPU.
+///
+/// Images must be in `vk::ImageLayout::TRANSFER_SRC_OPTIMAL` when presented.
+pub struct StreamTarget {
+    device: Arc<Device>,
+    queue: vk::Queue,
+    command_pool: vk::CommandPool,
+    pool_owner: ThreadOwner,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    frame_size: u64,
+    slots: Vec<StreamSlot>,
+    next_slot: usize,
+    frame_number: u64,
+    callback: FrameCallback,
+}
+
+impl Debug for StreamTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamTarget")
+            .field("format", &self.format)
+            .field("extent", &self.extent)
+            .field("slots", &self.slots)
+            .field("next_slot", &self.next_slot)
+            .field("frame_number", &self.frame_number)
+            .finish()
+    }
+}
+
+impl StreamTarget {
+    /// Create a stream target with `image_count` color images of the given format and extent.
+    ///
+    /// Copies are submitted on the graphics queue. `callback` is invoked with each finished
+    /// frame, in presentation order.
+    pub fn new(
+        device: Arc<Device>,
+        format: vk::Format,
+        extent: vk::Extent2D,
+        image_count: u32,
+        callback: impl FnMut(StreamFrame<'_>) + Send + 'static,
+    ) -> crate::Result<Self> {
+        let Some(texel_size) = format_texel_size(format) else {
+            return Err(crate::CreateError {
+                kind: vk::ObjectType::IMAGE,
+                name: "stream target".to_string(),
+                details: format!("{format:?} cannot be read back"),
+                code: vk::ErrorCode::FORMAT_NOT_SUPPORTED,
+            }
+            .into());
+        };
+        let frame_size = texel_size * extent.width as u64 * extent.height as u64;
+
+        let (queue_family_index, queue) = device.get_queue(QueueType::Graphics)?;
+
+        let command_pool_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+            .queue_family_index(queue_family_index as _);
+        let command_pool = unsafe {
+            device.create_command_pool(&command_pool_info, device.allocation_callbacks())
+        }?;
+
+        let mut target = Self {
+            device,
+            queue,
+            command_pool,
+            pool_owner: ThreadOwner::default(),
+            format,
+            extent,
+            frame_size,
+            slots: Vec::with_capacity(image_count as _),
+            next_slot: 0,
+            frame_number: 0,
+            callback: Box::new(callback),
+        };
+
+        for _ in 0..image_count.max(1) {
+            match target.create_slot() {
+                Ok(slot) => target.slots.push(slot),
+                Err(e) => {
+                    target.destroy();
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(target)
+    }
+
+    fn create_slot(&self) -> crate::Result<StreamSlot> {
+        let mut slot = StreamSlot::default();
+        if let Err(e) = self.init_slot(&mut slot) {
+            self.destroy_slot(&slot);
+            return Err(e);
+        }
+
+        Ok(slot)
+    }
+
+    /// Create the objects of `slot` one by one, so [`StreamTarget::destroy_slot`] can clean up
+    /// whatever was created when a step fails.
+    fn init_slot(&self, slot: &mut StreamSlot) -> crate::Result<()> {
+        let device = &self.device;
+        let physical_device = device.physical_device();
+        let allocation_callbacks = device.allocation_callbacks();
+
+        let image_info = vk::ImageCreateInfo::builder()
+            .image_type(vk::ImageType::_2D)
+            .format(self.format)
+            .extent(vk::Extent3D {
+                width: self.extent.width,
+                height: self.extent.height,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(
+                vk::ImageUsageFlags::COLOR_ATTACHMENT
+                    | vk::ImageUsageFlags::TRANSFER_SRC
+                    | vk::ImageUsageFlags::TRANSFER_DST,
+            )
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+        slot.image = unsafe {
+            device
+                .device()
+                .create_image(&image_info, allocation_callbacks)
+        }?;
+
+        let requirements = unsafe { device.get_image_memory_requirements(slot.image) };
+        let flags = vk::MemoryPropertyFlags::DEVICE_LOCAL;
+        let memory_type_index = physical_device
+            .memory_type_index(requirements.memory_type_bits, flags)
+            .ok_or(crate::Error::NoSuitableMemoryType {
+                type_bits: requirements.memory_type_bits,
+                flags,
+            })?;
+        // The images are rendered to every frame, keep them resident before other allocations.
+        slot.image_memory =
+            device.allocate_memory_with_priority(requirements.size, memory_type_index, 1.0)?;
+        unsafe { device.bind_image_memory(slot.image, slot.image_memory, 0) }?;
+
+        let view_info = vk::ImageViewCreateInfo::builder()
+            .image(slot.image)
+            .view_type(vk::ImageViewType::_2D)
+            .format(self.format)
+            .subresource_range(
+                vk::ImageSubresourceRange::builder()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .level_count(1)
+                    .layer_count(1),
+            );
+        slot.image_view = unsafe { device.create_image_view(&view_info, allocation_callbacks) }?;
+
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(self.frame_size)
+            .usage(vk::BufferUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        slot.buffer = unsafe { device.create_buffer(&buffer_info, allocation_callbacks) }?;
+
+        let requirements = unsafe { device.get_buffer_memory_requirements(slot.buffer) };
+        let flags = vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT;
+        let memory_type_index = physical_device
+            .memory_type_index(requirements.memory_type_bits, flags)
+            .ok_or(crate::Error::NoSuitableMemoryType {
+                type_bits: requirements.memory_type_bits,
+                flags,
+            })?;
+        let allocate_info = vk::MemoryAllocateInfo::builder()
+            .allocation_size(requirements.size)
+            .memory_type_index(memory_type_index);
+        slot.buffer_memory =
+            unsafe { device.allocate_memory(&allocate_info, allocation_callbacks) }?;
+        unsafe { device.bind_buffer_memory(slot.buffer, slot.buffer_memory, 0) }?;
+
+        let command_buffer_info = vk::CommandBufferAllocateInfo::builder()
+            .command_pool(self.command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(1);
+        slot.command_buffer = unsafe { device.allocate_command_buffers(&command_buffer_info) }?[0];
+
+        let fence_info = vk::FenceCreateInfo::builder().flags(vk::FenceCreateFlags::SIGNALED);
+        slot.fence = unsafe { device.create_fence(&fence_info, allocation_callbacks) }?;
+
+        Ok(())
+    }
+
+    /// Destroy the objects of `slot`. Null handles of a partially created slot are skipped.
+    fn destroy_slot(&self, slot: &StreamSlot) {
+        let allocation_callbacks = self.device.allocation_callbacks();
+        unsafe {
+            self.device.destroy_fence(slot.fence, allocation_callbacks);
+            if !slot.command_buffer.is_null() {
+                self.device
+                    .free_command_buffers(self.command_pool, &[slot.command_buffer]);
+            }
+            self.device
+                .destroy_buffer(slot.buffer, allocation_callbacks);
+            self.device
+                .free_memory(slot.buffer_memory, allocation_callbacks);
+            self.device
+                .destroy_image_view(slot.image_view, allocation_callbacks);
+            self.device.destroy_image(slot.image, allocation_callbacks);
+            self.device
+                .free_memory(slot.image_memory, allocation_callbacks);
+        }
+    }
+
+    pub fn format(&self) -> vk::Format {
+        self.format
+    }
+
+    pub fn extent(&self) -> vk::Extent2D {
+        self.extent
+    }
+
+    pub fn image_count(&self) -> u32 {
+        self.slots.len() as u32
+    }
+
+    /// Images owned by the stream target, indexed like swapchain images.
+    pub fn images(&self) -> Vec<vk::Image> {
+        self.slots.iter().map(|slot| slot.image).collect()
+    }
+
+    /// Views for [`StreamTarget::images`].
+    pub fn image_views(&self) -> Vec<vk::ImageView> {
+        self.slots.iter().map(|slot| slot.image_view).collect()
+    }
+
+    fn deliver(&mut self, index: usize) -> crate::Result<()> {
+        let Some(frame_number) = self.slots[index].pending_frame.take() else {
+            return Ok(());
+        };
+
+        let slot = &self.slots[index];
+        let data = unsafe {
+            self.device.map_memory(
+                slot.buffer_memory,
+                0,
+                self.frame_size,
+                vk::MemoryMapFlags::empty(),
+            )
+        }?;
+
+        let bytes =
+            unsafe { std::slice::from_raw_parts(data as *const u8, self.frame_size as usize) };
+        (self.callback)(StreamFrame {
+            image_index: index as u32,
+            frame_number,
+            format: self.format,
+            extent: self.extent,
+            data: bytes,
+        });
+
+        unsafe { self.device.unmap_memory(slot.buffer_memory) };
+
+        Ok(())
+    }
+
+    /// Wait until the next image is free and return its index.
+    ///
+    /// If the image still held a presented frame, it is delivered to the callback first.
+    pub fn acquire_next_image(&mut self) -> crate::Result<u32> {
+        let index = self.next_slot;
+        unsafe {
+            self.device
+                .wait_for_fences(&[self.slots[index].fence], true, u64::MAX)
+        }?;
+        self.deliver(index)?;
+
+        self.slots[index].acquired = true;
+        self.next_slot = (index + 1) % self.slots.len();
+
+        Ok(index as u32)
+    }
+
+    /// Queue a copy of `image_index` into its readback buffer after `wait_semaphores` signal.
+    ///
+    /// The image must be in `vk::ImageLayout::TRANSFER_SRC_OPTIMAL` and acquired with
+    /// [`StreamTarget::acquire_next_image`], otherwise `ConfigError::ImageNotAcquired` is
+    /// returned.
+    pub fn present(
+        &mut self,
+        image_index: u32,
+        wait_semaphores: &[vk::Semaphore],
+    ) -> crate::Result<()> {
+        let slot = self.slots.get(image_index as usize).ok_or(
+            crate::ConfigError::ImageIndexOutOfBounds {
+                index: image_index,
+                count: self.slots.len() as u32,
+            },
+        )?;
+        if !slot.acquired {
+            return Err(crate::ConfigError::ImageNotAcquired(image_index).into());
+        }
+        self.pool_owner
+            .check(vk::ObjectType::COMMAND_POOL, self.command_pool.as_raw());
+
+        unsafe {
+            self.device.wait_for_fences(&[slot.fence], true, u64::MAX)?;
+            self.device
+                .reset_command_buffer(slot.command_buffer, vk::CommandBufferResetFlags::empty())?;
+            self.device.begin_command_buffer(
+                slot.command_buffer,
+                &vk::CommandBufferBeginInfo::builder()
+                    .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+            )?;
+
+            let region = vk::BufferImageCopy::builder()
+                .image_subresource(
+                    vk::ImageSubresourceLayers::builder()
+                        .aspect_mask(vk::ImageAspectFlags::COLOR)
+                        .layer_count(1),
+                )
+                .image_extent(vk::Extent3D {
+                    width: self.extent.width,
+                    height: self.extent.height,
+                    depth: 1,
+                });
+            self.device.cmd_copy_image_to_buffer(
+                slot.command_buffer,
+                slot.image,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                slot.buffer,
+                &[region],
+            );
+
+            let barrier = vk::BufferMemoryBarrier::builder()
+                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .dst_access_mask(vk::AccessFlags::HOST_READ)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .buffer(slot.buffer)
+                .size(vk::WHOLE_SIZE);
+            self.device.cmd_pipeline_barrier(
+                slot.command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::HOST,
+                vk::DependencyFlags::empty(),
+                &[] as &[vk::MemoryBarrier],
+                &[barrier],
+                &[] as &[vk::ImageMemoryBarrier],
+            );
+
+            self.device.end_command_buffer(slot.command_buffer)?;
+
+            let wait_stages = vec![vk::PipelineStageFlags::TRANSFER; wait_semaphores.len()];
+            let command_buffers = [slot.command_buffer];
+            let submit_info = vk::SubmitInfo::builder()
+                .wait_semaphores(wait_semaphores)
+                .wait_dst_stage_mask(&wait_stages)
+                .command_buffers(&command_buffers);
+            // Reset right before submitting so a failed recording leaves the fence signaled.
+            self.device.reset_fences(&[slot.fence])?;
+            let _access = self.device.audit_queue(self.queue);
+            self.device
+                .queue_submit(self.queue, &[submit_info], slot.fence)?;
+        }
+
+        let slot = &mut self.slots[image_index as usize];
+        slot.acquired = false;
+        slot.pending_frame = Some(self.frame_number);
+        self.frame_number += 1;
+
+        Ok(())
+    }
+
+    /// Wait for every in-flight copy and deliver the remaining frames in presentation order.
+    pub fn flush(&mut self) -> crate::Result<()> {
+        let fences = self.slots.iter().map(|slot| slot.fence).collect::<Vec<_>>();
+        unsafe { self.device.wait_for_fences(&fences, true, u64::MAX) }?;
+
+        let mut pending = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.pending_frame.map(|frame| (frame, index)))
+            .collect::<Vec<_>>();
+        pending.sort_unstable();
+
+        for (_, index) in pending {
+            self.deliver(index)?;
+        }
+
+        Ok(())
+    }
+
+    /// Destroy every image, buffer and sync object owned by the stream target.
+    /// Pending frames are dropped without being delivered; call [`StreamTarget::flush`] first
+    /// to receive them.
+    pub fn destroy(&self) {
+        let allocation_callbacks = self.device.allocation_callbacks();
+        unsafe {
+            let fences = self.slots.iter().map(|slot| slot.fence).collect::<Vec<_>>();
+            if !fences.is_empty() {
+                let _ = self.device.wait_for_fences(&fences, true, u64::MAX);
+            }
+
+            for slot in &self.slots {
+                self.destroy_slot(slot);
+            }
+
+            self.device
+                .destroy_command_pool(self.command_pool, allocation_callbacks);
+        }
+    }
+}
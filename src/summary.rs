@@ -0,0 +1,113 @@
+use crate::QueueType;
+use std::fmt::{Display, Formatter};
+use vulkanalia::Version;
+use vulkanalia::vk;
+
+pub(crate) fn extension_names<'a>(
+    names: impl IntoIterator<Item = &'a vk::ExtensionName>,
+) -> Vec<String> {
+    names
+        .into_iter()
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Swapchain part of a [`CreationSummary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapchainSummary {
+    pub format: vk::Format,
+    pub color_space: vk::ColorSpaceKHR,
+    pub present_mode: vk::PresentModeKHR,
+    pub image_count: u32,
+    pub extent: vk::Extent2D,
+}
+
+/// What the bootstrap ended up creating, for bug reports and telemetry.
+///
+/// Returned by [`crate::Device::creation_summary`] and, with the swapchain filled in, by
+/// [`crate::Swapchain::creation_summary`]. Displays as a multi-line report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreationSummary {
+    pub device_name: String,
+    pub device_type: vk::PhysicalDeviceType,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    /// Raw driver version, vendor specific encoding. See [`CreationSummary::driver_version_string`].
+    pub driver_version: u32,
+    /// Driver name and info, when the device reports them.
+    pub driver: Option<crate::DriverInfo>,
+    pub device_api_version: Version,
+    pub instance_api_version: Version,
+    pub instance_extensions: Vec<String>,
+    pub layers: Vec<String>,
+    pub device_extensions: Vec<String>,
+    /// Queue family used for each queue type the device resolved.
+    pub queue_families: Vec<(QueueType, u32)>,
+    pub swapchain: Option<SwapchainSummary>,
+}
+
+impl CreationSummary {
+    /// Driver version decoded with the vendor's encoding (NVIDIA and Intel on Windows differ
+    /// from the Vulkan version encoding the other vendors use).
+    pub fn driver_version_string(&self) -> String {
+        let version = self.driver_version;
+        match self.vendor_id {
+            0x10DE => format!(
+                "{}.{}.{}.{}",
+                version >> 22,
+                (version >> 14) & 0xff,
+                (version >> 6) & 0xff,
+                version & 0x3f
+            ),
+            0x8086 if cfg!(windows) => format!("{}.{}", version >> 14, version & 0x3fff),
+            _ => Version::from(version).to_string(),
+        }
+    }
+}
+
+impl Display for CreationSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Device: {} ({:?}, vendor {:#06x}, device {:#06x})",
+            self.device_name, self.device_type, self.vendor_id, self.device_id
+        )?;
+        match &self.driver {
+            Some(driver) => writeln!(f, "Driver: {driver} ({})", self.driver_version_string())?,
+            None => writeln!(f, "Driver: {}", self.driver_version_string())?,
+        }
+        writeln!(
+            f,
+            "API version: instance {}, device {}",
+            self.instance_api_version, self.device_api_version
+        )?;
+        writeln!(f, "Layers: {}", self.layers.join(", "))?;
+        writeln!(
+            f,
+            "Instance extensions: {}",
+            self.instance_extensions.join(", ")
+        )?;
+        writeln!(
+            f,
+            "Device extensions: {}",
+            self.device_extensions.join(", ")
+        )?;
+        write!(f, "Queues:")?;
+        for (queue, family) in &self.queue_families {
+            write!(f, " {queue:?}={family}")?;
+        }
+        if let Some(swapchain) = &self.swapchain {
+            write!(
+                f,
+                "\nSwapchain: {:?} {:?}, {:?}, {} images, {}x{}",
+                swapchain.format,
+                swapchain.color_space,
+                swapchain.present_mode,
+                swapchain.image_count,
+                swapchain.extent.width,
+                swapchain.extent.height
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,21 +1,32 @@
 use crate::Device;
 use crate::Instance;
-use crate::device::QueueType;
+use crate::allocator::TrackedAllocationCallbacks;
+use crate::device::{QueueFamilyOwnershipTransfer, QueueType, ResourceSharing};
 use crate::error::FormatError;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use vulkanalia::Version;
 use vulkanalia::vk;
 use vulkanalia::vk::DeviceV1_0;
+use vulkanalia::vk::GoogleDisplayTimingExtensionDeviceCommands;
 use vulkanalia::vk::HasBuilder;
+use vulkanalia::vk::KhrGetSurfaceCapabilities2ExtensionInstanceCommands;
+use vulkanalia::vk::KhrPresentWaitExtensionDeviceCommands;
 use vulkanalia::vk::KhrSurfaceExtensionInstanceCommands;
 use vulkanalia::vk::KhrSwapchainExtensionDeviceCommands;
+use vulkanalia::vk::NvLowLatency2ExtensionDeviceCommands;
 use vulkanalia::vk::{AllocationCallbacks, Handle, SwapchainKHR};
 
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
-enum Priority {
-    Main,
-    Fallback,
+/// Generation of the next swapchain, see [`Swapchain::generation`].
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Lower values are tried first. Entries with equal priority keep the order they were added in.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
+struct Priority(u32);
+
+impl Priority {
+    const MAIN: Self = Self(0);
+    const FALLBACK: Self = Self(u32::MAX);
 }
 
 #[derive(Debug, Clone)]
@@ -30,14 +41,156 @@ struct PresentMode {
     priority: Priority,
 }
 
+/// Common image counts for [`SwapchainBuilder::desired_min_image_count`] and
+/// [`SwapchainBuilder::required_min_image_count`].
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BufferMode {
+    Single = 1,
+    Double = 2,
+    Triple = 3,
+}
+
+impl From<BufferMode> for u32 {
+    fn from(value: BufferMode) -> Self {
+        value as u32
+    }
+}
+
+/// A single swapchain configuration supported by the surface.
+///
+/// Returned by [`SwapchainBuilder::enumerate_configs`] and accepted by
+/// [`SwapchainBuilder::build_with_config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapchainCandidate {
+    pub format: vk::SurfaceFormatKHR,
+    pub present_mode: vk::PresentModeKHR,
+    pub image_count: u32,
+}
+
+/// What [`SwapchainBuilder::build`] would create, returned by [`SwapchainBuilder::plan`].
+#[derive(Debug, Clone)]
+pub struct SwapchainPlan {
+    pub candidate: SwapchainCandidate,
+    pub extent: vk::Extent2D,
+    pub image_array_layers: u32,
+    pub image_usage: vk::ImageUsageFlags,
+    pub pre_transform: vk::SurfaceTransformFlagsKHR,
+    pub composite_alpha: vk::CompositeAlphaFlagsKHR,
+    pub image_sharing: ResourceSharing,
+}
+
+impl SwapchainPlan {
+    /// The exact settings passed to the driver, in the form
+    /// [`SurfaceSupport::explain_incompatibility`] checks.
+    fn to_request(&self) -> SwapchainConfig {
+        SwapchainConfig {
+            desired_formats: vec![(self.candidate.format, 0)],
+            desired_present_modes: vec![(self.candidate.present_mode, 0)],
+            desired_extent: self.extent,
+            array_layer_count: self.image_array_layers,
+            required_min_image_count: self.candidate.image_count,
+            image_usage_flags: self.image_usage,
+            composite_alpha_flags: self.composite_alpha,
+            pre_transform: self.pre_transform,
+            image_sharing: Some(self.image_sharing.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Plain-data snapshot of the [`SwapchainBuilder`] settings.
+///
+/// Formats and present modes are stored with their numeric priority (lower is tried first).
+/// Apply it with [`SwapchainBuilder::with_config`]; the instance, device, allocation callbacks
+/// and old swapchain are not part of the config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapchainConfig {
+    pub desired_formats: Vec<(vk::SurfaceFormatKHR, u32)>,
+    pub desired_present_modes: Vec<(vk::PresentModeKHR, u32)>,
+    pub create_flags: vk::SwapchainCreateFlagsKHR,
+    pub desired_extent: vk::Extent2D,
+    pub auto_extent: bool,
+    pub array_layer_count: u32,
+    pub min_image_count: u32,
+    pub required_min_image_count: u32,
+    pub image_usage_flags: vk::ImageUsageFlags,
+    pub composite_alpha_flags: vk::CompositeAlphaFlagsKHR,
+    pub pre_transform: vk::SurfaceTransformFlagsKHR,
+    pub clipped: bool,
+    pub image_sharing: Option<ResourceSharing>,
+    pub low_latency: bool,
+}
+
+impl Default for SwapchainConfig {
+    fn default() -> Self {
+        Self {
+            desired_formats: vec![],
+            desired_present_modes: vec![],
+            create_flags: vk::SwapchainCreateFlagsKHR::default(),
+            desired_extent: vk::Extent2D {
+                width: 256,
+                height: 256,
+            },
+            auto_extent: true,
+            array_layer_count: 1,
+            min_image_count: 0,
+            required_min_image_count: 0,
+            image_usage_flags: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            composite_alpha_flags: vk::CompositeAlphaFlagsKHR::default(),
+            pre_transform: vk::SurfaceTransformFlagsKHR::default(),
+            clipped: true,
+            image_sharing: None,
+            low_latency: false,
+        }
+    }
+}
+
+/// Raw handle of the swapchain passed to [`SwapchainBuilder::set_old_swapchain`].
+///
+/// Clones start empty: the old swapchain is destroyed by the next build, which must happen only
+/// once.
+#[derive(Debug, Default)]
+struct OldSwapchain(AtomicU64);
+
+impl OldSwapchain {
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, handle: u64) {
+        self.0.store(handle, Ordering::Relaxed);
+    }
+
+    /// Clear the stored handle if it is still `handle`. Returns true for exactly one caller,
+    /// which then owns the destruction of the old swapchain.
+    fn take(&self, handle: u64) -> bool {
+        handle != 0
+            && self
+                .0
+                .compare_exchange(handle, 0, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+    }
+}
+
+impl Clone for OldSwapchain {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SwapchainBuilder {
     instance: Arc<Instance>,
     device: Arc<Device>,
+    surface: Option<vk::SurfaceKHR>,
     allocation_callbacks: Option<AllocationCallbacks>,
+    allocator: Option<TrackedAllocationCallbacks>,
     desired_formats: Vec<Format>,
     create_flags: vk::SwapchainCreateFlagsKHR,
     desired_width: u32,
     desired_height: u32,
+    auto_extent: bool,
     array_layer_count: u32,
     min_image_count: u32,
     required_min_image_count: u32,
@@ -46,63 +199,269 @@ pub struct SwapchainBuilder {
     desired_present_modes: Vec<PresentMode>,
     pre_transform: vk::SurfaceTransformFlagsKHR,
     clipped: bool,
-    old_swapchain: AtomicU64,
+    image_sharing: Option<ResourceSharing>,
+    low_latency: bool,
+    old_swapchain: OldSwapchain,
     graphics_queue_index: usize,
     present_queue_index: usize,
 }
 
-struct SurfaceFormatDetails {
-    capabilities: vk::SurfaceCapabilitiesKHR,
-    formats: Vec<vk::SurfaceFormatKHR>,
-    present_modes: Vec<vk::PresentModeKHR>,
+/// Image count limits of a surface for one present mode (`VK_EXT_surface_maintenance1`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresentModeImageCount {
+    pub present_mode: vk::PresentModeKHR,
+    pub min_image_count: u32,
+    pub max_image_count: u32,
+}
+
+/// What a surface supports on a physical device, as queried when building a swapchain.
+#[derive(Debug, Clone, Default)]
+pub struct SurfaceSupport {
+    pub capabilities: vk::SurfaceCapabilitiesKHR,
+    pub formats: Vec<vk::SurfaceFormatKHR>,
+    pub present_modes: Vec<vk::PresentModeKHR>,
+    /// Whether protected swapchains can be presented. `None` unless the instance was built with
+    /// [`InstanceBuilder::use_surface_capabilities2`](crate::InstanceBuilder::use_surface_capabilities2)
+    /// and `VK_KHR_surface_protected_capabilities` is available.
+    pub supports_protected: Option<bool>,
+    /// Per-present-mode image counts. Empty unless surface maintenance1 is enabled.
+    pub present_mode_image_counts: Vec<PresentModeImageCount>,
+}
+
+impl SurfaceSupport {
+    /// Explain why `request` can't be satisfied by this surface, or `None` if it can.
+    ///
+    /// Every problem found is listed, separated by `; `. Empty desired format and present mode
+    /// lists mean the builder defaults and are never reported.
+    pub fn explain_incompatibility(&self, request: &SwapchainConfig) -> Option<String> {
+        let capabilities = &self.capabilities;
+        let mut problems = Vec::new();
+
+        if !capabilities
+            .supported_usage_flags
+            .contains(request.image_usage_flags)
+        {
+            problems.push(format!(
+                "image usage {:?} is not supported (supported: {:?})",
+                request.image_usage_flags & !capabilities.supported_usage_flags,
+                capabilities.supported_usage_flags
+            ));
+        }
+
+        if !request.desired_formats.is_empty()
+            && !request.desired_formats.iter().any(|(desired, _)| {
+                self.formats.iter().any(|available| {
+                    available.format == desired.format
+                        && available.color_space == desired.color_space
+                })
+            })
+        {
+            problems.push(format!(
+                "none of the desired formats {:?} are available (available: {:?})",
+                request
+                    .desired_formats
+                    .iter()
+                    .map(|(f, _)| (f.format, f.color_space))
+                    .collect::<Vec<_>>(),
+                self.formats
+                    .iter()
+                    .map(|f| (f.format, f.color_space))
+                    .collect::<Vec<_>>()
+            ));
+        }
+
+        if !request.desired_present_modes.is_empty()
+            && !request
+                .desired_present_modes
+                .iter()
+                .any(|(desired, _)| self.present_modes.contains(desired))
+        {
+            problems.push(format!(
+                "none of the desired present modes {:?} are available (available: {:?})",
+                request
+                    .desired_present_modes
+                    .iter()
+                    .map(|(p, _)| *p)
+                    .collect::<Vec<_>>(),
+                self.present_modes
+            ));
+        }
+
+        let required = request.required_min_image_count;
+        if required >= 1 && required < capabilities.min_image_count {
+            problems.push(format!(
+                "required image count {required} is below the surface minimum of {}",
+                capabilities.min_image_count
+            ));
+        }
+        if required >= 1
+            && capabilities.max_image_count > 0
+            && required > capabilities.max_image_count
+        {
+            problems.push(format!(
+                "required image count {required} is above the surface maximum of {}",
+                capabilities.max_image_count
+            ));
+        }
+
+        if request.array_layer_count > capabilities.max_image_array_layers {
+            problems.push(format!(
+                "{} image array layers requested but the surface supports at most {}",
+                request.array_layer_count, capabilities.max_image_array_layers
+            ));
+        }
+
+        if request.pre_transform != vk::SurfaceTransformFlagsKHR::default()
+            && !capabilities
+                .supported_transforms
+                .contains(request.pre_transform)
+        {
+            problems.push(format!(
+                "pre-transform {:?} is not supported (supported: {:?})",
+                request.pre_transform, capabilities.supported_transforms
+            ));
+        }
+
+        if request.composite_alpha_flags != vk::CompositeAlphaFlagsKHR::default()
+            && !capabilities
+                .supported_composite_alpha
+                .contains(request.composite_alpha_flags)
+        {
+            problems.push(format!(
+                "composite alpha {:?} is not supported (supported: {:?})",
+                request.composite_alpha_flags, capabilities.supported_composite_alpha
+            ));
+        }
+
+        if problems.is_empty() {
+            None
+        } else {
+            Some(problems.join("; "))
+        }
+    }
 }
 
-fn query_surface_support_details(
+pub(crate) fn query_surface_support_details(
     phys_device: vk::PhysicalDevice,
-    instance: &vulkanalia::Instance,
+    instance: &Instance,
     surface: Option<vk::SurfaceKHR>,
-) -> crate::Result<SurfaceFormatDetails> {
+) -> crate::Result<SurfaceSupport> {
     let Some(surface) = surface else {
-        return Err(crate::SwapchainError::SurfaceHandleNotProvided.into());
+        return Err(crate::ConfigError::SurfaceHandleNotProvided.into());
     };
 
-    let capabilities =
-        unsafe { instance.get_physical_device_surface_capabilities_khr(phys_device, surface) }?;
-    let formats =
-        unsafe { instance.get_physical_device_surface_formats_khr(phys_device, surface) }?;
-    let present_modes =
-        unsafe { instance.get_physical_device_surface_present_modes_khr(phys_device, surface) }?;
+    let present_modes = unsafe {
+        instance
+            .instance
+            .get_physical_device_surface_present_modes_khr(phys_device, surface)
+    }?;
+
+    if !instance.surface_capabilities2_enabled {
+        let capabilities = unsafe {
+            instance
+                .instance
+                .get_physical_device_surface_capabilities_khr(phys_device, surface)
+        }?;
+        let formats = unsafe {
+            instance
+                .instance
+                .get_physical_device_surface_formats_khr(phys_device, surface)
+        }?;
+
+        return Ok(SurfaceSupport {
+            capabilities,
+            formats,
+            present_modes,
+            ..Default::default()
+        });
+    }
+
+    let surface_info = vk::PhysicalDeviceSurfaceInfo2KHR::builder().surface(surface);
+
+    let mut protected_capabilities = vk::SurfaceProtectedCapabilitiesKHR::default();
+    let mut capabilities2 = vk::SurfaceCapabilities2KHR::builder();
+    if instance.surface_protected_capabilities_enabled {
+        capabilities2 = capabilities2.push_next(&mut protected_capabilities);
+    }
+    unsafe {
+        instance
+            .instance
+            .get_physical_device_surface_capabilities2_khr(
+                phys_device,
+                &surface_info,
+                &mut capabilities2,
+            )
+    }?;
+    let capabilities = capabilities2.surface_capabilities;
+
+    let formats = unsafe {
+        instance
+            .instance
+            .get_physical_device_surface_formats2_khr(phys_device, &surface_info)
+    }?
+    .into_iter()
+    .map(|format| format.surface_format)
+    .collect();
+
+    let mut present_mode_image_counts = Vec::new();
+    if instance.surface_maintenance1_enabled {
+        for &present_mode in &present_modes {
+            let mut surface_present_mode =
+                vk::SurfacePresentModeKHR::builder().present_mode(present_mode);
+            let surface_info = vk::PhysicalDeviceSurfaceInfo2KHR::builder()
+                .surface(surface)
+                .push_next(&mut surface_present_mode);
+            let mut capabilities2 = vk::SurfaceCapabilities2KHR::builder();
+            unsafe {
+                instance
+                    .instance
+                    .get_physical_device_surface_capabilities2_khr(
+                        phys_device,
+                        &surface_info,
+                        &mut capabilities2,
+                    )
+            }?;
+
+            present_mode_image_counts.push(PresentModeImageCount {
+                present_mode,
+                min_image_count: capabilities2.surface_capabilities.min_image_count,
+                max_image_count: capabilities2.surface_capabilities.max_image_count,
+            });
+        }
+    }
 
-    Ok(SurfaceFormatDetails {
+    Ok(SurfaceSupport {
         capabilities,
         formats,
         present_modes,
+        supports_protected: instance
+            .surface_protected_capabilities_enabled
+            .then_some(protected_capabilities.supports_protected == vk::TRUE),
+        present_mode_image_counts,
     })
 }
 
-fn default_formats<'a>() -> Vec<Format> {
+fn default_formats() -> Vec<Format> {
     vec![
         Format {
             inner: vk::SurfaceFormat2KHR {
                 surface_format: vk::SurfaceFormatKHR {
                     format: vk::Format::B8G8R8A8_SRGB,
                     color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
-                    ..Default::default()
                 },
                 ..Default::default()
             },
-            priority: Priority::Main,
+            priority: Priority::MAIN,
         },
         Format {
             inner: vk::SurfaceFormat2KHR {
                 surface_format: vk::SurfaceFormatKHR {
                     format: vk::Format::R8G8B8_SRGB,
                     color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
-                    ..Default::default()
                 },
                 ..Default::default()
             },
-            priority: Priority::Fallback,
+            priority: Priority::FALLBACK,
         },
     ]
 }
@@ -111,11 +470,11 @@ fn default_present_modes() -> Vec<PresentMode> {
     vec![
         PresentMode {
             inner: vk::PresentModeKHR::MAILBOX,
-            priority: Priority::Main,
+            priority: Priority::MAIN,
         },
         PresentMode {
             inner: vk::PresentModeKHR::FIFO,
-            priority: Priority::Fallback,
+            priority: Priority::FALLBACK,
         },
     ]
 }
@@ -124,8 +483,8 @@ fn find_desired_surface_format(
     available: &[vk::SurfaceFormatKHR],
     desired: &mut [Format],
 ) -> crate::Result<vk::SurfaceFormatKHR> {
-    if !desired.is_sorted_by_key(|f| f.priority.clone()) {
-        desired.sort_unstable_by_key(|f| f.priority.clone());
+    if !desired.is_sorted_by_key(|f| f.priority) {
+        desired.sort_by_key(|f| f.priority);
     }
 
     for desired in desired.iter() {
@@ -148,16 +507,16 @@ fn find_desired_surface_format(
 fn find_best_surface_format(
     available: &[vk::SurfaceFormatKHR],
     desired: &mut [Format],
-) -> vk::SurfaceFormatKHR {
-    find_desired_surface_format(available, desired).unwrap_or(available[0])
+) -> crate::Result<vk::SurfaceFormatKHR> {
+    find_desired_surface_format(available, desired).or_else(|e| available.first().copied().ok_or(e))
 }
 
 fn find_present_mode(
     available: &[vk::PresentModeKHR],
     desired: &mut [PresentMode],
 ) -> vk::PresentModeKHR {
-    if !desired.is_sorted_by_key(|f| f.priority.clone()) {
-        desired.sort_unstable_by_key(|f| f.priority.clone());
+    if !desired.is_sorted_by_key(|f| f.priority) {
+        desired.sort_by_key(|f| f.priority);
     }
 
     for desired in desired {
@@ -171,77 +530,294 @@ fn find_present_mode(
     vk::PresentModeKHR::FIFO
 }
 
-impl SwapchainBuilder {
-    fn find_extent(&self, capabilities: &vk::SurfaceCapabilitiesKHR) -> vk::Extent2D {
-        if capabilities.current_extent.width != u32::MAX {
-            capabilities.current_extent
-        } else {
-            let mut actual_extent = vk::Extent2D {
-                width: self.desired_width,
-                height: self.desired_height,
-            };
-
-            actual_extent.width = capabilities
-                .min_image_extent
-                .width
-                .max(capabilities.max_image_extent.width.min(actual_extent.width));
-            actual_extent.height = capabilities.min_image_extent.height.max(
-                capabilities
-                    .max_image_extent
-                    .height
-                    .min(actual_extent.height),
-            );
+/// Pick the swapchain extent: the surface's current extent when it has one and `auto_extent` is
+/// set, otherwise the desired extent clamped to the surface limits.
+fn find_extent(
+    desired: vk::Extent2D,
+    auto_extent: bool,
+    capabilities: &vk::SurfaceCapabilitiesKHR,
+) -> vk::Extent2D {
+    if auto_extent && capabilities.current_extent.width != u32::MAX {
+        return capabilities.current_extent;
+    }
+
+    let min = capabilities.min_image_extent;
+    let max = capabilities.max_image_extent;
+    vk::Extent2D {
+        width: min.width.max(max.width.min(desired.width)),
+        height: min.height.max(max.height.min(desired.height)),
+    }
+}
+
+/// Pick the number of swapchain images from the builder's minimum and required minimum.
+///
+/// A `max_image_count` of zero means there is no upper limit.
+fn find_image_count(
+    min_image_count: u32,
+    required_min_image_count: u32,
+    capabilities: &vk::SurfaceCapabilitiesKHR,
+) -> crate::Result<u32> {
+    let mut image_count = min_image_count;
+    if required_min_image_count >= 1 {
+        if required_min_image_count < capabilities.min_image_count {
+            return Err(crate::SwapchainError::RequiredMinImageCountTooLow.into());
+        }
+
+        image_count = required_min_image_count;
+    } else if image_count == 0 {
+        // We intentionally use minImageCount + 1 to maintain existing behavior,
+        // even if it typically results in triple buffering on most systems.
+        image_count = capabilities.min_image_count.saturating_add(1);
+    } else if image_count < capabilities.min_image_count {
+        image_count = capabilities.min_image_count
+    }
+
+    if capabilities.max_image_count > 0 && image_count > capabilities.max_image_count {
+        image_count = capabilities.max_image_count;
+    }
+
+    Ok(image_count.max(1))
+}
+
+/// Upper bound of the image counts [`SwapchainBuilder::enumerate_configs`] lists for surfaces
+/// that don't limit the number of images.
+const MAX_ENUMERATED_IMAGE_COUNT: u32 = 4;
+
+/// Image counts a surface with the given limits accepts, at least `required_min_image_count`.
+///
+/// A `max_image_count` of zero means there is no upper limit, the range then ends at
+/// [`MAX_ENUMERATED_IMAGE_COUNT`] or `image_count`, whichever is higher.
+fn valid_image_counts(
+    required_min_image_count: u32,
+    image_count: u32,
+    min_image_count: u32,
+    max_image_count: u32,
+) -> std::ops::RangeInclusive<u32> {
+    let min = min_image_count.max(required_min_image_count).max(1);
+    let max = if max_image_count > 0 {
+        max_image_count
+    } else {
+        MAX_ENUMERATED_IMAGE_COUNT.max(image_count).max(min)
+    };
+
+    min..=max
+}
+
+/// Pick the composite alpha mode. An explicit request must be supported by the surface, otherwise
+/// the first supported bit is used since not every surface supports OPAQUE (e.g. Android).
+fn find_composite_alpha(
+    requested: vk::CompositeAlphaFlagsKHR,
+    supported: vk::CompositeAlphaFlagsKHR,
+) -> crate::Result<vk::CompositeAlphaFlagsKHR> {
+    if requested != vk::CompositeAlphaFlagsKHR::default() {
+        if !supported.contains(requested) {
+            return Err(crate::SwapchainError::CompositeAlphaNotSupported(requested).into());
+        }
+
+        return Ok(requested);
+    }
+
+    let bits = supported.bits();
+    if bits == 0 {
+        return Ok(vk::CompositeAlphaFlagsKHR::OPAQUE);
+    }
 
-            actual_extent
+    Ok(vk::CompositeAlphaFlagsKHR::from_bits_truncate(
+        bits & bits.wrapping_neg(),
+    ))
+}
+
+/// Pick how the swapchain images are shared between the graphics and present families.
+///
+/// An override is validated against `family_count`; a `CONCURRENT` override that names a
+/// single family falls back to `EXCLUSIVE`, which is all Vulkan allows for it.
+fn find_image_sharing(
+    image_sharing: Option<&ResourceSharing>,
+    graphics_queue_family: u32,
+    present_queue_family: u32,
+    family_count: u32,
+) -> crate::Result<ResourceSharing> {
+    let Some(sharing) = image_sharing else {
+        return Ok(ResourceSharing::from_families([
+            graphics_queue_family,
+            present_queue_family,
+        ]));
+    };
+
+    if let Some(&index) = sharing
+        .queue_family_indices
+        .iter()
+        .find(|&&index| index >= family_count)
+    {
+        return Err(crate::ConfigError::InvalidQueueFamilyOverride {
+            index,
+            count: family_count,
         }
+        .into());
     }
 
+    if sharing.sharing_mode == vk::SharingMode::CONCURRENT {
+        Ok(ResourceSharing::from_families(
+            sharing.queue_family_indices.iter().copied(),
+        ))
+    } else {
+        Ok(sharing.clone())
+    }
+}
+
+impl SwapchainBuilder {
     pub fn new(instance: Arc<Instance>, device: Arc<Device>) -> Self {
         Self {
             graphics_queue_index: device.get_queue(QueueType::Graphics).unwrap().0,
             present_queue_index: device.get_queue(QueueType::Present).unwrap().0,
+            surface: instance.surface,
             instance,
             device,
             allocation_callbacks: None,
+            allocator: None,
             desired_formats: Vec::with_capacity(4),
             create_flags: vk::SwapchainCreateFlagsKHR::default(),
             desired_width: 256,
             desired_height: 256,
+            auto_extent: true,
             array_layer_count: 1,
             min_image_count: 0,
             required_min_image_count: 0,
             image_usage_flags: vk::ImageUsageFlags::COLOR_ATTACHMENT,
             pre_transform: vk::SurfaceTransformFlagsKHR::default(),
             desired_present_modes: Vec::with_capacity(4),
-            composite_alpha_flags_khr: vk::CompositeAlphaFlagsKHR::OPAQUE,
+            composite_alpha_flags_khr: vk::CompositeAlphaFlagsKHR::default(),
             clipped: true,
+            image_sharing: None,
+            low_latency: false,
             old_swapchain: Default::default(),
         }
     }
 
+    /// Capture the current plain-data settings of this builder.
+    pub fn to_config(&self) -> SwapchainConfig {
+        SwapchainConfig {
+            desired_formats: self
+                .desired_formats
+                .iter()
+                .map(|f| (f.inner.surface_format, f.priority.0))
+                .collect(),
+            desired_present_modes: self
+                .desired_present_modes
+                .iter()
+                .map(|p| (p.inner, p.priority.0))
+                .collect(),
+            create_flags: self.create_flags,
+            desired_extent: vk::Extent2D {
+                width: self.desired_width,
+                height: self.desired_height,
+            },
+            auto_extent: self.auto_extent,
+            array_layer_count: self.array_layer_count,
+            min_image_count: self.min_image_count,
+            required_min_image_count: self.required_min_image_count,
+            image_usage_flags: self.image_usage_flags,
+            composite_alpha_flags: self.composite_alpha_flags_khr,
+            pre_transform: self.pre_transform,
+            clipped: self.clipped,
+            image_sharing: self.image_sharing.clone(),
+            low_latency: self.low_latency,
+        }
+    }
+
+    /// Replace the plain-data settings of this builder with `config`.
+    pub fn with_config(mut self, config: SwapchainConfig) -> Self {
+        self.desired_formats = config
+            .desired_formats
+            .into_iter()
+            .map(|(surface_format, priority)| Format {
+                inner: vk::SurfaceFormat2KHR {
+                    surface_format,
+                    ..Default::default()
+                },
+                priority: Priority(priority),
+            })
+            .collect();
+        self.desired_present_modes = config
+            .desired_present_modes
+            .into_iter()
+            .map(|(inner, priority)| PresentMode {
+                inner,
+                priority: Priority(priority),
+            })
+            .collect();
+        self.create_flags = config.create_flags;
+        self.desired_width = config.desired_extent.width;
+        self.desired_height = config.desired_extent.height;
+        self.auto_extent = config.auto_extent;
+        self.array_layer_count = config.array_layer_count;
+        self.min_image_count = config.min_image_count;
+        self.required_min_image_count = config.required_min_image_count;
+        self.image_usage_flags = config.image_usage_flags;
+        self.composite_alpha_flags_khr = config.composite_alpha_flags;
+        self.pre_transform = config.pre_transform;
+        self.clipped = config.clipped;
+        self.image_sharing = config.image_sharing;
+        self.low_latency = config.low_latency;
+        self
+    }
+
     /// Add a preferred surface format to try when creating the swapchain.
     /// Preferred formats are evaluated in the order they are added (main before fallback).
     pub fn desired_format(mut self, format: vk::SurfaceFormat2KHR) -> Self {
         self.desired_formats.push(Format {
             inner: format,
-            priority: Priority::Main,
+            priority: Priority::MAIN,
+        });
+        self
+    }
+
+    /// Add a surface format with an explicit priority. Lower values are tried first, so
+    /// `0` matches [`SwapchainBuilder::desired_format`] and `u32::MAX` matches
+    /// [`SwapchainBuilder::fallback_format`].
+    pub fn desired_format_with_priority(
+        mut self,
+        format: vk::SurfaceFormat2KHR,
+        priority: u32,
+    ) -> Self {
+        self.desired_formats.push(Format {
+            inner: format,
+            priority: Priority(priority),
         });
         self
     }
 
     /// Set the desired width/height for the swapchain extent when the surface allows
-    /// an arbitrary size (i.e. current_extent.width == u32::MAX).
+    /// an arbitrary size (i.e. current_extent.width == u32::MAX). Defaults to 256x256.
     pub fn desired_size(mut self, size: vk::Extent2D) -> Self {
         self.desired_width = size.width;
         self.desired_height = size.height;
         self
     }
 
+    /// Use the surface's current extent when it reports one (the default). When disabled, the
+    /// desired size is used on every surface, clamped to the surface's extent limits.
+    pub fn auto_extent(mut self, auto_extent: bool) -> Self {
+        self.auto_extent = auto_extent;
+        self
+    }
+
+    #[cfg(feature = "winit")]
+    pub(crate) fn set_desired_size(&mut self, size: vk::Extent2D) {
+        self.desired_width = size.width;
+        self.desired_height = size.height;
+    }
+
+    #[cfg(feature = "winit")]
+    pub(crate) fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
     /// Add a fallback surface format to consider if preferred formats are not available.
     pub fn fallback_format(mut self, format: vk::SurfaceFormat2KHR) -> Self {
         self.desired_formats.push(Format {
             inner: format,
-            priority: Priority::Fallback,
+            priority: Priority::FALLBACK,
         });
         self
     }
@@ -261,7 +837,22 @@ impl SwapchainBuilder {
     pub fn desired_present_mode(mut self, present_mode: vk::PresentModeKHR) -> Self {
         self.desired_present_modes.push(PresentMode {
             inner: present_mode,
-            priority: Priority::Main,
+            priority: Priority::MAIN,
+        });
+        self
+    }
+
+    /// Add a present mode with an explicit priority. Lower values are tried first, so
+    /// `0` matches [`SwapchainBuilder::desired_present_mode`] and `u32::MAX` matches
+    /// [`SwapchainBuilder::fallback_present_mode`].
+    pub fn desired_present_mode_with_priority(
+        mut self,
+        present_mode: vk::PresentModeKHR,
+        priority: u32,
+    ) -> Self {
+        self.desired_present_modes.push(PresentMode {
+            inner: present_mode,
+            priority: Priority(priority),
         });
         self
     }
@@ -270,7 +861,7 @@ impl SwapchainBuilder {
     pub fn fallback_present_mode(mut self, present_mode: vk::PresentModeKHR) -> Self {
         self.desired_present_modes.push(PresentMode {
             inner: present_mode,
-            priority: Priority::Fallback,
+            priority: Priority::FALLBACK,
         });
         self
     }
@@ -282,11 +873,20 @@ impl SwapchainBuilder {
 
     /// Sets the desired minimum image count for the swapchain.
     /// Note that the presentation engine is always free to create more images than requested.
-    /// You may pass one of the values specified in the BufferMode enum, or any integer value.
-    /// For instance, if you pass DOUBLE_BUFFERING, the presentation engine is allowed to give you a double buffering setup,
+    /// You may pass one of the values specified in the [`BufferMode`] enum, or any integer value.
+    /// For instance, if you pass [`BufferMode::Double`], the presentation engine is allowed to give you a double buffering setup,
     /// triple buffering, or more. This is up to the drivers.
-    pub fn desired_min_image_count(mut self, min_image_count: u32) -> Self {
-        self.min_image_count = min_image_count;
+    pub fn desired_min_image_count(mut self, min_image_count: impl Into<u32>) -> Self {
+        self.min_image_count = min_image_count.into();
+        self
+    }
+
+    /// Sets a required minimum image count for the swapchain.
+    /// If the surface requires more images than this, building fails with
+    /// `SwapchainError::RequiredMinImageCountTooLow`. Takes precedence over
+    /// [`SwapchainBuilder::desired_min_image_count`]. Pass 0 to clear the requirement.
+    pub fn required_min_image_count(mut self, required_min_image_count: impl Into<u32>) -> Self {
+        self.required_min_image_count = required_min_image_count.into();
         self
     }
 
@@ -301,6 +901,50 @@ impl SwapchainBuilder {
         self
     }
 
+    /// Create the swapchain with low latency mode allowed, so [`Swapchain::set_latency_sleep_mode`]
+    /// and the other `VK_NV_low_latency2` wrappers can be used. Ignored unless the device was
+    /// built with [`crate::DeviceBuilder::enable_low_latency`] and the extension is available.
+    pub fn low_latency(mut self, low_latency: bool) -> Self {
+        self.low_latency = low_latency;
+        self
+    }
+
+    /// Override how the swapchain images are shared between queue families.
+    ///
+    /// By default the images are `CONCURRENT` between the graphics and present families when they
+    /// differ and `EXCLUSIVE` otherwise. Use [`Device::resource_sharing`] to include e.g. a
+    /// dedicated transfer family, or pass an `EXCLUSIVE` sharing and handle the ownership
+    /// transfers manually.
+    pub fn image_sharing(mut self, sharing: ResourceSharing) -> Self {
+        self.image_sharing = Some(sharing);
+        self
+    }
+
+    /// Set the number of views in a multiview/stereo surface (e.g. 2 for a VR mirror).
+    /// Default is 1. Building fails with `SwapchainError::ImageArrayLayersNotSupported` if
+    /// the surface supports fewer layers.
+    pub fn image_array_layers(mut self, array_layer_count: u32) -> Self {
+        self.array_layer_count = array_layer_count;
+        self
+    }
+
+    /// Set the transform applied to images before presentation (e.g. display rotation on
+    /// Android). Defaults to the surface's current transform.
+    /// Building fails with `SwapchainError::PreTransformNotSupported` if the surface does not
+    /// support it.
+    pub fn pre_transform(mut self, transform: vk::SurfaceTransformFlagsKHR) -> Self {
+        self.pre_transform = transform;
+        self
+    }
+
+    /// Set how the alpha channel is used when compositing the surface. Defaults to the first
+    /// mode the surface supports (OPAQUE when available). Building fails with `SwapchainError::CompositeAlphaNotSupported` if the surface does not
+    /// support it.
+    pub fn composite_alpha_flags(mut self, flags: vk::CompositeAlphaFlagsKHR) -> Self {
+        self.composite_alpha_flags_khr = flags;
+        self
+    }
+
     pub fn create_flags(mut self, flags: vk::SwapchainCreateFlagsKHR) -> Self {
         self.create_flags = flags;
         self
@@ -321,80 +965,261 @@ impl SwapchainBuilder {
 
     pub fn allocation_callbacks(mut self, allocation_callbacks: AllocationCallbacks) -> Self {
         self.allocation_callbacks = Some(allocation_callbacks);
+        self.allocator = None;
+        self
+    }
+
+    /// Allocate host memory for the swapchain and its image views with `allocator`. Every
+    /// swapchain built keeps the allocator alive until it is dropped.
+    pub fn allocator(mut self, allocator: TrackedAllocationCallbacks) -> Self {
+        self.allocation_callbacks = Some(allocator.callbacks());
+        self.allocator = Some(allocator);
         self
     }
 
     /// This method should be called with previously created [`Swapchain`].
     ///
     /// # Note:
-    /// This method will mark old swapchain and destroy it when creating a new one.
+    /// This method will mark old swapchain and destroy it when creating a new one. If the driver
+    /// fails to create the new swapchain the old one is destroyed as well, since it was retired.
+    /// When building fails before that (e.g. `SwapchainError::ZeroExtent`) it is kept for the
+    /// next build.
     pub fn set_old_swapchain(&self, swapchain: Swapchain) {
         if swapchain.destroy_image_views().is_err() {
             #[cfg(feature = "enable_tracing")]
             tracing::warn!("Could not destroy swapchain image views");
             return;
         };
-        self.old_swapchain
-            .store(swapchain.swapchain.as_raw(), Ordering::Relaxed);
+        self.old_swapchain.set(swapchain.swapchain.as_raw());
     }
 
-    pub fn build(&self) -> crate::Result<Swapchain> {
-        if self.instance.surface.is_none() {
-            return Err(crate::SwapchainError::SurfaceHandleNotProvided.into());
-        };
+    /// Build the swapchain for `surface` instead of the instance surface, e.g. for a second
+    /// window. Building fails with `SwapchainError::PresentNotSupported` when the device's
+    /// present queue family can't present to it.
+    pub fn surface(mut self, surface: vk::SurfaceKHR) -> Self {
+        self.surface = Some(surface);
+        self
+    }
 
-        let mut desired_formats = self.desired_formats.clone();
-        if desired_formats.is_empty() {
-            desired_formats = default_formats();
+    /// Query what the surface supports on the device's physical device.
+    pub fn surface_support(&self) -> crate::Result<SurfaceSupport> {
+        let Some(surface) = self.surface else {
+            return Err(crate::ConfigError::SurfaceHandleNotProvided.into());
         };
-
-        let mut desired_present_modes = self.desired_present_modes.clone();
-        if desired_present_modes.is_empty() {
-            desired_present_modes = default_present_modes();
+        if !self
+            .device
+            .supports_present(surface, self.present_queue_index as u32)?
+        {
+            return Err(crate::SwapchainError::PresentNotSupported {
+                queue_family: self.present_queue_index as u32,
+            }
+            .into());
         }
 
-        let surface_support = query_surface_support_details(
+        query_surface_support_details(
             *self.device.physical_device().as_ref(),
-            &self.instance.instance,
-            self.instance.surface,
+            &self.instance,
+            Some(surface),
+        )
+    }
+
+    fn desired_formats(&self) -> Vec<Format> {
+        if self.desired_formats.is_empty() {
+            default_formats()
+        } else {
+            self.desired_formats.clone()
+        }
+    }
+
+    fn desired_present_modes(&self) -> Vec<PresentMode> {
+        if self.desired_present_modes.is_empty() {
+            default_present_modes()
+        } else {
+            self.desired_present_modes.clone()
+        }
+    }
+
+    /// Enumerate every (format, present mode, image count) combination the surface supports,
+    /// ranked by the builder's preferences (best first).
+    ///
+    /// Desired formats and present modes rank above the ones that were not asked for, and image
+    /// counts closer to the builder's image count rank higher. Image counts follow the per present
+    /// mode limits when surface maintenance1 is enabled. Surfaces without a maximum image count
+    /// are enumerated up to 4 images or the builder's image count, whichever is higher. Any of
+    /// the returned candidates can be passed to [`SwapchainBuilder::build_with_config`].
+    pub fn enumerate_configs(&self) -> crate::Result<Vec<SwapchainCandidate>> {
+        let surface_support = self.surface_support()?;
+        let image_count = find_image_count(
+            self.min_image_count,
+            self.required_min_image_count,
+            &surface_support.capabilities,
         )?;
 
-        let mut image_count = self.min_image_count;
-        if image_count >= 1 {
-            if self.required_min_image_count < surface_support.capabilities.min_image_count {
-                return Err(crate::SwapchainError::RequiredMinImageCountTooLow.into());
-            }
+        let mut desired_formats = self.desired_formats();
+        desired_formats.sort_by_key(|f| f.priority);
+        let mut desired_present_modes = self.desired_present_modes();
+        desired_present_modes.sort_by_key(|p| p.priority);
+
+        let format_rank = |format: &vk::SurfaceFormatKHR| {
+            desired_formats
+                .iter()
+                .position(|d| {
+                    d.inner.surface_format.format == format.format
+                        && d.inner.surface_format.color_space == format.color_space
+                })
+                .unwrap_or(desired_formats.len())
+        };
+        let present_mode_rank = |present_mode: &vk::PresentModeKHR| {
+            desired_present_modes
+                .iter()
+                .position(|d| &d.inner == present_mode)
+                .unwrap_or(desired_present_modes.len())
+        };
 
-            image_count = surface_support.capabilities.min_image_count;
-        } else if image_count == 0 {
-            // We intentionally use minImageCount + 1 to maintain existing behavior,
-            // even if it typically results in triple buffering on most systems.
-            image_count = surface_support.capabilities.min_image_count + 1;
-        } else if image_count < surface_support.capabilities.min_image_count {
-            image_count = surface_support.capabilities.min_image_count
+        let mut candidates = Vec::new();
+        for format in &surface_support.formats {
+            for &present_mode in &surface_support.present_modes {
+                let (min, max) = surface_support
+                    .present_mode_image_counts
+                    .iter()
+                    .find(|counts| counts.present_mode == present_mode)
+                    .map_or(
+                        (
+                            surface_support.capabilities.min_image_count,
+                            surface_support.capabilities.max_image_count,
+                        ),
+                        |counts| (counts.min_image_count, counts.max_image_count),
+                    );
+
+                candidates.extend(
+                    valid_image_counts(self.required_min_image_count, image_count, min, max).map(
+                        |image_count| SwapchainCandidate {
+                            format: *format,
+                            present_mode,
+                            image_count,
+                        },
+                    ),
+                );
+            }
         }
 
-        if surface_support.capabilities.max_image_count > 0
-            && image_count > surface_support.capabilities.max_image_count
+        candidates.sort_by_key(|c| {
+            (
+                format_rank(&c.format),
+                present_mode_rank(&c.present_mode),
+                c.image_count.abs_diff(image_count),
+                c.image_count,
+            )
+        });
+
+        Ok(candidates)
+    }
+
+    /// Run the checks of [`SwapchainBuilder::build`] and return the configuration it would
+    /// create, without creating anything.
+    pub fn plan(&self) -> crate::Result<SwapchainPlan> {
+        let surface_support = self.surface_support()?;
+        let candidate = self.preferred_candidate(&surface_support)?;
+
+        self.resolve_plan(&surface_support, candidate)
+    }
+
+    /// Build the swapchain using the configuration picked from the builder's preferences.
+    pub fn build(&self) -> crate::Result<Swapchain> {
+        let surface_support = self.surface_support()?;
+        let candidate = self.preferred_candidate(&surface_support)?;
+        let plan = self.resolve_plan(&surface_support, candidate)?;
+
+        self.create_swapchain(&surface_support, &plan)
+    }
+
+    fn preferred_candidate(
+        &self,
+        surface_support: &SurfaceSupport,
+    ) -> crate::Result<SwapchainCandidate> {
+        let mut desired_formats = self.desired_formats();
+        let mut desired_present_modes = self.desired_present_modes();
+
+        Ok(SwapchainCandidate {
+            format: find_best_surface_format(&surface_support.formats, &mut desired_formats)?,
+            present_mode: find_present_mode(
+                &surface_support.present_modes,
+                &mut desired_present_modes,
+            ),
+            image_count: find_image_count(
+                self.min_image_count,
+                self.required_min_image_count,
+                &surface_support.capabilities,
+            )?,
+        })
+    }
+
+    /// Build the swapchain with an explicit candidate, usually one returned by
+    /// [`SwapchainBuilder::enumerate_configs`].
+    ///
+    /// The format and present mode must still be supported by the surface. The image count is
+    /// clamped to the surface limits.
+    pub fn build_with_config(&self, config: &SwapchainCandidate) -> crate::Result<Swapchain> {
+        let surface_support = self.surface_support()?;
+
+        if !surface_support
+            .formats
+            .iter()
+            .any(|f| f.format == config.format.format && f.color_space == config.format.color_space)
         {
-            image_count = surface_support.capabilities.max_image_count;
+            return Err(crate::SwapchainError::NoSuitableDesiredFormat(FormatError {
+                available: surface_support.formats,
+                desired: vec![config.format],
+            })
+            .into());
         }
 
-        let surface_format =
-            find_best_surface_format(&surface_support.formats, &mut desired_formats);
-
-        let extent = self.find_extent(&surface_support.capabilities);
+        if !surface_support.present_modes.contains(&config.present_mode) {
+            return Err(crate::SwapchainError::PresentModeNotSupported(config.present_mode).into());
+        }
 
-        let mut image_array_layers = self.array_layer_count;
-        if surface_support.capabilities.max_image_array_layers < image_array_layers {
-            image_array_layers = surface_support.capabilities.max_image_array_layers;
+        let capabilities = &surface_support.capabilities;
+        let mut image_count = config.image_count.max(capabilities.min_image_count);
+        if capabilities.max_image_count > 0 {
+            image_count = image_count.min(capabilities.max_image_count);
         }
-        if image_array_layers == 0 {
-            image_array_layers = 1;
+
+        let candidate = SwapchainCandidate {
+            image_count,
+            ..*config
+        };
+        let plan = self.resolve_plan(&surface_support, candidate)?;
+
+        self.create_swapchain(&surface_support, &plan)
+    }
+
+    fn resolve_plan(
+        &self,
+        surface_support: &SurfaceSupport,
+        candidate: SwapchainCandidate,
+    ) -> crate::Result<SwapchainPlan> {
+        let present_mode = candidate.present_mode;
+
+        let extent = find_extent(
+            vk::Extent2D {
+                width: self.desired_width,
+                height: self.desired_height,
+            },
+            self.auto_extent,
+            &surface_support.capabilities,
+        );
+        if extent.width == 0 || extent.height == 0 {
+            return Err(crate::SwapchainError::ZeroExtent.into());
         }
 
-        let present_mode =
-            find_present_mode(&surface_support.present_modes, &mut desired_present_modes);
+        if surface_support.capabilities.max_image_array_layers < self.array_layer_count {
+            return Err(crate::SwapchainError::ImageArrayLayersNotSupported {
+                requested: self.array_layer_count,
+                max: surface_support.capabilities.max_image_array_layers,
+            }
+            .into());
+        }
+        let image_array_layers = self.array_layer_count.max(1);
 
         let is_unextended_present_mode =
             matches!(
@@ -416,79 +1241,217 @@ impl SwapchainBuilder {
         let mut pre_transform = self.pre_transform;
         if pre_transform == vk::SurfaceTransformFlagsKHR::default() {
             pre_transform = surface_support.capabilities.current_transform;
+        } else if !surface_support
+            .capabilities
+            .supported_transforms
+            .contains(pre_transform)
+        {
+            return Err(crate::SwapchainError::PreTransformNotSupported(pre_transform).into());
+        }
+
+        let composite_alpha = find_composite_alpha(
+            self.composite_alpha_flags_khr,
+            surface_support.capabilities.supported_composite_alpha,
+        )?;
+
+        let image_sharing = find_image_sharing(
+            self.image_sharing.as_ref(),
+            self.graphics_queue_index as _,
+            self.present_queue_index as _,
+            self.device.physical_device().queue_families.len() as _,
+        )?;
+
+        Ok(SwapchainPlan {
+            candidate,
+            extent,
+            image_array_layers,
+            image_usage: self.image_usage_flags,
+            pre_transform,
+            composite_alpha,
+            image_sharing,
+        })
+    }
+
+    fn destroy_old_swapchain(&self, old_swapchain: u64) {
+        if self.old_swapchain.take(old_swapchain) {
+            self.device
+                .live_objects()
+                .untrack(vk::ObjectType::SWAPCHAIN_KHR, old_swapchain);
+            unsafe {
+                self.device.destroy_swapchain_khr(
+                    SwapchainKHR::from_raw(old_swapchain),
+                    self.allocation_callbacks.as_ref(),
+                )
+            }
         }
+    }
+
+    /// Destroy the swapchain passed to [`SwapchainBuilder::set_old_swapchain`] if no build has
+    /// consumed it yet.
+    #[cfg(feature = "winit")]
+    pub(crate) fn discard_old_swapchain(&self) {
+        self.destroy_old_swapchain(self.old_swapchain.get());
+    }
 
-        let old_swapchain = self.old_swapchain.load(Ordering::Relaxed);
+    fn create_swapchain(
+        &self,
+        surface_support: &SurfaceSupport,
+        plan: &SwapchainPlan,
+    ) -> crate::Result<Swapchain> {
+        let surface_format = plan.candidate.format;
+        let present_mode = plan.candidate.present_mode;
+        let extent = plan.extent;
+        let old_swapchain = self.old_swapchain.get();
 
         let mut swapchain_create_info = vk::SwapchainCreateInfoKHR::builder()
             .flags(self.create_flags)
-            .surface(self.instance.surface.unwrap())
-            .min_image_count(image_count)
+            .surface(self.surface.unwrap())
+            .min_image_count(plan.candidate.image_count)
             .image_format(surface_format.format)
             .image_color_space(surface_format.color_space)
             .image_extent(extent)
-            .image_array_layers(image_array_layers)
-            .image_usage(self.image_usage_flags)
-            .pre_transform(pre_transform)
-            .composite_alpha(self.composite_alpha_flags_khr)
+            .image_array_layers(plan.image_array_layers)
+            .image_usage(plan.image_usage)
+            .pre_transform(plan.pre_transform)
+            .composite_alpha(plan.composite_alpha)
             .present_mode(present_mode)
             .clipped(self.clipped)
             .old_swapchain(SwapchainKHR::from_raw(old_swapchain));
 
-        let queue_family_indices = [
-            self.graphics_queue_index as _,
-            self.present_queue_index as _,
-        ];
+        swapchain_create_info.image_sharing_mode = plan.image_sharing.sharing_mode;
+        swapchain_create_info =
+            swapchain_create_info.queue_family_indices(plan.image_sharing.create_info_indices());
 
-        if self.graphics_queue_index != self.present_queue_index {
-            swapchain_create_info.image_sharing_mode = vk::SharingMode::CONCURRENT;
-            swapchain_create_info =
-                swapchain_create_info.queue_family_indices(&queue_family_indices);
-        } else {
-            swapchain_create_info.image_sharing_mode = vk::SharingMode::EXCLUSIVE;
+        let low_latency = self.low_latency && self.device.low_latency_enabled();
+        let mut latency_create_info =
+            vk::SwapchainLatencyCreateInfoNV::builder().latency_mode_enable(true);
+        if low_latency {
+            swapchain_create_info = swapchain_create_info.push_next(&mut latency_create_info);
         }
 
+        let image_sharing_mode = swapchain_create_info.image_sharing_mode;
+        let (_, present_queue) = self.device.get_queue(QueueType::Present)?;
+
         let swapchain = unsafe {
             self.device
                 .create_swapchain_khr(&swapchain_create_info, self.allocation_callbacks.as_ref())
         }
-        .map_err(|_| crate::SwapchainError::FailedCreateSwapchain)?;
+        .map_err(|code| {
+            // The old swapchain is retired even when creation fails and can't be passed again.
+            self.destroy_old_swapchain(old_swapchain);
 
-        if old_swapchain != 0 {
-            unsafe {
-                self.device.destroy_swapchain_khr(
-                    SwapchainKHR::from_raw(old_swapchain),
-                    self.allocation_callbacks.as_ref(),
-                )
+            match surface_support.explain_incompatibility(&plan.to_request()) {
+                Some(explanation) => {
+                    crate::SwapchainError::IncompatibleConfiguration { explanation, code }
+                }
+                None => crate::SwapchainError::FailedCreateSwapchain(code),
             }
-        }
+        })?;
+
+        self.device
+            .live_objects()
+            .track(vk::ObjectType::SWAPCHAIN_KHR, swapchain.as_raw());
+
+        self.destroy_old_swapchain(old_swapchain);
+
+        let image_count = match unsafe { self.device.get_swapchain_images_khr(swapchain) } {
+            Ok(images) => images.len() as u32,
+            Err(_) => {
+                self.device
+                    .live_objects()
+                    .untrack(vk::ObjectType::SWAPCHAIN_KHR, swapchain.as_raw());
+                unsafe {
+                    self.device
+                        .destroy_swapchain_khr(swapchain, self.allocation_callbacks.as_ref())
+                };
+                return Err(crate::SwapchainError::FailedGetSwapchainImages.into());
+            }
+        };
 
         Ok(Swapchain {
             device: self.device.clone(),
             swapchain,
             extent,
             image_format: surface_format.format,
+            color_space: surface_format.color_space,
+            present_mode,
+            image_count,
             image_usage_flags: self.image_usage_flags,
             instance_version: self.instance.instance_version,
             allocation_callbacks: self.allocation_callbacks,
+            _allocator: self.allocator.clone(),
             image_views: Mutex::new(Vec::with_capacity(image_count as _)),
+            image_sharing_mode,
+            graphics_queue_family: self.graphics_queue_index as _,
+            present_queue_family: self.present_queue_index as _,
+            present_queue,
+            low_latency,
+            generation: NEXT_GENERATION.fetch_add(1, Ordering::Relaxed),
         })
     }
 }
 
+/// A swapchain image together with its view, as returned by [`Swapchain::images`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapchainImage {
+    pub index: u32,
+    pub image: vk::Image,
+    pub view: vk::ImageView,
+    pub format: vk::Format,
+}
+
 #[derive(Debug)]
 pub struct Swapchain {
     device: Arc<Device>,
     swapchain: vk::SwapchainKHR,
     pub image_format: vk::Format,
     pub extent: vk::Extent2D,
+    color_space: vk::ColorSpaceKHR,
+    present_mode: vk::PresentModeKHR,
+    image_count: u32,
     image_usage_flags: vk::ImageUsageFlags,
     instance_version: Version,
     allocation_callbacks: Option<AllocationCallbacks>,
+    _allocator: Option<TrackedAllocationCallbacks>,
     image_views: Mutex<Vec<vk::ImageView>>,
+    image_sharing_mode: vk::SharingMode,
+    graphics_queue_family: u32,
+    present_queue_family: u32,
+    present_queue: vk::Queue,
+    low_latency: bool,
+    generation: u64,
 }
 
 impl Swapchain {
+    /// Increases every time a swapchain is built, recreations included.
+    ///
+    /// Values are unique within the process, so caches of swapchain dependent state (pipelines
+    /// with static formats, framebuffers) can store the generation they were built for and
+    /// rebuild when it differs.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Present mode the swapchain was created with.
+    pub fn present_mode(&self) -> vk::PresentModeKHR {
+        self.present_mode
+    }
+
+    /// Color space of the swapchain images.
+    pub fn color_space(&self) -> vk::ColorSpaceKHR {
+        self.color_space
+    }
+
+    /// Number of images the presentation engine actually created.
+    pub fn image_count(&self) -> u32 {
+        self.image_count
+    }
+
+    /// Usage flags the swapchain images were created with.
+    pub fn image_usage(&self) -> vk::ImageUsageFlags {
+        self.image_usage_flags
+    }
+
     /// Retrieve the images currently owned by the swapchain.
     pub fn get_images(&self) -> crate::Result<Vec<vk::Image>> {
         let images = unsafe { self.device.get_swapchain_images_khr(self.swapchain) }?;
@@ -501,6 +1464,9 @@ impl Swapchain {
         let mut image_views = self.image_views.lock().unwrap();
 
         for image_view in image_views.drain(..) {
+            self.device
+                .live_objects()
+                .untrack(vk::ObjectType::IMAGE_VIEW, image_view.as_raw());
             unsafe {
                 self.device
                     .device()
@@ -550,6 +1516,12 @@ impl Swapchain {
             })
             .collect::<crate::Result<_>>()?;
 
+        for view in &views {
+            self.device
+                .live_objects()
+                .track(vk::ObjectType::IMAGE_VIEW, view.as_raw());
+        }
+
         {
             let mut image_views = self.image_views.lock().unwrap();
             *image_views = views.clone();
@@ -558,9 +1530,333 @@ impl Swapchain {
         Ok(views)
     }
 
+    /// Queue the swapchain images are presented on.
+    pub fn present_queue(&self) -> vk::Queue {
+        self.present_queue
+    }
+
+    /// Ownership transfer from the graphics to the present family that must be recorded before
+    /// presenting, if any.
+    ///
+    /// Only needed when the images are `EXCLUSIVE` (see [`SwapchainBuilder::image_sharing`]) and
+    /// the graphics and present families differ. Release the image on the graphics queue, then
+    /// acquire it on the present queue with a barrier that waits on the render semaphore and
+    /// signals the semaphore passed to [`Swapchain::present`].
+    pub fn present_ownership_transfer(&self) -> Option<QueueFamilyOwnershipTransfer> {
+        (self.image_sharing_mode == vk::SharingMode::EXCLUSIVE
+            && self.graphics_queue_family != self.present_queue_family)
+            .then_some(QueueFamilyOwnershipTransfer {
+                src_queue_family_index: self.graphics_queue_family,
+                dst_queue_family_index: self.present_queue_family,
+            })
+    }
+
+    /// Present `image_index` on the present queue once `wait_semaphores` are signaled.
+    ///
+    /// The semaphores must be signaled by the submission that rendered the image; semaphores
+    /// synchronize across queues, so no extra work is needed when the present family differs
+    /// from the graphics family unless [`Swapchain::present_ownership_transfer`] returns a
+    /// transfer. Returns `true` when the swapchain is suboptimal. An out of date swapchain is
+    /// reported as `Error::VulkanErr(vk::ErrorCode::OUT_OF_DATE_KHR)` and must be recreated.
+    pub fn present(
+        &self,
+        image_index: u32,
+        wait_semaphores: &[vk::Semaphore],
+    ) -> crate::Result<bool> {
+        self.queue_present(image_index, wait_semaphores, None, None)
+    }
+
+    /// Like [`Swapchain::present`], tagging the present with `present_id` for
+    /// [`Swapchain::wait_for_present`]. Ids must increase with every present on this swapchain.
+    /// The id is dropped when `VK_KHR_present_id` is not enabled, see
+    /// [`crate::DeviceBuilder::enable_present_id`].
+    pub fn present_with_id(
+        &self,
+        image_index: u32,
+        wait_semaphores: &[vk::Semaphore],
+        present_id: u64,
+    ) -> crate::Result<bool> {
+        self.queue_present(image_index, wait_semaphores, Some(present_id), None)
+    }
+
+    /// Like [`Swapchain::present`], asking the presentation engine not to show the image before
+    /// `present_time.desired_present_time` (nanoseconds, `CLOCK_MONOTONIC`). The time is dropped
+    /// when `VK_GOOGLE_display_timing` is not enabled, see
+    /// [`crate::DeviceBuilder::enable_display_timing`].
+    pub fn present_with_time(
+        &self,
+        image_index: u32,
+        wait_semaphores: &[vk::Semaphore],
+        present_time: vk::PresentTimeGOOGLE,
+    ) -> crate::Result<bool> {
+        self.queue_present(image_index, wait_semaphores, None, Some(present_time))
+    }
+
+    fn queue_present(
+        &self,
+        image_index: u32,
+        wait_semaphores: &[vk::Semaphore],
+        present_id: Option<u64>,
+        present_time: Option<vk::PresentTimeGOOGLE>,
+    ) -> crate::Result<bool> {
+        let swapchains = [self.swapchain];
+        let image_indices = [image_index];
+        let mut present_info = vk::PresentInfoKHR::builder()
+            .wait_semaphores(wait_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices);
+
+        let present_ids = present_id.map(|id| [id]);
+        let mut present_id_info = vk::PresentIdKHR::builder();
+        if let Some(present_ids) = &present_ids
+            && self.device.present_id_enabled()
+        {
+            present_id_info = present_id_info.present_ids(present_ids);
+            present_info = present_info.push_next(&mut present_id_info);
+        }
+
+        let present_times = present_time.map(|time| [time]);
+        let mut present_times_info = vk::PresentTimesInfoGOOGLE::builder();
+        if let Some(present_times) = &present_times
+            && self.device.display_timing_enabled()
+        {
+            present_times_info = present_times_info.times(present_times);
+            present_info = present_info.push_next(&mut present_times_info);
+        }
+
+        let _access = self.device.audit_queue(self.present_queue);
+        let result = unsafe {
+            self.device
+                .queue_present_khr(self.present_queue, &present_info)
+        }?;
+
+        Ok(result == vk::SuccessCode::SUBOPTIMAL_KHR)
+    }
+
+    /// Wait until the present tagged with `present_id` (or a later one) is visible, for at most
+    /// `timeout` nanoseconds. Returns `false` when the timeout expired.
+    ///
+    /// Fails with `FEATURE_NOT_PRESENT` unless `VK_KHR_present_wait` was enabled, see
+    /// [`crate::DeviceBuilder::enable_present_wait`].
+    pub fn wait_for_present(&self, present_id: u64, timeout: u64) -> crate::Result<bool> {
+        if !self.device.present_wait_enabled() {
+            return Err(vk::ErrorCode::FEATURE_NOT_PRESENT.into());
+        }
+
+        let result = unsafe {
+            self.device
+                .wait_for_present_khr(self.swapchain, present_id, timeout)
+        }?;
+
+        Ok(result != vk::SuccessCode::TIMEOUT)
+    }
+
+    /// Duration of a refresh cycle of the display the swapchain presents to, in nanoseconds.
+    ///
+    /// Fails with `EXTENSION_NOT_PRESENT` unless `VK_GOOGLE_display_timing` was enabled, see
+    /// [`crate::DeviceBuilder::enable_display_timing`].
+    pub fn refresh_cycle_duration(&self) -> crate::Result<u64> {
+        if !self.device.display_timing_enabled() {
+            return Err(vk::ErrorCode::EXTENSION_NOT_PRESENT.into());
+        }
+
+        let duration = unsafe {
+            self.device
+                .get_refresh_cycle_duration_google(self.swapchain)
+        }?;
+
+        Ok(duration.refresh_duration)
+    }
+
+    /// Timing of the presents made with [`Swapchain::present_with_time`] that completed since
+    /// the last call. Each present is only reported once.
+    ///
+    /// Fails with `EXTENSION_NOT_PRESENT` unless `VK_GOOGLE_display_timing` was enabled.
+    pub fn past_presentation_timing(&self) -> crate::Result<Vec<vk::PastPresentationTimingGOOGLE>> {
+        if !self.device.display_timing_enabled() {
+            return Err(vk::ErrorCode::EXTENSION_NOT_PRESENT.into());
+        }
+
+        Ok(unsafe {
+            self.device
+                .get_past_presentation_timing_google(self.swapchain)
+        }?)
+    }
+
+    /// Whether the swapchain was created with low latency mode allowed, see
+    /// [`SwapchainBuilder::low_latency`].
+    pub fn low_latency(&self) -> bool {
+        self.low_latency
+    }
+
+    fn require_low_latency(&self) -> crate::Result<()> {
+        if !self.low_latency {
+            return Err(vk::ErrorCode::EXTENSION_NOT_PRESENT.into());
+        }
+        Ok(())
+    }
+
+    /// Turn low latency mode on or off. `minimum_interval_us` caps the frame rate (0 for no
+    /// limit), `low_latency_boost` trades power for latency by keeping clocks high.
+    ///
+    /// All low latency wrappers fail with `EXTENSION_NOT_PRESENT` unless
+    /// [`Swapchain::low_latency`] is set.
+    pub fn set_latency_sleep_mode(
+        &self,
+        low_latency_mode: bool,
+        low_latency_boost: bool,
+        minimum_interval_us: u32,
+    ) -> crate::Result<()> {
+        self.require_low_latency()?;
+        let sleep_mode_info = vk::LatencySleepModeInfoNV::builder()
+            .low_latency_mode(low_latency_mode)
+            .low_latency_boost(low_latency_boost)
+            .minimum_interval_us(minimum_interval_us);
+
+        unsafe {
+            self.device
+                .set_latency_sleep_mode_nv(self.swapchain, &sleep_mode_info)
+        }?;
+        Ok(())
+    }
+
+    /// Ask the driver when to start the next frame: `signal_semaphore` (a timeline semaphore) is
+    /// signaled with `value` when the application should sample input and start simulating.
+    pub fn latency_sleep(&self, signal_semaphore: vk::Semaphore, value: u64) -> crate::Result<()> {
+        self.require_low_latency()?;
+        let sleep_info = vk::LatencySleepInfoNV::builder()
+            .signal_semaphore(signal_semaphore)
+            .value(value);
+
+        unsafe { self.device.latency_sleep_nv(self.swapchain, &sleep_info) }?;
+        Ok(())
+    }
+
+    /// Mark a stage of the frame tagged with `present_id`, see
+    /// [`Swapchain::present_with_id`].
+    pub fn set_latency_marker(
+        &self,
+        present_id: u64,
+        marker: vk::LatencyMarkerNV,
+    ) -> crate::Result<()> {
+        self.require_low_latency()?;
+        let marker_info = vk::SetLatencyMarkerInfoNV::builder()
+            .present_id(present_id)
+            .marker(marker);
+
+        unsafe {
+            self.device
+                .set_latency_marker_nv(self.swapchain, &marker_info)
+        };
+        Ok(())
+    }
+
+    /// Timestamps of the markers of the most recent frames.
+    pub fn latency_timings(&self) -> crate::Result<Vec<vk::LatencyTimingsFrameReportNV>> {
+        self.require_low_latency()?;
+        let mut marker_info = vk::GetLatencyMarkerInfoNV::default();
+        unsafe {
+            self.device
+                .get_latency_timings_nv(self.swapchain, &mut marker_info)
+        };
+
+        let mut timings =
+            vec![vk::LatencyTimingsFrameReportNV::default(); marker_info.timing_count as usize];
+        marker_info.timings = timings.as_mut_ptr();
+        unsafe {
+            self.device
+                .get_latency_timings_nv(self.swapchain, &mut marker_info)
+        };
+        timings.truncate(marker_info.timing_count as usize);
+
+        Ok(timings)
+    }
+
+    /// Swapchain images paired with their views.
+    ///
+    /// Reuses the cached image views when present, otherwise creates them like
+    /// `get_image_views`. The entries stay valid until `destroy_image_views` or until the
+    /// swapchain is recreated, after which `images` must be called on the new swapchain.
+    pub fn images(&self) -> crate::Result<Vec<SwapchainImage>> {
+        let images = self.get_images()?;
+
+        let cached = self.image_views.lock().unwrap().clone();
+        let views = if cached.len() == images.len() {
+            cached
+        } else {
+            self.destroy_image_views()?;
+            self.get_image_views()?
+        };
+
+        Ok(images
+            .into_iter()
+            .zip(views)
+            .enumerate()
+            .map(|(index, (image, view))| SwapchainImage {
+                index: index as u32,
+                image,
+                view,
+                format: self.image_format,
+            })
+            .collect())
+    }
+
+    /// Copy swapchain image `image_index` to the host as tightly packed RGBA8, e.g. for
+    /// golden-image tests.
+    ///
+    /// The image must be in `PRESENT_SRC_KHR`, rendered by work that finished or was submitted
+    /// earlier to the `queue` queue, and is left in that layout so it can still be presented.
+    /// Needs `TRANSFER_SRC` usage (see [`SwapchainBuilder::add_image_usage_flags`]) and an
+    /// 8-bit RGBA or BGRA format. Blocks until the copy finished; sRGB values stay encoded.
+    /// Fails with `ConfigError::ImageIndexOutOfBounds` when `image_index` is not an image of the
+    /// swapchain.
+    pub fn read_pixels(&self, image_index: u32, queue: QueueType) -> crate::Result<Vec<u8>> {
+        if !self
+            .image_usage_flags
+            .contains(vk::ImageUsageFlags::TRANSFER_SRC)
+        {
+            return Err(crate::ConfigError::ConflictingOptions(
+                "read_pixels needs swapchain images with TRANSFER_SRC usage".to_string(),
+            )
+            .into());
+        }
+        let images = self.get_images()?;
+        let image = images.get(image_index as usize).copied().ok_or(
+            crate::ConfigError::ImageIndexOutOfBounds {
+                index: image_index,
+                count: images.len() as u32,
+            },
+        )?;
+        crate::readback::read_image_rgba8(
+            &self.device,
+            image,
+            self.image_format,
+            self.extent,
+            vk::ImageLayout::PRESENT_SRC_KHR,
+            queue,
+        )
+    }
+
+    /// [`Device::creation_summary`] with the swapchain configuration filled in.
+    pub fn creation_summary(&self) -> crate::CreationSummary {
+        crate::CreationSummary {
+            swapchain: Some(crate::SwapchainSummary {
+                format: self.image_format,
+                color_space: self.color_space,
+                present_mode: self.present_mode,
+                image_count: self.image_count,
+                extent: self.extent,
+            }),
+            ..self.device.creation_summary()
+        }
+    }
+
     /// Destroy the swapchain handle. Image views should be destroyed separately
     /// (e.g. via `Swapchain::destroy_image_views`) before destroying the swapchain.
     pub fn destroy(&self) {
+        self.device
+            .live_objects()
+            .untrack(vk::ObjectType::SWAPCHAIN_KHR, self.swapchain.as_raw());
         unsafe {
             self.device
                 .destroy_swapchain_khr(self.swapchain, self.allocation_callbacks.as_ref())
@@ -573,3 +1869,291 @@ impl AsRef<SwapchainKHR> for Swapchain {
         &self.swapchain
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn explain_incompatibility() {
+        let support = SurfaceSupport {
+            capabilities: vk::SurfaceCapabilitiesKHR {
+                min_image_count: 2,
+                max_image_count: 3,
+                max_image_array_layers: 1,
+                supported_usage_flags: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+                supported_composite_alpha: vk::CompositeAlphaFlagsKHR::OPAQUE,
+                ..Default::default()
+            },
+            formats: vec![vk::SurfaceFormatKHR {
+                format: vk::Format::B8G8R8A8_SRGB,
+                color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+            }],
+            present_modes: vec![vk::PresentModeKHR::FIFO],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            support.explain_incompatibility(&SwapchainConfig::default()),
+            None
+        );
+
+        let request = SwapchainConfig {
+            image_usage_flags: vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::STORAGE,
+            required_min_image_count: 4,
+            ..Default::default()
+        };
+        let explanation = support.explain_incompatibility(&request).unwrap();
+        assert!(explanation.contains("STORAGE"));
+        assert!(explanation.contains("above the surface maximum of 3"));
+    }
+
+    #[test]
+    fn valid_image_counts_cover_surface_limits() {
+        assert_eq!(valid_image_counts(0, 3, 2, 8), 2..=8);
+        assert_eq!(valid_image_counts(3, 3, 2, 8), 3..=8);
+        assert_eq!(
+            valid_image_counts(0, 3, 2, 0),
+            2..=MAX_ENUMERATED_IMAGE_COUNT
+        );
+        assert_eq!(valid_image_counts(0, 6, 2, 0), 2..=6);
+        assert_eq!(
+            valid_image_counts(0, 1, 0, 0),
+            1..=MAX_ENUMERATED_IMAGE_COUNT
+        );
+        assert!(valid_image_counts(5, 5, 2, 4).is_empty());
+    }
+
+    #[test]
+    fn old_swapchain_is_taken_once() {
+        let old_swapchain = OldSwapchain::default();
+        assert!(!old_swapchain.take(old_swapchain.get()));
+
+        old_swapchain.set(42);
+        assert_eq!(old_swapchain.clone().get(), 0);
+
+        let handle = old_swapchain.get();
+        assert!(old_swapchain.take(handle));
+        assert!(!old_swapchain.take(handle));
+        assert_eq!(old_swapchain.get(), 0);
+    }
+
+    #[test]
+    fn explain_plan_candidate() {
+        let support = SurfaceSupport {
+            capabilities: vk::SurfaceCapabilitiesKHR {
+                min_image_count: 2,
+                max_image_count: 3,
+                max_image_array_layers: 1,
+                supported_usage_flags: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+                supported_transforms: vk::SurfaceTransformFlagsKHR::IDENTITY,
+                supported_composite_alpha: vk::CompositeAlphaFlagsKHR::OPAQUE,
+                ..Default::default()
+            },
+            formats: vec![vk::SurfaceFormatKHR {
+                format: vk::Format::B8G8R8A8_SRGB,
+                color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+            }],
+            present_modes: vec![vk::PresentModeKHR::FIFO],
+            ..Default::default()
+        };
+        let mut plan = SwapchainPlan {
+            candidate: SwapchainCandidate {
+                format: support.formats[0],
+                present_mode: vk::PresentModeKHR::FIFO,
+                image_count: 3,
+            },
+            extent: vk::Extent2D {
+                width: 256,
+                height: 256,
+            },
+            image_array_layers: 1,
+            image_usage: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            pre_transform: vk::SurfaceTransformFlagsKHR::IDENTITY,
+            composite_alpha: vk::CompositeAlphaFlagsKHR::OPAQUE,
+            image_sharing: ResourceSharing::from_families([0]),
+        };
+        assert_eq!(support.explain_incompatibility(&plan.to_request()), None);
+
+        plan.candidate.present_mode = vk::PresentModeKHR::MAILBOX;
+        let explanation = support.explain_incompatibility(&plan.to_request()).unwrap();
+        assert!(explanation.contains("MAILBOX"));
+    }
+
+    #[test]
+    fn composite_alpha_defaults_to_first_supported() {
+        let android =
+            vk::CompositeAlphaFlagsKHR::INHERIT | vk::CompositeAlphaFlagsKHR::PRE_MULTIPLIED;
+        assert_eq!(
+            find_composite_alpha(vk::CompositeAlphaFlagsKHR::default(), android).unwrap(),
+            vk::CompositeAlphaFlagsKHR::PRE_MULTIPLIED
+        );
+        assert_eq!(
+            find_composite_alpha(
+                vk::CompositeAlphaFlagsKHR::default(),
+                android | vk::CompositeAlphaFlagsKHR::OPAQUE
+            )
+            .unwrap(),
+            vk::CompositeAlphaFlagsKHR::OPAQUE
+        );
+        assert_eq!(
+            find_composite_alpha(vk::CompositeAlphaFlagsKHR::INHERIT, android).unwrap(),
+            vk::CompositeAlphaFlagsKHR::INHERIT
+        );
+        assert!(find_composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE, android).is_err());
+    }
+
+    fn capabilities() -> impl Strategy<Value = vk::SurfaceCapabilitiesKHR> {
+        let extent = || (0u32..8192, 0u32..8192);
+        (
+            0u32..8,
+            prop_oneof![Just(0u32), 0u32..16],
+            extent(),
+            extent(),
+            prop_oneof![Just(None), extent().prop_map(Some)],
+        )
+            .prop_map(|(min_count, max_count, a, b, current)| {
+                let max_image_count = if max_count == 0 {
+                    0
+                } else {
+                    max_count.max(min_count)
+                };
+                let current_extent = match current {
+                    Some((width, height)) => vk::Extent2D { width, height },
+                    None => vk::Extent2D {
+                        width: u32::MAX,
+                        height: u32::MAX,
+                    },
+                };
+
+                vk::SurfaceCapabilitiesKHR {
+                    min_image_count: min_count,
+                    max_image_count,
+                    current_extent,
+                    min_image_extent: vk::Extent2D {
+                        width: a.0.min(b.0),
+                        height: a.1.min(b.1),
+                    },
+                    max_image_extent: vk::Extent2D {
+                        width: a.0.max(b.0),
+                        height: a.1.max(b.1),
+                    },
+                    max_image_array_layers: 1,
+                    ..Default::default()
+                }
+            })
+    }
+
+    const FORMATS: [vk::Format; 4] = [
+        vk::Format::B8G8R8A8_SRGB,
+        vk::Format::R8G8B8A8_SRGB,
+        vk::Format::B8G8R8A8_UNORM,
+        vk::Format::A2B10G10R10_UNORM_PACK32,
+    ];
+    const PRESENT_MODES: [vk::PresentModeKHR; 4] = [
+        vk::PresentModeKHR::IMMEDIATE,
+        vk::PresentModeKHR::MAILBOX,
+        vk::PresentModeKHR::FIFO,
+        vk::PresentModeKHR::FIFO_RELAXED,
+    ];
+
+    proptest! {
+        #[test]
+        fn image_count_within_surface_limits(
+            capabilities in capabilities(),
+            min_image_count in 0u32..8,
+            required_min_image_count in 0u32..8,
+        ) {
+            match find_image_count(min_image_count, required_min_image_count, &capabilities) {
+                Ok(count) => {
+                    prop_assert!(count >= 1);
+                    prop_assert!(count >= capabilities.min_image_count);
+                    if capabilities.max_image_count > 0 {
+                        prop_assert!(count <= capabilities.max_image_count);
+                    }
+                }
+                Err(_) => {
+                    prop_assert!(required_min_image_count >= 1);
+                    prop_assert!(required_min_image_count < capabilities.min_image_count);
+                }
+            }
+        }
+
+        #[test]
+        fn extent_within_surface_limits(
+            capabilities in capabilities(),
+            width in any::<u32>(),
+            height in any::<u32>(),
+            auto_extent in any::<bool>(),
+        ) {
+            let extent = find_extent(vk::Extent2D { width, height }, auto_extent, &capabilities);
+            if auto_extent && capabilities.current_extent.width != u32::MAX {
+                prop_assert_eq!(extent, capabilities.current_extent);
+            } else {
+                let (min, max) = (capabilities.min_image_extent, capabilities.max_image_extent);
+                prop_assert!((min.width..=max.width).contains(&extent.width));
+                prop_assert!((min.height..=max.height).contains(&extent.height));
+            }
+        }
+
+        #[test]
+        fn format_is_available(
+            available in proptest::sample::subsequence(FORMATS.to_vec(), 0..=FORMATS.len()),
+        ) {
+            let available = available
+                .into_iter()
+                .map(|format| vk::SurfaceFormatKHR {
+                    format,
+                    color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+                })
+                .collect::<Vec<_>>();
+
+            match find_best_surface_format(&available, &mut default_formats()) {
+                Ok(format) => prop_assert!(available.contains(&format)),
+                Err(_) => prop_assert!(available.is_empty()),
+            }
+        }
+
+        #[test]
+        fn present_mode_is_available_or_fifo(
+            available in proptest::sample::subsequence(PRESENT_MODES.to_vec(), 0..=PRESENT_MODES.len()),
+        ) {
+            let present_mode = find_present_mode(&available, &mut default_present_modes());
+            prop_assert!(
+                available.contains(&present_mode) || present_mode == vk::PresentModeKHR::FIFO
+            );
+        }
+
+        #[test]
+        fn concurrent_sharing_has_distinct_families(
+            graphics in 0u32..4,
+            present in 0u32..4,
+            override_indices in proptest::option::of(proptest::collection::vec(0u32..6, 0..4)),
+        ) {
+            let family_count = 4;
+            let sharing = override_indices.as_ref().map(|indices| ResourceSharing {
+                sharing_mode: vk::SharingMode::CONCURRENT,
+                queue_family_indices: indices.clone(),
+            });
+
+            match find_image_sharing(sharing.as_ref(), graphics, present, family_count) {
+                Ok(sharing) => {
+                    let indices = sharing.create_info_indices();
+                    if sharing.sharing_mode == vk::SharingMode::CONCURRENT {
+                        prop_assert!(indices.len() >= 2);
+                        for (i, index) in indices.iter().enumerate() {
+                            prop_assert!(*index < family_count);
+                            prop_assert!(!indices[..i].contains(index));
+                        }
+                    } else {
+                        prop_assert!(indices.is_empty());
+                    }
+                }
+                Err(_) => prop_assert!(
+                    override_indices.is_some_and(|indices| indices.iter().any(|&i| i >= family_count))
+                ),
+            }
+        }
+    }
+}
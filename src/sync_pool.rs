@@ -0,0 +1,116 @@
+use crate::{Device, Resettable};
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia::vk::DeviceV1_0;
+
+/// Recycles binary semaphores and fences between frames.
+///
+/// Keep one pool per frame in flight, created with [`Device::create_sync_pool`]. [`SyncPool::semaphore`] and [`SyncPool::fence`] hand out
+/// free objects, creating new ones only when the pool is empty, and [`SyncPool::reset`] (also
+/// available through [`Resettable`], e.g. in a [`crate::FrameContext`]) takes everything back
+/// once the frame's work has finished on the GPU. Fences are handed out unsignaled.
+///
+/// Objects are created like with [`Device::create_semaphores`] and [`Device::create_fences`],
+/// named `name semaphore[0]`, `name fence[0]`, ..., and show up in the leak report until the
+/// pool is destroyed.
+#[derive(Debug)]
+pub struct SyncPool {
+    device: Arc<Device>,
+    name: String,
+    free_semaphores: Vec<vk::Semaphore>,
+    used_semaphores: Vec<vk::Semaphore>,
+    free_fences: Vec<vk::Fence>,
+    used_fences: Vec<vk::Fence>,
+}
+
+impl SyncPool {
+    /// An empty pool whose objects are named after `name`.
+    pub fn new(device: Arc<Device>, name: impl Into<String>) -> Self {
+        Self {
+            device,
+            name: name.into(),
+            free_semaphores: vec![],
+            used_semaphores: vec![],
+            free_fences: vec![],
+            used_fences: vec![],
+        }
+    }
+
+    /// A binary semaphore that is free until the next reset.
+    pub fn semaphore(&mut self) -> crate::Result<vk::Semaphore> {
+        let semaphore = match self.free_semaphores.pop() {
+            Some(semaphore) => semaphore,
+            None => {
+                let index = self.used_semaphores.len();
+                self.device.create_indexed_semaphores(
+                    &format!("{} semaphore", self.name),
+                    index..index + 1,
+                )?[0]
+            }
+        };
+        self.used_semaphores.push(semaphore);
+
+        Ok(semaphore)
+    }
+
+    /// An unsignaled fence that is free until the next reset.
+    pub fn fence(&mut self) -> crate::Result<vk::Fence> {
+        let fence = match self.free_fences.pop() {
+            Some(fence) => fence,
+            None => {
+                let index = self.used_fences.len();
+                self.device.create_indexed_fences(
+                    &format!("{} fence", self.name),
+                    index..index + 1,
+                    false,
+                )?[0]
+            }
+        };
+        self.used_fences.push(fence);
+
+        Ok(fence)
+    }
+
+    /// Number of semaphores and fences handed out since the last reset.
+    pub fn in_use(&self) -> (usize, usize) {
+        (self.used_semaphores.len(), self.used_fences.len())
+    }
+
+    /// Take back every semaphore and fence handed out since the last reset. The work using them
+    /// must have finished, and every semaphore that was signaled must have been waited on.
+    pub fn reset(&mut self) -> crate::Result<()> {
+        if !self.used_fences.is_empty() {
+            unsafe { self.device.reset_fences(&self.used_fences) }?;
+        }
+        self.free_fences.append(&mut self.used_fences);
+        self.free_semaphores.append(&mut self.used_semaphores);
+
+        Ok(())
+    }
+
+    /// Destroy every semaphore and fence of the pool, whether handed out or not. The pool is
+    /// empty afterwards.
+    pub fn destroy(&mut self) {
+        self.device.destroy_semaphores(&self.free_semaphores);
+        self.device.destroy_semaphores(&self.used_semaphores);
+        self.device.destroy_fences(&self.free_fences);
+        self.device.destroy_fences(&self.used_fences);
+        self.free_semaphores.clear();
+        self.used_semaphores.clear();
+        self.free_fences.clear();
+        self.used_fences.clear();
+    }
+}
+
+impl Resettable for SyncPool {
+    fn reset(&mut self) -> crate::Result<()> {
+        SyncPool::reset(self)
+    }
+}
+
+impl Device {
+    /// An empty [`SyncPool`] on this device whose objects are named after `name`.
+    pub fn create_sync_pool(self: &Arc<Self>, name: impl Into<String>) -> SyncPool {
+        SyncPool::new(self.clone(), name)
+    }
+}
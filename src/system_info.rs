@@ -1,18 +1,55 @@
 use std::fmt::{Debug, Formatter};
-use vulkanalia::loader::{LIBRARY, LibloadingLoader};
+use std::sync::OnceLock;
+use vulkanalia::loader::LIBRARY;
 use vulkanalia::vk::{EntryV1_0, EntryV1_1};
 use vulkanalia::{Entry, vk};
 
 pub const VALIDATION_LAYER_NAME: vk::ExtensionName =
     vk::ExtensionName::from_bytes(b"VK_LAYER_KHRONOS_validation");
+pub const API_DUMP_LAYER_NAME: vk::ExtensionName =
+    vk::ExtensionName::from_bytes(b"VK_LAYER_LUNARG_api_dump");
+pub const CAPTURE_LAYER_NAME: vk::ExtensionName =
+    vk::ExtensionName::from_bytes(b"VK_LAYER_LUNARG_gfxreconstruct");
 pub const DEBUG_UTILS_EXT_NAME: vk::ExtensionName = vk::EXT_DEBUG_UTILS_EXTENSION.name;
 
+/// The Vulkan loader library, opened once per process and kept loaded.
+static VULKAN_LIBRARY: OnceLock<libloading::Library> = OnceLock::new();
+
+fn load_entry() -> crate::Result<Entry> {
+    let library = match VULKAN_LIBRARY.get() {
+        Some(library) => library,
+        None => {
+            let library = unsafe { libloading::Library::new(LIBRARY) }?;
+            VULKAN_LIBRARY.get_or_init(|| library)
+        }
+    };
+
+    let static_commands = unsafe {
+        vk::StaticCommands {
+            get_instance_proc_addr: *library.get(b"vkGetInstanceProcAddr\0")?,
+            get_device_proc_addr: *library.get(b"vkGetDeviceProcAddr\0")?,
+        }
+    };
+
+    Ok(unsafe { Entry::from_commands(&static_commands) })
+}
+
+/// Layers, extensions and API version of the Vulkan installation.
+///
+/// Gathered by [`crate::InstanceBuilder::build`]; pass one to
+/// [`crate::InstanceBuilder::system_info`] to skip the enumeration when creating several
+/// instances.
+#[derive(Clone)]
 pub struct SystemInfo {
     pub available_layers: Vec<vk::LayerProperties>,
     pub available_extensions: Vec<vk::ExtensionProperties>,
     pub validation_layers_available: bool,
     pub debug_utils_available: bool,
     pub instance_api_version: u32,
+    /// Extensions exposed by the implementation and implicit layers, without explicit layers.
+    pub(crate) implementation_extensions: Vec<vk::ExtensionName>,
+    /// Extensions exposed by each layer, as `(layer, extension)`.
+    pub(crate) layer_extensions: Vec<(vk::ExtensionName, vk::ExtensionName)>,
     pub(crate) entry: Entry,
 }
 
@@ -32,12 +69,14 @@ impl Debug for SystemInfo {
 }
 
 impl SystemInfo {
+    /// Enumerate the layers and extensions of the system.
+    ///
+    /// The Vulkan library is only loaded by the first call, later calls reuse it.
     #[cfg_attr(feature = "enable_tracing", tracing::instrument)]
     pub fn get_system_info() -> crate::Result<Self> {
         #[cfg(feature = "enable_tracing")]
         tracing::trace!("Loading entry...");
-        let loader = unsafe { LibloadingLoader::new(LIBRARY) }.unwrap();
-        let entry = unsafe { Entry::new(loader).unwrap() };
+        let entry = load_entry()?;
         #[cfg(feature = "enable_tracing")]
         tracing::trace!("Entry loaded.");
         let mut validation_layers_available = false;
@@ -54,6 +93,11 @@ impl SystemInfo {
 
         let mut available_extensions =
             unsafe { entry.enumerate_instance_extension_properties(None) }?;
+        let implementation_extensions = available_extensions
+            .iter()
+            .map(|ext| ext.extension_name)
+            .collect();
+        let mut extensions_by_layer = vec![];
 
         for ext in &available_extensions {
             if ext.extension_name == DEBUG_UTILS_EXT_NAME {
@@ -67,6 +111,11 @@ impl SystemInfo {
             }?;
 
             available_extensions.extend_from_slice(&layer_extensions);
+            extensions_by_layer.extend(
+                layer_extensions
+                    .iter()
+                    .map(|ext| (layer.layer_name, ext.extension_name)),
+            );
 
             for ext in &layer_extensions {
                 if ext.extension_name == DEBUG_UTILS_EXT_NAME {
@@ -86,6 +135,8 @@ impl SystemInfo {
             debug_utils_available,
             validation_layers_available,
             instance_api_version,
+            implementation_extensions,
+            layer_extensions: extensions_by_layer,
             entry,
         })
     }
@@ -101,20 +152,89 @@ impl SystemInfo {
         Ok(false)
     }
 
-    /// Return true if every extension in `extensions` is available on the system.
+    /// Return the extensions in `extensions` that are not available on the system, so an empty
+    /// list means all of them are.
     pub fn are_extensions_available(
         &self,
-        extensions: &Vec<vk::ExtensionName>,
-    ) -> crate::Result<bool> {
-        let mut all_found = true;
+        extensions: &[vk::ExtensionName],
+    ) -> crate::Result<Vec<vk::ExtensionName>> {
+        let mut unavailable = vec![];
         for ext in extensions {
-            let found = self.is_extension_available(ext)?;
-            if !found {
-                all_found = false;
+            if !self.is_extension_available(ext)? {
+                unavailable.push(*ext);
+            }
+        }
+
+        Ok(unavailable)
+    }
+
+    /// Explicit layers that expose `extension`.
+    pub fn layers_providing(&self, extension: &vk::ExtensionName) -> Vec<vk::ExtensionName> {
+        self.layer_extensions
+            .iter()
+            .filter(|(_, ext)| ext == extension)
+            .map(|(layer, _)| *layer)
+            .collect()
+    }
+
+    /// Extensions of `extensions` that neither the implementation nor one of `enabled_layers`
+    /// exposes, with the layers that would provide them.
+    pub fn missing_extensions<'a>(
+        &self,
+        extensions: impl IntoIterator<Item = &'a vk::ExtensionName>,
+        enabled_layers: &[vk::ExtensionName],
+    ) -> Vec<crate::MissingExtension> {
+        extensions
+            .into_iter()
+            .filter(|extension| !self.implementation_extensions.contains(extension))
+            .filter_map(|extension| {
+                let provided_by = self.layers_providing(extension);
+                (!provided_by
+                    .iter()
+                    .any(|layer| enabled_layers.contains(layer)))
+                .then_some(crate::MissingExtension {
+                    name: *extension,
+                    provided_by,
+                })
+            })
+            .collect()
+    }
+
+    /// Add the layer that provides each extension of `extensions` that only a layer exposes to
+    /// `enabled_layers`, unless one of its providers is enabled already.
+    pub(crate) fn enable_providing_layers<'a>(
+        &self,
+        extensions: impl IntoIterator<Item = &'a vk::ExtensionName>,
+        enabled_layers: &mut Vec<vk::ExtensionName>,
+    ) {
+        for missing in self.missing_extensions(extensions, enabled_layers) {
+            if let Some(layer) = missing.provided_by.first() {
+                #[cfg(feature = "enable_tracing")]
+                tracing::debug!(
+                    "Enabling layer {} which provides {}",
+                    layer.to_string_lossy(),
+                    missing.name.to_string_lossy()
+                );
+                enabled_layers.push(*layer);
             }
         }
+    }
 
-        Ok(all_found)
+    /// Layers of `layers` that are not installed.
+    pub fn missing_layers<'a>(
+        &self,
+        layers: impl IntoIterator<Item = &'a vk::ExtensionName>,
+    ) -> Vec<vk::ExtensionName> {
+        layers
+            .into_iter()
+            .filter(|layer| {
+                !self
+                    .available_layers
+                    .iter()
+                    .any(|available| available.layer_name == **layer)
+            })
+            .copied()
+            .collect()
     }
 
     /// Return true if the given instance layer name is available on the system.
@@ -128,20 +248,20 @@ impl SystemInfo {
         Ok(false)
     }
 
-    /// Return true if every layer in `layers` is available on the system.
-    pub fn are_layers_available<'a, I: IntoIterator<Item = vk::ExtensionName>>(
+    /// Return the layers in `layers` that are not available on the system, so an empty list
+    /// means all of them are.
+    pub fn are_layers_available<I: IntoIterator<Item = vk::ExtensionName>>(
         &self,
         layers: I,
-    ) -> crate::Result<bool> {
-        let mut all_found = true;
-        for ext in layers {
-            let found = self.is_layer_available(ext)?;
-            if !found {
-                all_found = false;
+    ) -> crate::Result<Vec<vk::ExtensionName>> {
+        let mut unavailable = vec![];
+        for layer in layers {
+            if !self.is_layer_available(layer)? {
+                unavailable.push(layer);
             }
         }
 
-        Ok(all_found)
+        Ok(unavailable)
     }
 }
 
@@ -0,0 +1,241 @@
+use crate::system_info::{DEBUG_UTILS_EXT_NAME, SystemInfo, VALIDATION_LAYER_NAME};
+use std::ffi::{CStr, c_char};
+use std::path::{Path, PathBuf};
+use vulkanalia::{Entry, Version, vk};
+
+/// A Vulkan installation described in code, for testing instance configuration without a GPU
+/// or Vulkan loader.
+///
+/// Pass [`MockSystem::system_info`] to [`crate::InstanceBuilder::system_info`]: version, layer
+/// and extension checks, [`crate::InstanceBuilder::plan`] and the errors of
+/// [`crate::InstanceBuilder::build`] then behave deterministically. Creating the instance
+/// itself always fails with `InstanceError::FailedCreateInstance`; use a software driver (see
+/// [`lavapipe_manifest`]) for tests that need devices.
+///
+/// ``` no_run
+/// # fn main() -> vulkanalia_bootstrap::Result<()> {
+/// use vulkanalia::{Version, vk};
+/// use vulkanalia_bootstrap::InstanceBuilder;
+/// use vulkanalia_bootstrap::testing::MockSystem;
+///
+/// let system = MockSystem::new(Version::V1_3_0).extension(vk::EXT_DEBUG_UTILS_EXTENSION.name);
+/// let plan = InstanceBuilder::new(None)
+///     .system_info(system.system_info())
+///     .require_api_version(Version::V1_3_0)
+///     .plan()?;
+/// assert_eq!(plan.api_version, Version::V1_3_0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockSystem {
+    api_version: Version,
+    extensions: Vec<vk::ExtensionName>,
+    layers: Vec<(vk::ExtensionName, Vec<vk::ExtensionName>)>,
+}
+
+impl MockSystem {
+    /// A system with a loader supporting `api_version` and no layers or extensions.
+    pub fn new(api_version: Version) -> Self {
+        Self {
+            api_version,
+            extensions: vec![],
+            layers: vec![],
+        }
+    }
+
+    /// Add an instance extension exposed by the implementation.
+    pub fn extension(mut self, name: vk::ExtensionName) -> Self {
+        self.extensions.push(name);
+        self
+    }
+
+    /// Add an installed layer exposing `extensions`.
+    pub fn layer(mut self, name: vk::ExtensionName, extensions: &[vk::ExtensionName]) -> Self {
+        self.layers.push((name, extensions.to_vec()));
+        self
+    }
+
+    /// System info reporting the mocked layers and extensions.
+    pub fn system_info(&self) -> SystemInfo {
+        let extension_properties = |name: &vk::ExtensionName| vk::ExtensionProperties {
+            extension_name: *name,
+            spec_version: 1,
+        };
+
+        let mut available_extensions = self
+            .extensions
+            .iter()
+            .map(extension_properties)
+            .collect::<Vec<_>>();
+        let mut layer_extensions = vec![];
+        for (layer, extensions) in &self.layers {
+            available_extensions.extend(extensions.iter().map(extension_properties));
+            layer_extensions.extend(extensions.iter().map(|extension| (*layer, *extension)));
+        }
+
+        let all_extensions = || available_extensions.iter().map(|ext| ext.extension_name);
+        let debug_utils_available = all_extensions().any(|ext| ext == DEBUG_UTILS_EXT_NAME);
+
+        SystemInfo {
+            available_layers: self
+                .layers
+                .iter()
+                .map(|(name, _)| vk::LayerProperties {
+                    layer_name: *name,
+                    spec_version: self.api_version.into(),
+                    implementation_version: 1,
+                    ..Default::default()
+                })
+                .collect(),
+            validation_layers_available: self
+                .layers
+                .iter()
+                .any(|(name, _)| *name == VALIDATION_LAYER_NAME),
+            debug_utils_available,
+            instance_api_version: self.api_version.into(),
+            implementation_extensions: self.extensions.clone(),
+            layer_extensions,
+            available_extensions,
+            entry: mock_entry(),
+        }
+    }
+}
+
+fn mock_entry() -> Entry {
+    let commands = vk::StaticCommands {
+        get_instance_proc_addr: mock_get_instance_proc_addr,
+        get_device_proc_addr: mock_get_device_proc_addr,
+    };
+    unsafe { Entry::from_commands(&commands) }
+}
+
+unsafe extern "system" fn mock_get_instance_proc_addr(
+    _instance: vk::Instance,
+    name: *const c_char,
+) -> vk::PFN_vkVoidFunction {
+    let name = unsafe { CStr::from_ptr(name) };
+    if name == c"vkCreateInstance" {
+        let create_instance: vk::PFN_vkCreateInstance = mock_create_instance;
+        Some(unsafe {
+            std::mem::transmute::<vk::PFN_vkCreateInstance, unsafe extern "system" fn()>(
+                create_instance,
+            )
+        })
+    } else {
+        None
+    }
+}
+
+unsafe extern "system" fn mock_get_device_proc_addr(
+    _device: vk::Device,
+    _name: *const c_char,
+) -> vk::PFN_vkVoidFunction {
+    None
+}
+
+unsafe extern "system" fn mock_create_instance(
+    _create_info: *const vk::InstanceCreateInfo,
+    _allocator: *const vk::AllocationCallbacks,
+    _instance: *mut vk::Instance,
+) -> vk::Result {
+    vk::Result::ERROR_INCOMPATIBLE_DRIVER
+}
+
+const DRIVER_MANIFEST_DIRS: &[&str] = &[
+    "/usr/share/vulkan/icd.d",
+    "/usr/local/share/vulkan/icd.d",
+    "/etc/vulkan/icd.d",
+];
+
+fn find_driver_manifest(dirs: &[PathBuf], file_prefix: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(file_prefix) && name.ends_with(".json"))
+        })
+}
+
+/// Driver manifest of lavapipe, Mesa's software rasterizer, in the standard install locations.
+/// Its device is only selected with
+/// [`crate::PhysicalDeviceSelector::allow_software_rasterizer`].
+pub fn lavapipe_manifest() -> Option<PathBuf> {
+    let dirs = DRIVER_MANIFEST_DIRS
+        .iter()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    find_driver_manifest(&dirs, "lvp_icd")
+}
+
+/// Driver manifest of the Khronos Mock ICD, from the Vulkan SDK (`VULKAN_SDK`) or the standard
+/// install locations. The Mock ICD reports one device supporting everything and does no work.
+pub fn mock_icd_manifest() -> Option<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(sdk) = std::env::var_os("VULKAN_SDK") {
+        let sdk = PathBuf::from(sdk);
+        dirs.push(sdk.join("share/vulkan/icd.d"));
+        dirs.push(sdk.join("etc/vulkan/icd.d"));
+        dirs.push(sdk.join("bin"));
+    }
+    dirs.extend(DRIVER_MANIFEST_DIRS.iter().map(PathBuf::from));
+    find_driver_manifest(&dirs, "VkICD_mock_icd")
+}
+
+/// Make the Vulkan loader use only the driver described by `manifest`, e.g. from
+/// [`lavapipe_manifest`] or [`mock_icd_manifest`].
+///
+/// Must be called before the first instance or [`SystemInfo::get_system_info`]; the loader
+/// reads the driver list once.
+///
+/// # Safety
+///
+/// Sets environment variables, no other thread may read or write the environment at the same
+/// time (see [`std::env::set_var`]).
+pub unsafe fn use_driver_manifest(manifest: &Path) {
+    unsafe {
+        std::env::set_var("VK_DRIVER_FILES", manifest);
+        // Loaders older than 1.3.207 only know the deprecated name.
+        std::env::set_var("VK_ICD_FILENAMES", manifest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, InstanceBuilder, InstanceError};
+
+    #[test]
+    fn mocked_checks() {
+        let system = MockSystem::new(Version::V1_1_0).layer(
+            VALIDATION_LAYER_NAME,
+            &[vk::EXT_LAYER_SETTINGS_EXTENSION.name],
+        );
+        let builder = InstanceBuilder::new(None).system_info(system.system_info());
+
+        let too_new = builder
+            .clone()
+            .minimum_instance_version(Version::V1_3_0)
+            .plan();
+        assert!(matches!(
+            too_new,
+            Err(Error::Instance(InstanceError::VulkanVersion13Unavailable))
+        ));
+
+        let plan = builder
+            .clone()
+            .enable_extension(vk::EXT_LAYER_SETTINGS_EXTENSION.name)
+            .plan()
+            .unwrap();
+        assert_eq!(plan.layers, [VALIDATION_LAYER_NAME]);
+
+        assert!(matches!(
+            builder.build(),
+            Err(Error::Instance(InstanceError::FailedCreateInstance))
+        ));
+    }
+}
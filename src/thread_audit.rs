@@ -0,0 +1,127 @@
+#[cfg(feature = "thread_audit")]
+use std::collections::HashMap;
+#[cfg(feature = "thread_audit")]
+use std::sync::{Mutex, OnceLock};
+#[cfg(feature = "thread_audit")]
+use std::thread::{self, ThreadId};
+use vulkanalia::vk;
+
+/// Queues in use right now, with the thread using them.
+///
+/// Only checks anything with the `thread_audit` feature, otherwise every call is a no-op.
+#[derive(Debug, Default)]
+pub(crate) struct QueueAudit {
+    /// Thread using the queue and how often it entered, for nested accesses.
+    #[cfg(feature = "thread_audit")]
+    busy: Mutex<HashMap<usize, (ThreadId, usize)>>,
+}
+
+impl QueueAudit {
+    /// Mark `queue` as used by the current thread. Panics when another thread is using it.
+    #[cfg_attr(not(feature = "thread_audit"), allow(unused_variables))]
+    pub(crate) fn enter(&self, queue: vk::Queue) {
+        #[cfg(feature = "thread_audit")]
+        {
+            use vk::Handle;
+
+            let current = thread::current().id();
+            let conflict = {
+                let mut busy = self.busy.lock().unwrap_or_else(|e| e.into_inner());
+                let (thread, count) = busy.entry(queue.as_raw()).or_insert((current, 0));
+                if *thread == current {
+                    *count += 1;
+                    None
+                } else {
+                    Some(*thread)
+                }
+            };
+
+            if let Some(thread) = conflict {
+                panic!(
+                    "Queue {:#x} used on thread {current:?} while thread {thread:?} is using it. \
+                     Queues are externally synchronized, guard queues shared between threads \
+                     with a lock",
+                    queue.as_raw()
+                );
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "thread_audit"), allow(unused_variables))]
+    pub(crate) fn leave(&self, queue: vk::Queue) {
+        #[cfg(feature = "thread_audit")]
+        {
+            use vk::Handle;
+
+            let mut busy = self.busy.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((_, count)) = busy.get_mut(&queue.as_raw()) {
+                *count -= 1;
+                if *count == 0 {
+                    busy.remove(&queue.as_raw());
+                }
+            }
+        }
+    }
+}
+
+/// Binds an externally synchronized object (a command pool the crate created) to the first
+/// thread that uses it.
+///
+/// Only checks anything with the `thread_audit` feature, otherwise every call is a no-op.
+#[derive(Debug, Default)]
+pub(crate) struct ThreadOwner {
+    #[cfg(feature = "thread_audit")]
+    owner: OnceLock<ThreadId>,
+}
+
+impl ThreadOwner {
+    /// Panics when the object was already used on another thread.
+    #[cfg_attr(not(feature = "thread_audit"), allow(unused_variables))]
+    pub(crate) fn check(&self, object_type: vk::ObjectType, handle: u64) {
+        #[cfg(feature = "thread_audit")]
+        {
+            let current = thread::current().id();
+            let owner = *self.owner.get_or_init(|| current);
+            if owner != current {
+                panic!(
+                    "{object_type:?} {handle:#x} used on thread {current:?} but it belongs to \
+                     thread {owner:?}. Create one per thread instead of sharing it"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "thread_audit"))]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use vulkanalia::vk::Handle;
+
+    #[test]
+    fn concurrent_queue_use_panics() {
+        let audit = Arc::new(QueueAudit::default());
+        let queue = vk::Queue::from_raw(1);
+        audit.enter(queue);
+        audit.enter(queue);
+        audit.leave(queue);
+
+        let other = audit.clone();
+        assert!(thread::spawn(move || other.enter(queue)).join().is_err());
+
+        audit.leave(queue);
+        let other = audit.clone();
+        assert!(thread::spawn(move || other.enter(queue)).join().is_ok());
+    }
+
+    #[test]
+    fn owner_is_first_user() {
+        let owner = Arc::new(ThreadOwner::default());
+        let other = owner.clone();
+        thread::spawn(move || other.check(vk::ObjectType::COMMAND_POOL, 1))
+            .join()
+            .unwrap();
+
+        assert!(std::panic::catch_unwind(|| owner.check(vk::ObjectType::COMMAND_POOL, 1)).is_err());
+    }
+}
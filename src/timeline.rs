@@ -0,0 +1,83 @@
+use crate::Device;
+use std::sync::Arc;
+use std::time::Duration;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, DeviceV1_2, HasBuilder};
+
+/// A timeline semaphore with host-side signal, wait and query.
+///
+/// The device must be created with the Vulkan 1.2 `timeline_semaphore` feature. Pass
+/// [`TimelineSemaphore::handle`] with a value in `vk::TimelineSemaphoreSubmitInfo` to wait on or
+/// signal it from the GPU.
+#[derive(Debug)]
+pub struct TimelineSemaphore {
+    device: Arc<Device>,
+    semaphore: vk::Semaphore,
+}
+
+impl TimelineSemaphore {
+    /// Create a timeline semaphore starting at `initial_value`, named `name` for debug tools.
+    pub fn new(device: Arc<Device>, name: &str, initial_value: u64) -> crate::Result<Self> {
+        let mut type_info = vk::SemaphoreTypeCreateInfo::builder()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(initial_value);
+        let semaphore_info = vk::SemaphoreCreateInfo::builder().push_next(&mut type_info);
+        let semaphore =
+            unsafe { device.create_semaphore(&semaphore_info, device.allocation_callbacks()) }
+                .map_err(|code| crate::CreateError {
+                    kind: vk::ObjectType::SEMAPHORE,
+                    name: name.to_string(),
+                    details: format!("timeline, initial value {initial_value}"),
+                    code,
+                })?;
+
+        let timeline = Self { device, semaphore };
+        if let Err(e) = timeline.device.set_object_name(semaphore, name) {
+            timeline.destroy();
+            return Err(e);
+        }
+
+        Ok(timeline)
+    }
+
+    pub fn handle(&self) -> vk::Semaphore {
+        self.semaphore
+    }
+
+    /// The value the semaphore has reached.
+    pub fn value(&self) -> crate::Result<u64> {
+        Ok(unsafe { self.device.get_semaphore_counter_value(self.semaphore) }?)
+    }
+
+    /// Set the semaphore to `value` from the host. It must be larger than the current value.
+    pub fn signal(&self, value: u64) -> crate::Result<()> {
+        let signal_info = vk::SemaphoreSignalInfo::builder()
+            .semaphore(self.semaphore)
+            .value(value);
+        Ok(unsafe { self.device.signal_semaphore(&signal_info) }?)
+    }
+
+    /// Block until the semaphore reaches `value`. Returns false when `timeout` passed first,
+    /// `None` waits forever.
+    pub fn wait(&self, value: u64, timeout: Option<Duration>) -> crate::Result<bool> {
+        let semaphores = [self.semaphore];
+        let values = [value];
+        let wait_info = vk::SemaphoreWaitInfo::builder()
+            .semaphores(&semaphores)
+            .values(&values);
+        let timeout = timeout.map_or(u64::MAX, |timeout| {
+            timeout.as_nanos().try_into().unwrap_or(u64::MAX)
+        });
+
+        let result = unsafe { self.device.wait_semaphores(&wait_info, timeout) }?;
+        Ok(result != vk::SuccessCode::TIMEOUT)
+    }
+
+    /// Destroy the semaphore. The GPU must no longer use it.
+    pub fn destroy(&self) {
+        unsafe {
+            self.device
+                .destroy_semaphore(self.semaphore, self.device.allocation_callbacks())
+        };
+    }
+}
@@ -1,3 +1,4 @@
+use crate::debug_output::{DebugSink, is_debug_printf};
 use std::borrow::Cow;
 use std::ffi;
 use vulkanalia::vk;
@@ -7,7 +8,7 @@ pub unsafe extern "system" fn vulkan_tracing_callback(
     message_severity: DebugUtilsMessageSeverityFlagsEXT,
     _message_type: vk::DebugUtilsMessageTypeFlagsEXT,
     p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
-    _user_data: *mut std::os::raw::c_void,
+    user_data: *mut std::os::raw::c_void,
 ) -> vk::Bool32 {
     unsafe {
         let callback_data = *p_callback_data;
@@ -25,20 +26,39 @@ pub unsafe extern "system" fn vulkan_tracing_callback(
             ffi::CStr::from_ptr(callback_data.message).to_string_lossy()
         };
 
+        let sink = (user_data as *const DebugSink).as_ref();
+        if is_debug_printf(&message_id_name) {
+            if !sink.is_some_and(|sink| sink.route_printf(&message)) {
+                tracing::info!(target: "vulkanalia_bootstrap::debug_printf", "{message}");
+            }
+            return vk::FALSE;
+        }
+        if sink.is_some_and(|sink| !sink.severity.contains(message_severity)) {
+            return vk::FALSE;
+        }
+
+        let note = match sink {
+            Some(sink) => match sink.repeat_count(message_id_number, &message) {
+                Some(count) => sink.repeat_note(count),
+                None => return vk::FALSE,
+            },
+            None => String::new(),
+        };
+
         match message_severity {
             DebugUtilsMessageSeverityFlagsEXT::VERBOSE => {
-                tracing::trace!("[{message_id_name} ({message_id_number})]: {message}");
+                tracing::trace!("[{message_id_name} ({message_id_number})]: {message}{note}");
             }
             DebugUtilsMessageSeverityFlagsEXT::INFO => {
-                tracing::debug!("[{message_id_name} ({message_id_number})]: {message}");
+                tracing::debug!("[{message_id_name} ({message_id_number})]: {message}{note}");
             }
             DebugUtilsMessageSeverityFlagsEXT::ERROR => {
-                tracing::error!("[{message_id_name} ({message_id_number})]: {message}");
+                tracing::error!("[{message_id_name} ({message_id_number})]: {message}{note}");
             }
             DebugUtilsMessageSeverityFlagsEXT::WARNING => {
-                tracing::warn!("[{message_id_name} ({message_id_number})]: {message}");
+                tracing::warn!("[{message_id_name} ({message_id_number})]: {message}{note}");
             }
-            _ => tracing::debug!("[{message_id_name} ({message_id_number})]: {message}"),
+            _ => tracing::debug!("[{message_id_name} ({message_id_number})]: {message}{note}"),
         }
 
         vk::FALSE
@@ -0,0 +1,329 @@
+use crate::device::QueueType;
+use crate::thread_audit::ThreadOwner;
+use crate::{Device, StagingBelt};
+use std::sync::Arc;
+use vulkanalia::vk;
+use vulkanalia::vk::{DeviceV1_0, DeviceV1_2, Handle, HasBuilder};
+
+#[derive(Debug)]
+enum UploadCopy {
+    Buffer {
+        staging: vk::Buffer,
+        dst: vk::Buffer,
+        region: vk::BufferCopy,
+    },
+    Image {
+        staging: vk::Buffer,
+        dst: vk::Image,
+        region: vk::BufferImageCopy,
+    },
+}
+
+#[derive(Debug)]
+struct InFlightBatch {
+    value: u64,
+    command_buffer: vk::CommandBuffer,
+}
+
+/// Batches buffer and image uploads and submits them on the transfer queue.
+///
+/// Data is staged in a [`StagingBelt`] whose chunks are reused once the batch reading them
+/// completed.
+/// Each call to [`UploadScheduler::submit`] signals the scheduler's timeline semaphore with the
+/// value returned by the upload calls, so other queues can wait on [`UploadScheduler::semaphore`]
+/// and the CPU can track progress with [`UploadScheduler::poll_completed`].
+///
+/// The device must be created with the Vulkan 1.2 `timeline_semaphore` feature. Uploads use the
+/// transfer queue family when the device has one, and the graphics family otherwise; resources
+/// used on another family need a queue family ownership transfer or `CONCURRENT` sharing
+/// (see [`Device::resource_sharing`]).
+#[derive(Debug)]
+pub struct UploadScheduler {
+    device: Arc<Device>,
+    queue: vk::Queue,
+    queue_family_index: u32,
+    command_pool: vk::CommandPool,
+    pool_owner: ThreadOwner,
+    semaphore: vk::Semaphore,
+    belt: StagingBelt,
+    next_value: u64,
+    pending_copies: Vec<UploadCopy>,
+    in_flight: Vec<InFlightBatch>,
+}
+
+impl UploadScheduler {
+    pub fn new(device: Arc<Device>) -> crate::Result<Self> {
+        let (queue_family_index, queue) = device
+            .get_queue(QueueType::Transfer)
+            .or_else(|_| device.get_queue(QueueType::Graphics))?;
+
+        let command_pool_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+            .queue_family_index(queue_family_index as _);
+        let command_pool = unsafe {
+            device.create_command_pool(&command_pool_info, device.allocation_callbacks())
+        }?;
+
+        let mut type_info = vk::SemaphoreTypeCreateInfo::builder()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(0);
+        let semaphore_info = vk::SemaphoreCreateInfo::builder().push_next(&mut type_info);
+        let semaphore = match unsafe {
+            device.create_semaphore(&semaphore_info, device.allocation_callbacks())
+        } {
+            Ok(semaphore) => semaphore,
+            Err(e) => {
+                unsafe { device.destroy_command_pool(command_pool, device.allocation_callbacks()) };
+                return Err(e.into());
+            }
+        };
+
+        Ok(Self {
+            belt: StagingBelt::new(device.clone()),
+            device,
+            queue,
+            queue_family_index: queue_family_index as _,
+            command_pool,
+            pool_owner: ThreadOwner::default(),
+            semaphore,
+            next_value: 1,
+            pending_copies: vec![],
+            in_flight: vec![],
+        })
+    }
+
+    /// Priority of the staging allocations, see [`Device::allocate_memory_with_priority`].
+    /// Defaults to 0.5, the priority Vulkan gives allocations without one.
+    pub fn memory_priority(mut self, priority: f32) -> Self {
+        self.belt = self.belt.memory_priority(priority);
+        self
+    }
+
+    /// Size of the staging belt chunks, see [`StagingBelt::chunk_size`].
+    pub fn staging_chunk_size(mut self, size: vk::DeviceSize) -> Self {
+        self.belt = self.belt.chunk_size(size);
+        self
+    }
+
+    /// Timeline semaphore signaled with the value of each submitted batch.
+    pub fn semaphore(&self) -> vk::Semaphore {
+        self.semaphore
+    }
+
+    /// Queue family the uploads are submitted on.
+    pub fn queue_family_index(&self) -> u32 {
+        self.queue_family_index
+    }
+
+    /// Queue a copy of `data` into `dst` at `offset`.
+    ///
+    /// Returns the timeline value that signals completion once the batch is submitted.
+    pub fn upload_buffer(
+        &mut self,
+        data: &[u8],
+        dst: vk::Buffer,
+        offset: vk::DeviceSize,
+    ) -> crate::Result<u64> {
+        let (staging, staging_offset) = self.belt.write(data)?;
+        self.pending_copies.push(UploadCopy::Buffer {
+            staging,
+            dst,
+            region: vk::BufferCopy {
+                src_offset: staging_offset,
+                dst_offset: offset,
+                size: data.len() as u64,
+            },
+        });
+
+        Ok(self.next_value)
+    }
+
+    /// Queue a copy of tightly packed texel `data` into `subresource` of `dst`.
+    ///
+    /// `dst` must be in `vk::ImageLayout::TRANSFER_DST_OPTIMAL` when the batch executes.
+    /// Returns the timeline value that signals completion once the batch is submitted.
+    pub fn upload_image(
+        &mut self,
+        data: &[u8],
+        dst: vk::Image,
+        subresource: vk::ImageSubresourceLayers,
+        offset: vk::Offset3D,
+        extent: vk::Extent3D,
+    ) -> crate::Result<u64> {
+        let (staging, staging_offset) = self.belt.write(data)?;
+        self.pending_copies.push(UploadCopy::Image {
+            staging,
+            dst,
+            region: vk::BufferImageCopy {
+                buffer_offset: staging_offset,
+                buffer_row_length: 0,
+                buffer_image_height: 0,
+                image_subresource: subresource,
+                image_offset: offset,
+                image_extent: extent,
+            },
+        });
+
+        Ok(self.next_value)
+    }
+
+    /// Record and submit every queued upload as one batch.
+    ///
+    /// Returns the timeline value the batch signals, or the last submitted value when nothing
+    /// was queued. On failure the queued uploads are dropped and have to be queued again; the
+    /// values returned for them are reused by the next batch.
+    #[cfg_attr(feature = "enable_tracing", tracing::instrument(skip(self)))]
+    pub fn submit(&mut self) -> crate::Result<u64> {
+        if self.pending_copies.is_empty() {
+            return Ok(self.next_value - 1);
+        }
+
+        self.pool_owner
+            .check(vk::ObjectType::COMMAND_POOL, self.command_pool.as_raw());
+
+        let device = &self.device;
+        let command_buffer_info = vk::CommandBufferAllocateInfo::builder()
+            .command_pool(self.command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(1);
+        let command_buffer = match unsafe { device.allocate_command_buffers(&command_buffer_info) }
+        {
+            Ok(command_buffers) => command_buffers[0],
+            Err(e) => {
+                self.drop_pending();
+                return Err(e.into());
+            }
+        };
+
+        let value = self.next_value;
+        if let Err(e) = self.record_and_submit(command_buffer, value) {
+            unsafe { device.free_command_buffers(self.command_pool, &[command_buffer]) };
+            self.drop_pending();
+            return Err(e);
+        }
+
+        self.pending_copies.clear();
+        self.belt.finish(value);
+        self.in_flight.push(InFlightBatch {
+            value,
+            command_buffer,
+        });
+        self.next_value += 1;
+
+        Ok(value)
+    }
+
+    /// Forget the queued copies of a batch that was never submitted and reuse their staging
+    /// memory.
+    fn drop_pending(&mut self) {
+        self.pending_copies.clear();
+        self.belt.discard();
+    }
+
+    /// Record the queued copies into `command_buffer` and submit it, signaling `value`.
+    fn record_and_submit(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        value: u64,
+    ) -> crate::Result<()> {
+        let device = &self.device;
+
+        let begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+        unsafe { device.begin_command_buffer(command_buffer, &begin_info) }?;
+
+        for copy in &self.pending_copies {
+            match copy {
+                UploadCopy::Buffer {
+                    staging,
+                    dst,
+                    region,
+                } => unsafe { device.cmd_copy_buffer(command_buffer, *staging, *dst, &[*region]) },
+                UploadCopy::Image {
+                    staging,
+                    dst,
+                    region,
+                } => unsafe {
+                    device.cmd_copy_buffer_to_image(
+                        command_buffer,
+                        *staging,
+                        *dst,
+                        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                        &[*region],
+                    )
+                },
+            }
+        }
+
+        unsafe { device.end_command_buffer(command_buffer) }?;
+
+        let signal_values = [value];
+        let mut timeline_info =
+            vk::TimelineSemaphoreSubmitInfo::builder().signal_semaphore_values(&signal_values);
+        let command_buffers = [command_buffer];
+        let signal_semaphores = [self.semaphore];
+        let submit_info = vk::SubmitInfo::builder()
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores)
+            .push_next(&mut timeline_info);
+
+        let _access = device.audit_queue(self.queue);
+        unsafe { device.queue_submit(self.queue, &[submit_info], vk::Fence::null()) }?;
+
+        Ok(())
+    }
+
+    /// Free the resources of finished batches and return the highest completed timeline value.
+    pub fn poll_completed(&mut self) -> crate::Result<u64> {
+        let completed = unsafe { self.device.get_semaphore_counter_value(self.semaphore) }?;
+
+        let (finished, in_flight) = std::mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition::<Vec<_>, _>(|batch| batch.value <= completed);
+        self.in_flight = in_flight;
+
+        if !finished.is_empty() {
+            self.pool_owner
+                .check(vk::ObjectType::COMMAND_POOL, self.command_pool.as_raw());
+            let command_buffers = finished
+                .iter()
+                .map(|batch| batch.command_buffer)
+                .collect::<Vec<_>>();
+            unsafe {
+                self.device
+                    .free_command_buffers(self.command_pool, &command_buffers)
+            };
+        }
+        self.belt.recall(completed);
+
+        Ok(completed)
+    }
+
+    /// Wait for every submitted batch, then destroy the scheduler's Vulkan objects.
+    /// Uploads that were queued but not submitted are dropped.
+    ///
+    /// When the wait fails (e.g. `DEVICE_LOST`) nothing is destroyed and the error is
+    /// returned, since batches may still be reading the staging memory.
+    pub fn destroy(&mut self) -> crate::Result<()> {
+        let semaphores = [self.semaphore];
+        let values = [self.next_value - 1];
+        let wait_info = vk::SemaphoreWaitInfo::builder()
+            .semaphores(&semaphores)
+            .values(&values);
+        unsafe { self.device.wait_semaphores(&wait_info, u64::MAX) }?;
+
+        self.pending_copies.clear();
+        self.in_flight.clear();
+        self.belt.destroy();
+
+        let allocation_callbacks = self.device.allocation_callbacks();
+        unsafe {
+            self.device
+                .destroy_semaphore(self.semaphore, allocation_callbacks);
+            self.device
+                .destroy_command_pool(self.command_pool, allocation_callbacks);
+        }
+
+        Ok(())
+    }
+}
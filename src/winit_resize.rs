@@ -0,0 +1,148 @@
+use crate::{Swapchain, SwapchainBuilder};
+use std::time::{Duration, Instant};
+use vulkanalia::vk;
+use vulkanalia::vk::DeviceV1_0;
+use winit::event::WindowEvent;
+use winit::window::Window;
+
+/// A swapchain that follows the size of a winit window.
+///
+/// Feed it every [`WindowEvent`] with [`WinitSwapchain::handle_event`] and call
+/// [`WinitSwapchain::prepare`] before acquiring an image. Resizes are debounced so dragging a
+/// window edge doesn't rebuild the swapchain on every event, and nothing is built while the
+/// window is minimized (zero-size).
+///
+/// ``` no_run
+/// # fn run(window: &winit::window::Window, builder: vulkanalia_bootstrap::SwapchainBuilder, event: winit::event::WindowEvent) -> vulkanalia_bootstrap::Result<()> {
+/// use vulkanalia_bootstrap::WinitSwapchain;
+///
+/// let mut swapchain = WinitSwapchain::new(builder, window)?;
+///
+/// // In `ApplicationHandler::window_event`:
+/// swapchain.handle_event(window, &event);
+/// if swapchain.prepare()? {
+///     // Acquire, render and present with `swapchain.swapchain()`, which is always `Some`
+///     // after `prepare` returned true. Call `mark_out_of_date` when acquire or present
+///     // report `OUT_OF_DATE_KHR` or suboptimal.
+/// }
+///
+/// swapchain.destroy();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WinitSwapchain {
+    builder: SwapchainBuilder,
+    swapchain: Option<Swapchain>,
+    size: vk::Extent2D,
+    out_of_date: bool,
+    last_resize: Option<Instant>,
+    debounce: Duration,
+}
+
+impl WinitSwapchain {
+    /// Build the swapchain for the current size of `window`.
+    pub fn new(mut builder: SwapchainBuilder, window: &Window) -> crate::Result<Self> {
+        let size = window_extent(window);
+        builder.set_desired_size(size);
+        let swapchain = builder.build()?;
+
+        Ok(Self {
+            builder,
+            swapchain: Some(swapchain),
+            size,
+            out_of_date: false,
+            last_resize: None,
+            debounce: Duration::from_millis(50),
+        })
+    }
+
+    /// How long the window size must be stable before the swapchain is rebuilt. Defaults to
+    /// 50ms. Out of date swapchains are rebuilt immediately.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Track `Resized` and `ScaleFactorChanged` events of `window`, other events are ignored.
+    pub fn handle_event(&mut self, window: &Window, event: &WindowEvent) {
+        match event {
+            WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. } => {
+                let size = window_extent(window);
+                if size != self.size || self.last_resize.is_some() {
+                    self.size = size;
+                    self.last_resize = Some(Instant::now());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Rebuild the swapchain before the next frame, e.g. after acquire or present returned
+    /// `OUT_OF_DATE_KHR` or reported a suboptimal swapchain.
+    pub fn mark_out_of_date(&mut self) {
+        self.out_of_date = true;
+    }
+
+    /// Whether the window currently has a zero-size extent.
+    pub fn is_minimized(&self) -> bool {
+        self.size.width == 0 || self.size.height == 0
+    }
+
+    /// Rebuild the swapchain when a resize settled or it was marked out of date.
+    ///
+    /// Returns `false` when the window is minimized or a resize is still being debounced; skip
+    /// rendering this frame then. Waits for the device to be idle before rebuilding, check
+    /// [`Swapchain::generation`] to know when swapchain dependent state must be recreated.
+    pub fn prepare(&mut self) -> crate::Result<bool> {
+        if self.is_minimized() {
+            return Ok(false);
+        }
+
+        let settled = self
+            .last_resize
+            .is_some_and(|resized| resized.elapsed() >= self.debounce);
+        if !self.out_of_date && !settled {
+            return Ok(self.last_resize.is_none());
+        }
+
+        unsafe { self.builder.device().device_wait_idle() }?;
+        if let Some(old) = self.swapchain.take() {
+            self.builder.set_old_swapchain(old);
+        }
+        self.builder.set_desired_size(self.size);
+        self.swapchain = match self.builder.build() {
+            Ok(swapchain) => Some(swapchain),
+            // The surface can report a zero extent before the window sees the minimize event.
+            Err(crate::Error::Swapchain(crate::SwapchainError::ZeroExtent)) => return Ok(false),
+            Err(error) => return Err(error),
+        };
+        self.out_of_date = false;
+        self.last_resize = None;
+
+        Ok(true)
+    }
+
+    /// The current swapchain, `None` when the last rebuild in [`WinitSwapchain::prepare`]
+    /// failed.
+    pub fn swapchain(&self) -> Option<&Swapchain> {
+        self.swapchain.as_ref()
+    }
+
+    /// Destroy the swapchain and its image views, or the swapchain retired by a failed rebuild.
+    pub fn destroy(&self) {
+        if let Some(swapchain) = &self.swapchain {
+            let _ = swapchain.destroy_image_views();
+            swapchain.destroy();
+        }
+        self.builder.discard_old_swapchain();
+    }
+}
+
+fn window_extent(window: &Window) -> vk::Extent2D {
+    let size = window.inner_size();
+    vk::Extent2D {
+        width: size.width,
+        height: size.height,
+    }
+}